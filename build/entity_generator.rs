@@ -28,8 +28,17 @@ use crate::{
     DxfResult,
     ExtensionGroup,
     Handle,
+    HatchBoundaryPath,
+    HatchGradientColor,
+    HatchPatternDefinitionLine,
     LwPolylineVertex,
+    MLeaderArrowheadOverride,
+    MLeaderBlockAttribute,
+    MLeaderContextData,
+    MeshEdge,
+    MeshFace,
     Point,
+    TableCell,
     Vector,
     XData,
 };
@@ -218,6 +227,7 @@ fn generate_base_entity(fun: &mut String, element: &Element) {
 fn generate_entity_types(fun: &mut String, element: &Element) {
     fun.push_str("#[derive(Clone, Debug, PartialEq)]\n");
     fun.push_str("#[cfg_attr(feature = \"serialize\", derive(Serialize, Deserialize))]\n");
+    fun.push_str("#[allow(clippy::large_enum_variant)] // entities are stored by value, no good way to avoid this\n");
     fun.push_str("pub enum EntityType {\n");
     for c in &element.children {
         if c.name != "Entity" {
@@ -228,6 +238,9 @@ fn generate_entity_types(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("    /// An entity type not otherwise supported.  The code pairs following the common entity values\n");
+    fun.push_str("    /// are preserved verbatim so the entity can be written back unchanged.\n");
+    fun.push_str("    Unknown { type_string: String, pairs: Vec<CodePair> },\n");
     fun.push_str("}\n");
     fun.push_str("\n");
 
@@ -429,6 +442,7 @@ fn generate_is_supported_on_version(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            &EntityType::Unknown { .. } => { true },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -468,6 +482,7 @@ fn generate_type_string(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            EntityType::Unknown { type_string, .. } => { type_string },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -550,6 +565,7 @@ fn generate_try_apply_code_pair(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            &mut EntityType::Unknown { .. } => { panic!(\"this case should have been covered in a custom reader\"); },\n");
 
     fun.push_str("        }\n");
     fun.push_str("        Ok(true)\n");
@@ -584,6 +600,7 @@ fn generate_write(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            &EntityType::Unknown { .. } => { panic!(\"this case should have been covered in a custom writer\"); },\n");
     fun.push_str("        }\n");
     fun.push_str("\n");
     fun.push_str("        Ok(())\n");
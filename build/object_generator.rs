@@ -234,6 +234,9 @@ fn generate_object_types(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("    /// An object type not otherwise supported.  The code pairs following the common object values\n");
+    fun.push_str("    /// are preserved verbatim so the object can be written back unchanged.\n");
+    fun.push_str("    Unknown { type_string: String, pairs: Vec<CodePair> },\n");
     fun.push_str("}\n");
     fun.push_str("\n");
 
@@ -414,6 +417,7 @@ fn generate_is_supported_on_version(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            ObjectType::Unknown { .. } => { true },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -449,6 +453,7 @@ fn generate_type_string(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            ObjectType::Unknown { ref type_string, .. } => { type_string },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -543,6 +548,7 @@ fn generate_try_apply_code_pair(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            ObjectType::Unknown { .. } => { panic!(\"this case should have been covered in a custom reader\"); },\n");
 
     fun.push_str("        }\n");
     fun.push_str("        Ok(true)\n");
@@ -589,6 +595,7 @@ fn generate_write(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            ObjectType::Unknown { .. } => { panic!(\"this case should have been covered in a custom writer\"); },\n");
     fun.push_str("        }\n");
     fun.push_str("\n");
     fun.push_str("        Ok(())\n");
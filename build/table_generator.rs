@@ -58,7 +58,7 @@ fn generate_table_items(fun: &mut String, element: &Element) {
     for table in &element.children {
         let mut seen_fields = HashSet::new();
         let table_item = &table.children[0];
-        fun.push_str("#[derive(Debug)]\n");
+        fun.push_str("#[derive(Clone, Debug)]\n");
         fun.push_str("#[cfg_attr(feature = \"serialize\", derive(Serialize, Deserialize))]\n");
         fun.push_str(&format!("pub struct {name} {{\n", name = name(&table_item)));
         fun.push_str("    pub name: String,\n");
@@ -294,17 +294,16 @@ fn generate_table_reader(fun: &mut String, element: &Element) {
         fun.push_str("                    }\n");
         fun.push_str("\n");
         fun.push_str("                    if item.handle.is_empty() {\n");
-        fun.push_str(&format!(
-            "                        drawing.add_{item_type}(item);\n",
-            item_type = item_type
-        ));
+        fun.push_str("                        item.handle = drawing.next_handle();\n");
         fun.push_str("                    }\n");
-        fun.push_str("                    else {\n");
+        fun.push_str("\n");
+        fun.push_str(
+            "                    // references to other tables are checked once they've all been read\n",
+        );
         fun.push_str(&format!(
-            "                        drawing.add_{item_type}_no_handle_set(item);\n",
+            "                    drawing.add_{item_type}_no_handle_set(item);\n",
             item_type = item_type
         ));
-        fun.push_str("                    }\n");
         fun.push_str("                }\n");
         fun.push_str("                else {\n");
         fun.push_str("                    // do nothing, probably the table's handle or flags\n");
@@ -325,12 +324,38 @@ fn generate_table_writer(fun: &mut String, element: &Element) {
     fun.push_str("pub(crate) fn write_tables<T>(drawing: &Drawing, write_handles: bool, writer: &mut CodePairWriter<T>) -> DxfResult<()>\n");
     fun.push_str("    where T: Write + ?Sized {\n");
     fun.push_str("\n");
+
+    // strict writers for R12 readers get the tables in the order AutoCAD uses so that, e.g., line
+    // types precede the layers that refer to them; everything else keeps the order of the spec
+    let order = [
+        "VPORT",
+        "LTYPE",
+        "LAYER",
+        "STYLE",
+        "VIEW",
+        "UCS",
+        "APPID",
+        "DIMSTYLE",
+        "BLOCK_RECORD",
+    ];
+    let mut ordered_tables = element.children.iter().collect::<Vec<_>>();
+    ordered_tables.sort_by_key(|t| order.iter().position(|o| *o == attr(&t, "TypeString")));
+    fun.push_str("    if writer.is_strict() {\n");
+    for table in ordered_tables {
+        fun.push_str(&format!(
+            "        write_{collection}(drawing, write_handles, writer)?;\n",
+            collection = attr(&table, "Collection")
+        ));
+    }
+    fun.push_str("    }\n");
+    fun.push_str("    else {\n");
     for table in &element.children {
         fun.push_str(&format!(
-            "    write_{collection}(drawing, write_handles, writer)?;\n",
+            "        write_{collection}(drawing, write_handles, writer)?;\n",
             collection = attr(&table, "Collection")
         ));
     }
+    fun.push_str("    }\n");
 
     fun.push_str("    Ok(())\n");
     fun.push_str("}\n");
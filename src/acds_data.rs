@@ -0,0 +1,114 @@
+use crate::entities::Entity;
+use crate::{CodePair, Drawing, DrawingItem, DxfResult, ExtraSection, Handle};
+
+/// The contents of an `ACDSDATA` section.  In R2013 and later drawings this section holds the
+/// ACIS (SAT/SAB) data of `3DSOLID`, `REGION`, and `BODY` entities.
+///
+/// This is a read-only view; the section itself is preserved verbatim as an `ExtraSection`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AcDsData {
+    pub version: i16,
+    pub records: Vec<AcDsRecord>,
+}
+
+/// A single `ACDSRECORD` of an `ACDSDATA` section.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AcDsRecord {
+    /// The index of the `ACDSSCHEMA` describing this record.
+    pub schema_index: i32,
+    /// The handle of the entity this record belongs to.
+    pub entity_handle: Handle,
+    /// The raw ACIS data.
+    pub data: Vec<u8>,
+}
+
+impl Default for AcDsData {
+    fn default() -> Self {
+        AcDsData {
+            version: 2,
+            records: vec![],
+        }
+    }
+}
+
+impl Default for AcDsRecord {
+    fn default() -> Self {
+        AcDsRecord {
+            schema_index: 0,
+            entity_handle: Handle::empty(),
+            data: vec![],
+        }
+    }
+}
+
+impl AcDsData {
+    /// Parses the records of an `ACDSDATA` section.
+    pub fn from_section(section: &ExtraSection) -> DxfResult<AcDsData> {
+        let mut acds = AcDsData::default();
+        let mut current_record: Option<AcDsRecord> = None;
+        let mut current_value_name = String::new();
+        for pair in &section.pairs {
+            match (pair.code, current_record.as_mut()) {
+                (0, _) => {
+                    if let Some(record) = current_record.take() {
+                        acds.records.push(record);
+                    }
+                    if pair.assert_string()? == "ACDSRECORD" {
+                        current_record = Some(AcDsRecord::default());
+                    }
+                    current_value_name.clear();
+                }
+                (70, None) => acds.version = pair.assert_i16()?,
+                (_, None) => (), // part of the section header or a schema
+                (_, Some(record)) => {
+                    AcDsData::apply_record_pair(record, pair, &mut current_value_name)?
+                }
+            }
+        }
+
+        if let Some(record) = current_record {
+            acds.records.push(record);
+        }
+
+        Ok(acds)
+    }
+    /// Returns the record belonging to the specified entity, if any.
+    pub fn get_record_for_entity(&self, entity_handle: Handle) -> Option<&AcDsRecord> {
+        self.records
+            .iter()
+            .find(|r| r.entity_handle == entity_handle)
+    }
+}
+
+impl AcDsRecord {
+    /// Returns the entity this record belongs to.
+    pub fn get_entity<'a>(&self, drawing: &'a Drawing) -> Option<&'a Entity> {
+        match drawing.get_item_by_handle(self.entity_handle) {
+            Some(DrawingItem::Entity(entity)) => Some(entity),
+            _ => None,
+        }
+    }
+}
+
+// private implementation
+impl AcDsData {
+    fn apply_record_pair(
+        record: &mut AcDsRecord,
+        pair: &CodePair,
+        current_value_name: &mut String,
+    ) -> DxfResult<()> {
+        match pair.code {
+            // each value is introduced by its name
+            2 => *current_value_name = pair.assert_string()?,
+            90 if current_value_name.is_empty() => record.schema_index = pair.assert_i32()?,
+            320 if current_value_name == "AcDbDs::ID" => record.entity_handle = pair.as_handle()?,
+            310 if current_value_name == "ASM_Data" => {
+                record.data.extend(pair.assert_binary()?);
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use crate::Handle;
+
+/// The kind of problem found by `Drawing::audit`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum AuditProblemKind {
+    /// Another item that was found earlier has the same handle.  The fix assigns a new handle.
+    DuplicateHandle,
+    /// The handle is at or above `$HANDSEED`, so new items could be given the same handle.  The
+    /// fix raises `$HANDSEED`.
+    HandleNotBelowSeed,
+    /// The named pointer, e.g., `owner`, doesn't refer to an item in the drawing.  The fix clears
+    /// the pointer or removes the dictionary entry.
+    DanglingPointer { name: String, pointer: Handle },
+    /// The entity or block is on a layer that doesn't exist.  The fix adds the layer.
+    MissingLayer(String),
+    /// The entity's line type doesn't exist.  The fix adds the line type.
+    MissingLineType(String),
+    /// The entity's text style doesn't exist.  The fix adds the text style.
+    MissingTextStyle(String),
+    /// The entity's dimension style doesn't exist.  The fix adds the dimension style.
+    MissingDimStyle(String),
+    /// The block has no `BlockRecord` of the same name.  The fix adds the block record.
+    MissingBlockRecord(String),
+    /// Another entry of the table, e.g., `LAYER`, that was found earlier has the same name,
+    /// ignoring case.  The fix renames the entry.
+    DuplicateTableName { table: String, name: String },
+}
+
+impl fmt::Display for AuditProblemKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuditProblemKind::DuplicateHandle => write!(formatter, "duplicate handle"),
+            AuditProblemKind::HandleNotBelowSeed => {
+                write!(formatter, "handle not below $HANDSEED")
+            }
+            AuditProblemKind::DanglingPointer { name, pointer } => write!(
+                formatter,
+                "{} pointer {} refers to a missing item",
+                name,
+                pointer.as_string()
+            ),
+            AuditProblemKind::MissingLayer(name) => write!(formatter, "missing layer '{}'", name),
+            AuditProblemKind::MissingLineType(name) => {
+                write!(formatter, "missing line type '{}'", name)
+            }
+            AuditProblemKind::MissingTextStyle(name) => {
+                write!(formatter, "missing text style '{}'", name)
+            }
+            AuditProblemKind::MissingDimStyle(name) => {
+                write!(formatter, "missing dimension style '{}'", name)
+            }
+            AuditProblemKind::MissingBlockRecord(name) => {
+                write!(formatter, "missing block record '{}'", name)
+            }
+            AuditProblemKind::DuplicateTableName { table, name } => {
+                write!(formatter, "duplicate {} name '{}'", table, name)
+            }
+        }
+    }
+}
+
+/// A problem found by `Drawing::audit`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AuditProblem {
+    /// The handle of the offending item, or an empty handle if it has none.
+    pub handle: Handle,
+    pub kind: AuditProblemKind,
+    /// `true` if the problem was fixed by `Drawing::audit_and_fix`.
+    pub fixed: bool,
+}
+
+impl fmt::Display for AuditProblem {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if !self.handle.is_empty() {
+            write!(formatter, "handle {}: ", self.handle.as_string())?;
+        }
+        write!(formatter, "{}", self.kind)?;
+        if self.fixed {
+            write!(formatter, " (fixed)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of `Drawing::audit` or `Drawing::audit_and_fix`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AuditReport {
+    pub problems: Vec<AuditProblem>,
+}
+
+impl AuditReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+    /// Returns an iterator for the problems that weren't fixed.
+    pub fn unfixed(&self) -> impl Iterator<Item = &AuditProblem> {
+        self.problems.iter().filter(|p| !p.fixed)
+    }
+}
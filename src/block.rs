@@ -166,7 +166,7 @@ impl Block {
                         CodePair { code: 0, .. } => {
                             // should be an entity
                             iter.put_back(Ok(pair));
-                            let mut iter = EntityIter { iter };
+                            let mut iter = EntityIter::new(iter);
                             iter.read_entities_into_vec(&mut current.entities)?;
                         }
                         _ => {
@@ -441,8 +441,16 @@ mod tests {
     #[test]
     fn read_block_with_unsupported_entity_first() {
         let block = read_single_block(vec!["0", "UNSUPPORTED_ENTITY", "0", "LINE"]);
-        assert_eq!(1, block.entities.len());
+        assert_eq!(2, block.entities.len());
         match block.entities[0].specific {
+            EntityType::Unknown {
+                ref type_string, ..
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string)
+            }
+            _ => panic!("expected an unknown entity"),
+        }
+        match block.entities[1].specific {
             EntityType::Line(_) => (),
             _ => panic!("expected a line"),
         }
@@ -451,22 +459,38 @@ mod tests {
     #[test]
     fn read_block_with_unsupported_entity_last() {
         let block = read_single_block(vec!["0", "LINE", "0", "UNSUPPORTED_ENTITY"]);
-        assert_eq!(1, block.entities.len());
+        assert_eq!(2, block.entities.len());
         match block.entities[0].specific {
             EntityType::Line(_) => (),
             _ => panic!("expected a line"),
         }
+        match block.entities[1].specific {
+            EntityType::Unknown {
+                ref type_string, ..
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string)
+            }
+            _ => panic!("expected an unknown entity"),
+        }
     }
 
     #[test]
     fn read_block_with_unsupported_entity_in_the_middle() {
         let block = read_single_block(vec!["0", "LINE", "0", "UNSUPPORTED_ENTITY", "0", "CIRCLE"]);
-        assert_eq!(2, block.entities.len());
+        assert_eq!(3, block.entities.len());
         match block.entities[0].specific {
             EntityType::Line(_) => (),
             _ => panic!("expected a line"),
         }
         match block.entities[1].specific {
+            EntityType::Unknown {
+                ref type_string, ..
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string)
+            }
+            _ => panic!("expected an unknown entity"),
+        }
+        match block.entities[2].specific {
             EntityType::Circle(_) => (),
             _ => panic!("expected a circle"),
         }
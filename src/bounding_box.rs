@@ -0,0 +1,837 @@
+use std::f64::consts::PI;
+use std::slice;
+
+use crate::drawing::MAX_INSERT_DEPTH;
+use crate::entities::*;
+use crate::helper_functions::*;
+use crate::{Drawing, Point, TransformationMatrix, Vector};
+
+/// The number of samples taken per knot span when searching a spline for its extrema.
+const SPLINE_SAMPLES_PER_SPAN: usize = 16;
+
+/// An axis-aligned box in world coordinates.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+// public implementation
+impl BoundingBox {
+    /// Creates the smallest box containing both points.
+    pub fn new(p1: Point, p2: Point) -> Self {
+        let mut bbox = BoundingBox::from_point(p1);
+        bbox.include_point(&p2);
+        bbox
+    }
+    /// Creates an empty box at the specified point.
+    pub fn from_point(p: Point) -> Self {
+        BoundingBox {
+            min: p.clone(),
+            max: p,
+        }
+    }
+    /// Creates the smallest box containing all of the points, or `None` if there are none.
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let mut bbox = None;
+        for p in points {
+            BoundingBox::include(&mut bbox, p);
+        }
+        bbox
+    }
+    /// Grows the box to contain the specified point.
+    pub fn include_point(&mut self, p: &Point) {
+        self.min = Point::new(
+            self.min.x.min(p.x),
+            self.min.y.min(p.y),
+            self.min.z.min(p.z),
+        );
+        self.max = Point::new(
+            self.max.x.max(p.x),
+            self.max.y.max(p.y),
+            self.max.z.max(p.z),
+        );
+    }
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut bbox = self.clone();
+        bbox.include_point(&other.min);
+        bbox.include_point(&other.max);
+        bbox
+    }
+    /// The extent of the box along each axis.
+    pub fn size(&self) -> Vector {
+        &self.max - &self.min
+    }
+    /// The point halfway between the corners.
+    pub fn center(&self) -> Point {
+        self.min.lerp(&self.max, 0.5)
+    }
+    /// Returns `true` if the point is inside or on the boundary of the box.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+}
+
+// internal visibility only
+impl BoundingBox {
+    /// Computes the box of the entity after applying `transform`, following block references at
+    /// most `MAX_INSERT_DEPTH - depth` levels deep.
+    pub(crate) fn of_entity(
+        entity: &Entity,
+        drawing: &Drawing,
+        transform: &TransformationMatrix,
+        depth: usize,
+    ) -> Option<BoundingBox> {
+        let mut bbox = None;
+        let points = |bbox: &mut Option<BoundingBox>, points: &[Point]| {
+            for p in points {
+                BoundingBox::include(bbox, &transform.transform_point(p));
+            }
+        };
+        match entity.specific {
+            EntityType::Arc(ref arc) => {
+                let (start, end) = BoundingBox::normalize_angles(
+                    arc.start_angle.to_radians(),
+                    arc.end_angle.to_radians(),
+                );
+                let transform = *transform * TransformationMatrix::from_ocs_normal(&arc.normal);
+                BoundingBox::include_circular_arc(
+                    &mut bbox,
+                    &transform,
+                    &arc.center,
+                    arc.radius,
+                    start,
+                    end,
+                );
+            }
+            EntityType::Circle(ref circle) => {
+                let transform = *transform * TransformationMatrix::from_ocs_normal(&circle.normal);
+                BoundingBox::include_circular_arc(
+                    &mut bbox,
+                    &transform,
+                    &circle.center,
+                    circle.radius,
+                    0.0,
+                    2.0 * PI,
+                );
+            }
+            EntityType::Ellipse(ref ellipse) => {
+                let (start, end) =
+                    BoundingBox::normalize_angles(ellipse.start_parameter, ellipse.end_parameter);
+                let minor_axis = ellipse.normal.normalize().cross(&ellipse.major_axis)
+                    * ellipse.minor_axis_ratio;
+                BoundingBox::include_elliptical_arc(
+                    &mut bbox,
+                    transform.transform_point(&ellipse.center),
+                    transform.transform_vector(&ellipse.major_axis),
+                    transform.transform_vector(&minor_axis),
+                    start,
+                    end,
+                );
+            }
+            EntityType::Line(ref line) => points(&mut bbox, &[line.p1.clone(), line.p2.clone()]),
+            EntityType::LwPolyline(ref poly) => {
+                let transform =
+                    *transform * TransformationMatrix::from_ocs_normal(&poly.extrusion_direction);
+                let vertices = poly
+                    .vertices
+                    .iter()
+                    .map(|v| (Point::new(v.x, v.y, entity.common.elevation), v.bulge))
+                    .collect::<Vec<_>>();
+                BoundingBox::include_bulged_vertices(
+                    &mut bbox,
+                    &transform,
+                    &vertices,
+                    poly.get_is_closed(),
+                );
+            }
+            EntityType::Polyline(ref poly) => {
+                let vertices = poly
+                    .vertices()
+                    .filter(|v| !v.get_is_spline_frame_control_point())
+                    .filter(|v| !poly.get_is_polyface_mesh() || v.get_is_3d_polygon_mesh());
+                if poly.get_is_3d_polyline()
+                    || poly.get_is_3d_polygon_mesh()
+                    || poly.get_is_polyface_mesh()
+                {
+                    let vertices = vertices.map(|v| v.location.clone()).collect::<Vec<_>>();
+                    points(&mut bbox, &vertices);
+                } else {
+                    let transform =
+                        *transform * TransformationMatrix::from_ocs_normal(&poly.normal);
+                    let vertices = vertices
+                        .map(|v| {
+                            let p = Point::new(v.location.x, v.location.y, poly.location.z);
+                            (p, v.bulge)
+                        })
+                        .collect::<Vec<_>>();
+                    BoundingBox::include_bulged_vertices(
+                        &mut bbox,
+                        &transform,
+                        &vertices,
+                        poly.get_is_closed(),
+                    );
+                }
+            }
+            EntityType::Spline(ref spline) => {
+                BoundingBox::include_spline(&mut bbox, transform, spline);
+            }
+            EntityType::Insert(ref insert) => {
+                BoundingBox::include_insert(&mut bbox, drawing, transform, insert, depth);
+            }
+            EntityType::RotatedDimension(ref dim) => {
+                BoundingBox::include_dimension(&mut bbox, drawing, transform, &dim.dimension_base);
+            }
+            EntityType::RadialDimension(ref dim) => {
+                BoundingBox::include_dimension(&mut bbox, drawing, transform, &dim.dimension_base);
+            }
+            EntityType::DiameterDimension(ref dim) => {
+                BoundingBox::include_dimension(&mut bbox, drawing, transform, &dim.dimension_base);
+            }
+            EntityType::AngularThreePointDimension(ref dim) => {
+                BoundingBox::include_dimension(&mut bbox, drawing, transform, &dim.dimension_base);
+            }
+            EntityType::OrdinateDimension(ref dim) => {
+                BoundingBox::include_dimension(&mut bbox, drawing, transform, &dim.dimension_base);
+            }
+            EntityType::Face3D(ref face) => points(
+                &mut bbox,
+                &[
+                    face.first_corner.clone(),
+                    face.second_corner.clone(),
+                    face.third_corner.clone(),
+                    face.fourth_corner.clone(),
+                ],
+            ),
+            EntityType::Solid(ref solid) => points(&mut bbox, &solid.corners_wcs()),
+            EntityType::Trace(ref trace) => points(&mut bbox, &trace.corners_wcs()),
+            EntityType::ModelPoint(ref point) => {
+                points(&mut bbox, slice::from_ref(&point.location))
+            }
+            EntityType::Vertex(ref vertex) => points(&mut bbox, slice::from_ref(&vertex.location)),
+            EntityType::Leader(ref leader) => points(&mut bbox, &leader.vertices),
+            EntityType::MLine(ref mline) => points(&mut bbox, &mline.vertices),
+            EntityType::Mesh(ref mesh) => points(&mut bbox, &mesh.vertices),
+            EntityType::Image(ref image) => points(
+                &mut bbox,
+                &BoundingBox::image_corners(
+                    &image.location,
+                    &image.u_vector,
+                    &image.v_vector,
+                    &image.image_size,
+                ),
+            ),
+            EntityType::Wipeout(ref wipeout) => points(
+                &mut bbox,
+                &BoundingBox::image_corners(
+                    &wipeout.location,
+                    &wipeout.u_vector,
+                    &wipeout.v_vector,
+                    &wipeout.image_size,
+                ),
+            ),
+            // text only contributes its insertion point; glyph extents depend on the font
+            EntityType::Text(ref text) => points(&mut bbox, &[text.location_wcs()]),
+            EntityType::Attribute(ref att) => {
+                points(&mut bbox, &[att.location.ocs_to_wcs(&att.normal)])
+            }
+            EntityType::AttributeDefinition(ref att) => {
+                points(&mut bbox, &[att.location.ocs_to_wcs(&att.normal)])
+            }
+            EntityType::MText(ref mtext) => {
+                points(&mut bbox, slice::from_ref(&mtext.insertion_point))
+            }
+            _ => (),
+        }
+
+        bbox
+    }
+}
+
+// private implementation
+impl BoundingBox {
+    fn include(bbox: &mut Option<BoundingBox>, p: &Point) {
+        match bbox {
+            Some(bbox) => bbox.include_point(p),
+            None => *bbox = Some(BoundingBox::from_point(p.clone())),
+        }
+    }
+    fn include_box(bbox: &mut Option<BoundingBox>, other: &BoundingBox) {
+        BoundingBox::include(bbox, &other.min);
+        BoundingBox::include(bbox, &other.max);
+    }
+    /// Returns `(start, end)` with `0 <= start < 2π` and `start < end <= start + 2π`.  Non-finite
+    /// angles give the whole turn.
+    fn normalize_angles(start: f64, end: f64) -> (f64, f64) {
+        if !start.is_finite() || !end.is_finite() {
+            return (0.0, 2.0 * PI);
+        }
+        let start = start.rem_euclid(2.0 * PI);
+        let mut end = end.rem_euclid(2.0 * PI);
+        if end <= start {
+            end += 2.0 * PI;
+        }
+        (start, end)
+    }
+    /// Includes the arc of the specified radius centered at `center`, both in the coordinate
+    /// system of `transform`, from angle `start` to `end` in radians.
+    fn include_circular_arc(
+        bbox: &mut Option<BoundingBox>,
+        transform: &TransformationMatrix,
+        center: &Point,
+        radius: f64,
+        start: f64,
+        end: f64,
+    ) {
+        BoundingBox::include_elliptical_arc(
+            bbox,
+            transform.transform_point(center),
+            transform.transform_vector(&(Vector::x_axis() * radius)),
+            transform.transform_vector(&(Vector::y_axis() * radius)),
+            start,
+            end,
+        );
+    }
+    /// Includes the curve `center + u·cos(t) + v·sin(t)` for `t` in `start..=end`.  An affine
+    /// transformation of an ellipse keeps this form, so the result is exact for any transform.
+    fn include_elliptical_arc(
+        bbox: &mut Option<BoundingBox>,
+        center: Point,
+        u: Vector,
+        v: Vector,
+        start: f64,
+        end: f64,
+    ) {
+        let point_at = |t: f64| {
+            let (sin, cos) = t.sin_cos();
+            &center + &(&u * cos) + &v * sin
+        };
+        BoundingBox::include(bbox, &point_at(start));
+        BoundingBox::include(bbox, &point_at(end));
+
+        // each coordinate is extreme where its derivative `-u·sin(t) + v·cos(t)` is zero
+        for (u, v) in [(u.x, v.x), (u.y, v.y), (u.z, v.z)] {
+            let extremum = v.atan2(u);
+            for candidate in [extremum, extremum + PI] {
+                let t = start + (candidate - start).rem_euclid(2.0 * PI);
+                if t <= end {
+                    BoundingBox::include(bbox, &point_at(t));
+                }
+            }
+        }
+    }
+    /// Includes a 2D polyline whose vertices are in the coordinate system of `transform`.  Each
+    /// vertex's bulge describes the segment to the next vertex.
+    fn include_bulged_vertices(
+        bbox: &mut Option<BoundingBox>,
+        transform: &TransformationMatrix,
+        vertices: &[(Point, f64)],
+        is_closed: bool,
+    ) {
+        let segment_count = match (vertices.len(), is_closed) {
+            (0, _) => 0,
+            (count, true) => count,
+            (count, false) => count - 1,
+        };
+        for (p, _) in vertices {
+            BoundingBox::include(bbox, &transform.transform_point(p));
+        }
+        for i in 0..segment_count {
+            let (start, bulge) = &vertices[i];
+            let (end, _) = &vertices[(i + 1) % vertices.len()];
+            BoundingBox::include_bulge(bbox, transform, start, end, *bulge);
+        }
+    }
+    fn include_bulge(
+        bbox: &mut Option<BoundingBox>,
+        transform: &TransformationMatrix,
+        start: &Point,
+        end: &Point,
+        bulge: f64,
+    ) {
+        if let Some((center, radius, start_angle, included_angle)) = bulge_to_arc(start, end, bulge)
+        {
+            let (start_angle, end_angle) = if included_angle > 0.0 {
+                (start_angle, start_angle + included_angle)
+            } else {
+                (start_angle + included_angle, start_angle)
+            };
+            BoundingBox::include_circular_arc(
+                bbox,
+                transform,
+                &center,
+                radius,
+                start_angle,
+                end_angle,
+            );
+        }
+    }
+    fn include_spline(
+        bbox: &mut Option<BoundingBox>,
+        transform: &TransformationMatrix,
+        spline: &Spline,
+    ) {
+        if !spline.has_valid_knots() {
+            // the control polygon contains the curve; the fit points lie on it
+            let points = if spline.control_points.is_empty() {
+                &spline.fit_points
+            } else {
+                &spline.control_points
+            };
+            for p in points {
+                BoundingBox::include(bbox, &transform.transform_point(p));
+            }
+            return;
+        }
+
+        // splines are invariant under affine transformations of their control points
+        let spline = Spline {
+            control_points: spline
+                .control_points
+                .iter()
+                .map(|p| transform.transform_point(p))
+                .collect(),
+            ..spline.clone()
+        };
+        let (t0, t1) = spline.domain();
+        let mut parameters = vec![t0];
+        for span in spline.knot_values.windows(2) {
+            let (k0, k1) = (span[0].max(t0), span[1].min(t1));
+            if k1 > k0 {
+                for i in 1..=SPLINE_SAMPLES_PER_SPAN {
+                    parameters.push(k0 + (k1 - k0) * i as f64 / SPLINE_SAMPLES_PER_SPAN as f64);
+                }
+            }
+        }
+
+        let samples = parameters
+            .iter()
+            .map(|t| spline.point_at(*t))
+            .collect::<Vec<_>>();
+        for p in &samples {
+            BoundingBox::include(bbox, p);
+        }
+
+        // refine each sampled local extremum of each coordinate
+        let coordinates: [fn(&Point) -> f64; 3] = [|p| p.x, |p| p.y, |p| p.z];
+        for coordinate in &coordinates {
+            for i in 1..samples.len().saturating_sub(1) {
+                let (before, value, after) = (
+                    coordinate(&samples[i - 1]),
+                    coordinate(&samples[i]),
+                    coordinate(&samples[i + 1]),
+                );
+                let sign = if value >= before && value >= after {
+                    1.0
+                } else if value <= before && value <= after {
+                    -1.0
+                } else {
+                    continue;
+                };
+                let t = golden_section_maximum(
+                    |t| sign * coordinate(&spline.point_at(t)),
+                    parameters[i - 1],
+                    parameters[i + 1],
+                );
+                BoundingBox::include(bbox, &spline.point_at(t));
+            }
+        }
+    }
+    fn include_insert(
+        bbox: &mut Option<BoundingBox>,
+        drawing: &Drawing,
+        transform: &TransformationMatrix,
+        insert: &Insert,
+        depth: usize,
+    ) {
+        for att in insert.attributes() {
+            let location = att.location.ocs_to_wcs(&att.normal);
+            BoundingBox::include(bbox, &transform.transform_point(&location));
+        }
+
+        let block = match drawing.blocks().find(|b| b.name == insert.name) {
+            Some(block) if depth < MAX_INSERT_DEPTH => block,
+            _ => return,
+        };
+        let block_transform = *transform
+            * TransformationMatrix::from_insert(insert)
+            * TransformationMatrix::translation(&-block.base_point.to_vector());
+        let mut cell = None;
+        for entity in &block.entities {
+            if let Some(entity_box) =
+                BoundingBox::of_entity(entity, drawing, &block_transform, depth + 1)
+            {
+                BoundingBox::include_box(&mut cell, &entity_box);
+            }
+        }
+        let cell = match cell {
+            Some(cell) => cell,
+            None => return,
+        };
+
+        // the array cells are translated copies, so the outermost ones bound the rest
+        let array_transform = *transform
+            * TransformationMatrix::from_ocs_normal(&insert.extrusion_direction)
+            * TransformationMatrix::rotation(&Vector::z_axis(), insert.rotation.to_radians());
+        let last_column = f64::from(insert.column_count.max(1) - 1);
+        let last_row = f64::from(insert.row_count.max(1) - 1);
+        for (column, row) in [
+            (0.0, 0.0),
+            (last_column, 0.0),
+            (0.0, last_row),
+            (last_column, last_row),
+        ] {
+            let offset = array_transform.transform_vector(&Vector::new(
+                column * insert.column_spacing,
+                row * insert.row_spacing,
+                0.0,
+            ));
+            BoundingBox::include(bbox, &(&cell.min + &offset));
+            BoundingBox::include(bbox, &(&cell.max + &offset));
+        }
+    }
+    fn include_dimension(
+        bbox: &mut Option<BoundingBox>,
+        drawing: &Drawing,
+        transform: &TransformationMatrix,
+        dimension: &DimensionBase,
+    ) {
+        // the dimension's anonymous block holds its rendered geometry in world coordinates
+        match drawing.blocks().find(|b| b.name == dimension.block_name) {
+            Some(block) if !dimension.block_name.is_empty() => {
+                for entity in &block.entities {
+                    if let Some(entity_box) = BoundingBox::of_entity(entity, drawing, transform, 1)
+                    {
+                        BoundingBox::include_box(bbox, &entity_box);
+                    }
+                }
+            }
+            _ => {
+                BoundingBox::include(
+                    bbox,
+                    &transform.transform_point(&dimension.definition_point_1),
+                );
+                BoundingBox::include(bbox, &transform.transform_point(&dimension.text_mid_point));
+            }
+        }
+    }
+    fn image_corners(location: &Point, u: &Vector, v: &Vector, size: &Vector) -> [Point; 4] {
+        let width = u * size.x;
+        let height = v * size.y;
+        [
+            location.clone(),
+            location + &width,
+            location + &height,
+            location + &width + &height,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+
+    fn assert_box(expected_min: Point, expected_max: Point, actual: Option<BoundingBox>) {
+        let actual = actual.expect("expected a bounding box");
+        assert!(
+            actual.min.is_close_to(&expected_min, 1.0e-9)
+                && actual.max.is_close_to(&expected_max, 1.0e-9),
+            "expected {:?}..{:?}, got {:?}..{:?}",
+            expected_min,
+            expected_max,
+            actual.min,
+            actual.max
+        );
+    }
+
+    fn bounding_box(drawing: &Drawing, specific: EntityType) -> Option<BoundingBox> {
+        Entity::new(specific).bounding_box(drawing)
+    }
+
+    #[test]
+    fn box_operations() {
+        let bbox = BoundingBox::new(Point::new(1.0, 2.0, 3.0), Point::new(-1.0, 0.0, 3.0));
+        assert_eq!(Point::new(-1.0, 0.0, 3.0), bbox.min);
+        assert_eq!(Point::new(1.0, 2.0, 3.0), bbox.max);
+        assert_eq!(Vector::new(2.0, 2.0, 0.0), bbox.size());
+        assert_eq!(Point::new(0.0, 1.0, 3.0), bbox.center());
+        assert!(bbox.contains_point(&Point::new(0.5, 0.5, 3.0)));
+        assert!(!bbox.contains_point(&Point::new(0.5, 0.5, 4.0)));
+
+        let other = BoundingBox::from_point(Point::new(5.0, 5.0, 5.0));
+        let union = bbox.union(&other);
+        assert_eq!(Point::new(-1.0, 0.0, 3.0), union.min);
+        assert_eq!(Point::new(5.0, 5.0, 5.0), union.max);
+
+        assert_eq!(None, BoundingBox::from_points(std::iter::empty()));
+    }
+
+    #[test]
+    fn line_bounding_box() {
+        let drawing = Drawing::new();
+        let line = Line::new(Point::new(3.0, -1.0, 0.0), Point::new(1.0, 2.0, 4.0));
+        assert_box(
+            Point::new(1.0, -1.0, 0.0),
+            Point::new(3.0, 2.0, 4.0),
+            bounding_box(&drawing, EntityType::Line(line)),
+        );
+    }
+
+    #[test]
+    fn circle_bounding_box() {
+        let drawing = Drawing::new();
+        let circle = Circle::new(Point::new(1.0, 1.0, 0.0), 2.0);
+        assert_box(
+            Point::new(-1.0, -1.0, 0.0),
+            Point::new(3.0, 3.0, 0.0),
+            bounding_box(&drawing, EntityType::Circle(circle)),
+        );
+    }
+
+    #[test]
+    fn arc_bounding_box_includes_crossed_quadrants_only() {
+        let drawing = Drawing::new();
+
+        // from 45 to 135 degrees only crosses the top of the circle
+        let arc = Arc::new(Point::origin(), 1.0, 45.0, 135.0);
+        let half = 0.5_f64.sqrt();
+        assert_box(
+            Point::new(-half, half, 0.0),
+            Point::new(half, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Arc(arc)),
+        );
+
+        // from 270 to 90 degrees wraps through 0
+        let arc = Arc::new(Point::origin(), 1.0, 270.0, 90.0);
+        assert_box(
+            Point::new(0.0, -1.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Arc(arc)),
+        );
+    }
+
+    #[test]
+    fn arc_bounding_box_with_out_of_range_angles() {
+        let drawing = Drawing::new();
+
+        // 405 to -225 degrees is the same arc as 45 to 135
+        let arc = Arc::new(Point::origin(), 1.0, 405.0, -225.0);
+        let half = 0.5_f64.sqrt();
+        assert_box(
+            Point::new(-half, half, 0.0),
+            Point::new(half, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Arc(arc)),
+        );
+
+        // huge angles can't be stepped through a turn at a time
+        let arc = Arc::new(Point::origin(), 1.0, 1.0e300, 0.0);
+        assert!(bounding_box(&drawing, EntityType::Arc(arc)).is_some());
+
+        // non-finite angles fall back to the whole circle
+        let arc = Arc::new(Point::origin(), 1.0, f64::INFINITY, f64::NAN);
+        assert_box(
+            Point::new(-1.0, -1.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Arc(arc)),
+        );
+    }
+
+    #[test]
+    fn mirrored_arc_bounding_box() {
+        let drawing = Drawing::new();
+
+        // a negative normal mirrors the OCS X axis
+        let mut arc = Arc::new(Point::new(2.0, 0.0, 0.0), 1.0, 0.0, 90.0);
+        arc.normal = Vector::new(0.0, 0.0, -1.0);
+        assert_box(
+            Point::new(-3.0, 0.0, 0.0),
+            Point::new(-2.0, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Arc(arc)),
+        );
+    }
+
+    #[test]
+    fn rotated_ellipse_bounding_box() {
+        let drawing = Drawing::new();
+        let ellipse = Ellipse {
+            center: Point::origin(),
+            major_axis: Vector::new(2.0, 2.0, 0.0),
+            minor_axis_ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: 2.0 * std::f64::consts::PI,
+            ..Default::default()
+        };
+
+        // semi-axes a = √8 and b = √2 rotated 45°, so each half-extent is √((a² + b²) / 2)
+        let extent = 5.0_f64.sqrt();
+        assert_box(
+            Point::new(-extent, -extent, 0.0),
+            Point::new(extent, extent, 0.0),
+            bounding_box(&drawing, EntityType::Ellipse(ellipse)),
+        );
+    }
+
+    #[test]
+    fn lw_polyline_bulge_bounding_box() {
+        let drawing = Drawing::new();
+
+        // a counter-clockwise semicircle from (0,0) to (2,0) bulges below the chord
+        let poly = LwPolyline {
+            vertices: vec![
+                LwPolylineVertex {
+                    x: 0.0,
+                    y: 0.0,
+                    bulge: 1.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 2.0,
+                    y: 0.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut entity = Entity::new(EntityType::LwPolyline(poly));
+        entity.common.elevation = 3.0;
+        assert_box(
+            Point::new(0.0, -1.0, 3.0),
+            Point::new(2.0, 0.0, 3.0),
+            entity.bounding_box(&drawing),
+        );
+    }
+
+    #[test]
+    fn spline_bounding_box() {
+        let drawing = Drawing::new();
+
+        // a quadratic Bézier from (0,0) through the control point (1,2) to (2,0) peaks at y = 1
+        let spline = Spline {
+            degree_of_curve: 2,
+            knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            control_points: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 2.0, 0.0),
+                Point::new(2.0, 0.0, 0.0),
+            ],
+            ..Default::default()
+        };
+        assert_box(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 1.0, 0.0),
+            bounding_box(&drawing, EntityType::Spline(spline)),
+        );
+    }
+
+    #[test]
+    fn insert_bounding_box_follows_block_transform() {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("b"),
+            base_point: Point::new(1.0, 0.0, 0.0),
+            ..Default::default()
+        };
+        block.entities.push(Entity::new(EntityType::Line(Line::new(
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        ))));
+        drawing.add_block(block);
+
+        // rotated 90° and doubled, the line runs from the location up by 2
+        let insert = Insert {
+            name: String::from("b"),
+            location: Point::new(10.0, 10.0, 0.0),
+            x_scale_factor: 2.0,
+            y_scale_factor: 2.0,
+            rotation: 90.0,
+            ..Default::default()
+        };
+        assert_box(
+            Point::new(10.0, 10.0, 0.0),
+            Point::new(10.0, 12.0, 0.0),
+            bounding_box(&drawing, EntityType::Insert(insert)),
+        );
+    }
+
+    #[test]
+    fn insert_bounding_box_includes_array() {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        block
+            .entities
+            .push(Entity::new(EntityType::Circle(Circle::new(
+                Point::origin(),
+                1.0,
+            ))));
+        drawing.add_block(block);
+
+        let insert = Insert {
+            name: String::from("b"),
+            column_count: 3,
+            row_count: 2,
+            column_spacing: 5.0,
+            row_spacing: 4.0,
+            ..Default::default()
+        };
+        assert_box(
+            Point::new(-1.0, -1.0, 0.0),
+            Point::new(11.0, 5.0, 0.0),
+            bounding_box(&drawing, EntityType::Insert(insert)),
+        );
+    }
+
+    #[test]
+    fn insert_of_missing_block_has_no_bounding_box() {
+        let drawing = Drawing::new();
+        let insert = Insert {
+            name: String::from("missing"),
+            ..Default::default()
+        };
+        assert_eq!(None, bounding_box(&drawing, EntityType::Insert(insert)));
+    }
+
+    #[test]
+    fn compute_extents_updates_header() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Circle(Circle::new(
+            Point::new(5.0, 5.0, 0.0),
+            1.0,
+        ))));
+        drawing.add_entity(Entity::new(EntityType::Line(Line::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+        ))));
+        let mut paper_space_line = Entity::new(EntityType::Line(Line::new(
+            Point::new(100.0, 100.0, 0.0),
+            Point::new(200.0, 200.0, 0.0),
+        )));
+        paper_space_line.common.is_in_paper_space = true;
+        drawing.add_entity(paper_space_line);
+
+        let extents = drawing.compute_extents();
+        assert_box(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(6.0, 6.0, 0.0),
+            extents,
+        );
+        assert_eq!(
+            Point::new(0.0, 0.0, 0.0),
+            drawing.header.minimum_drawing_extents
+        );
+        assert_eq!(
+            Point::new(6.0, 6.0, 0.0),
+            drawing.header.maximum_drawing_extents
+        );
+    }
+}
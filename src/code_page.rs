@@ -0,0 +1,67 @@
+use encoding_rs::Encoding;
+
+/// Returns the text encoding that corresponds to a `$DWGCODEPAGE` value, e.g., `ANSI_1252`, or
+/// `None` if there is no suitable encoding.
+pub(crate) fn get_encoding_from_code_page(code_page: &str) -> Option<&'static Encoding> {
+    let encoding = match &*code_page.to_uppercase() {
+        "ANSI_874" | "DOS874" => encoding_rs::WINDOWS_874,
+        "ANSI_932" | "DOS932" => encoding_rs::SHIFT_JIS,
+        "ANSI_936" | "DOS936" | "GB2312" => encoding_rs::GBK,
+        "ANSI_949" | "DOS949" | "KSC5601" => encoding_rs::EUC_KR,
+        "ANSI_950" | "DOS950" | "BIG5" => encoding_rs::BIG5,
+        "ANSI_1250" => encoding_rs::WINDOWS_1250,
+        "ANSI_1251" => encoding_rs::WINDOWS_1251,
+        "ANSI_1252" | "ISO8859-1" => encoding_rs::WINDOWS_1252,
+        "ANSI_1253" => encoding_rs::WINDOWS_1253,
+        "ANSI_1254" | "ISO8859-9" => encoding_rs::WINDOWS_1254,
+        "ANSI_1255" => encoding_rs::WINDOWS_1255,
+        "ANSI_1256" => encoding_rs::WINDOWS_1256,
+        "ANSI_1257" => encoding_rs::WINDOWS_1257,
+        "ANSI_1258" => encoding_rs::WINDOWS_1258,
+        "DOS866" => encoding_rs::IBM866,
+        "ISO8859-2" => encoding_rs::ISO_8859_2,
+        "ISO8859-3" => encoding_rs::ISO_8859_3,
+        "ISO8859-4" => encoding_rs::ISO_8859_4,
+        "ISO8859-5" => encoding_rs::ISO_8859_5,
+        "ISO8859-6" => encoding_rs::ISO_8859_6,
+        "ISO8859-7" => encoding_rs::ISO_8859_7,
+        "ISO8859-8" => encoding_rs::ISO_8859_8,
+        "ISO8859-10" => encoding_rs::ISO_8859_10,
+        "ISO8859-13" => encoding_rs::ISO_8859_13,
+        "ISO8859-14" => encoding_rs::ISO_8859_14,
+        "ISO8859-15" => encoding_rs::ISO_8859_15,
+        "KOI8-R" => encoding_rs::KOI8_R,
+        "KOI8-U" => encoding_rs::KOI8_U,
+        "MACINTOSH" => encoding_rs::MACINTOSH,
+        "UTF8" | "UTF-8" => encoding_rs::UTF_8,
+        _ => return None, // unknown, or a DOS code page with no equivalent encoding
+    };
+    Some(encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::code_page::*;
+
+    #[test]
+    fn get_encoding_from_known_code_pages() {
+        assert_eq!(
+            Some(encoding_rs::SHIFT_JIS),
+            get_encoding_from_code_page("ANSI_932")
+        );
+        assert_eq!(
+            Some(encoding_rs::WINDOWS_1251),
+            get_encoding_from_code_page("ansi_1251")
+        );
+        assert_eq!(
+            Some(encoding_rs::IBM866),
+            get_encoding_from_code_page("DOS866")
+        );
+    }
+
+    #[test]
+    fn get_encoding_from_unknown_code_page() {
+        assert_eq!(None, get_encoding_from_code_page("DOS437"));
+        assert_eq!(None, get_encoding_from_code_page("not-a-code-page"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{CodePair, CodePairValue, DxfError, DxfResult, ExpectedType};
 
+use crate::code_page::get_encoding_from_code_page;
 use crate::code_pair_value::un_escape_ascii_to_unicode;
 use crate::helper_functions::*;
 use encoding_rs::Encoding;
@@ -8,6 +9,7 @@ use std::io::Read;
 pub(crate) struct CodePairIter<T: Read> {
     reader: T,
     string_encoding: &'static Encoding,
+    detect_string_encoding: bool,
     first_line: String,
     read_first_line: bool,
     read_as_text: bool,
@@ -18,10 +20,16 @@ pub(crate) struct CodePairIter<T: Read> {
 }
 
 impl<T: Read> CodePairIter<T> {
-    pub fn new(reader: T, string_encoding: &'static Encoding, first_line: String) -> Self {
+    pub fn new(
+        reader: T,
+        string_encoding: &'static Encoding,
+        detect_string_encoding: bool,
+        first_line: String,
+    ) -> Self {
         CodePairIter {
             reader,
             string_encoding,
+            detect_string_encoding,
             first_line,
             read_first_line: false,
             read_as_text: true,
@@ -34,6 +42,14 @@ impl<T: Read> CodePairIter<T> {
     pub fn read_as_utf8(&mut self) {
         self.string_encoding = encoding_rs::UTF_8;
     }
+    pub fn read_with_code_page(&mut self, code_page: &str) {
+        // R2007 and later drawings are always UTF-8, regardless of the code page
+        if self.detect_string_encoding && self.string_encoding != encoding_rs::UTF_8 {
+            if let Some(encoding) = get_encoding_from_code_page(code_page) {
+                self.string_encoding = encoding;
+            }
+        }
+    }
     fn detect_binary_or_text_file(&mut self) -> DxfResult<()> {
         match &*self.first_line {
             "AutoCAD Binary DXF" => {
@@ -82,7 +98,7 @@ impl<T: Read> CodePairIter<T> {
         }
 
         let code_offset = self.offset;
-        let code = try_into_option!(parse_i32(String::from(code_line), code_offset));
+        let code = parse_i32(String::from(code_line), code_offset);
 
         // Read value.  If no line is available die horribly.
         self.offset += 1;
@@ -92,6 +108,9 @@ impl<T: Read> CodePairIter<T> {
             None => return Some(Err(DxfError::UnexpectedEndOfInput)),
         };
 
+        // the value line has been consumed so a malformed code only affects this pair
+        let code = try_into_option!(code);
+
         // construct the value pair
         let expected_type = match ExpectedType::get_expected_type(code) {
             Some(t) => t,
@@ -114,7 +133,7 @@ impl<T: Read> CodePairIter<T> {
                 CodePairValue::Double(try_into_option!(parse_f64(value_line, self.offset)))
             }
             ExpectedType::Str => {
-                let value_line = if self.string_encoding == encoding_rs::WINDOWS_1252 {
+                let value_line = if self.string_encoding != encoding_rs::UTF_8 {
                     un_escape_ascii_to_unicode(&value_line)
                 } else {
                     value_line
@@ -221,17 +240,18 @@ impl<T: Read> CodePairIter<T> {
         Some(Ok(CodePair::new(code, value, self.offset)))
     }
     fn read_string_binary(&mut self) -> DxfResult<String> {
-        let mut s = String::new();
+        let mut bytes = vec![];
         loop {
             match read_u8(&mut self.reader) {
                 Some(Ok(0)) => break,
-                Some(Ok(c)) => s.push(c as char),
+                Some(Ok(c)) => bytes.push(c),
                 Some(Err(e)) => return Err(DxfError::IoError(e)),
                 None => return Err(DxfError::UnexpectedEndOfInput),
             }
         }
 
-        Ok(s)
+        let (s, _, _) = self.string_encoding.decode(&bytes);
+        Ok(s.into_owned())
     }
 }
 
@@ -271,6 +291,7 @@ mod tests {
         let mut reader = CodePairIter::<&[u8]> {
             reader: data.as_slice(),
             string_encoding: encoding_rs::WINDOWS_1252,
+            detect_string_encoding: false,
             first_line: String::from("not-important"),
             read_first_line: true,
             read_as_text: false,
@@ -307,6 +328,7 @@ mod tests {
         let mut reader = CodePairIter::<&[u8]> {
             reader: data.as_bytes(),
             string_encoding: encoding_rs::WINDOWS_1252,
+            detect_string_encoding: false,
             first_line: String::from("not-important"),
             read_first_line: true,
             read_as_text: true,
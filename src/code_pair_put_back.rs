@@ -1,12 +1,19 @@
 use std::io::Read;
 
 use crate::code_pair_iter::CodePairIter;
+use crate::dxf_error::DxfError;
 use crate::dxf_result::DxfResult;
-use crate::CodePair;
+use crate::{CodePair, Diagnostic, Handle};
 
 pub(crate) struct CodePairPutBack<T: Read> {
     top: Vec<DxfResult<CodePair>>,
     iter: CodePairIter<T>,
+    recovery: bool,
+    strict: bool,
+    section: String,
+    handle: Handle,
+    offset: usize,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl<T: Read> CodePairPutBack<T> {
@@ -14,7 +21,16 @@ impl<T: Read> CodePairPutBack<T> {
     where
         T: Read,
     {
-        CodePairPutBack { top: vec![], iter }
+        CodePairPutBack {
+            top: vec![],
+            iter,
+            recovery: false,
+            strict: false,
+            section: String::new(),
+            handle: Handle::empty(),
+            offset: 0,
+            diagnostics: vec![],
+        }
     }
     pub fn put_back(&mut self, item: DxfResult<CodePair>) {
         self.top.push(item);
@@ -22,16 +38,93 @@ impl<T: Read> CodePairPutBack<T> {
     pub fn read_as_utf8(&mut self) {
         self.iter.read_as_utf8()
     }
+    pub fn read_with_code_page(&mut self, code_page: &str) {
+        self.iter.read_with_code_page(code_page)
+    }
+    pub fn set_recovery(&mut self, recovery: bool) {
+        self.recovery = recovery;
+    }
+    /// When `true`, an entity or object that can't be read fails the load instead of ending the
+    /// section early.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+    pub fn set_section(&mut self, section: &str) {
+        self.section = String::from(section);
+        self.handle = Handle::empty();
+    }
+    /// In recovery mode, records the error as a `Diagnostic` and returns `Ok(())`, otherwise
+    /// returns the error.
+    pub fn recover(&mut self, error: DxfError) -> DxfResult<()> {
+        if !self.recovery {
+            return Err(error);
+        }
+
+        self.diagnostics.push(Diagnostic {
+            offset: error.offset().unwrap_or(self.offset),
+            section: self.section.clone(),
+            handle: self.handle,
+            message: error.to_string(),
+        });
+        Ok(())
+    }
+    /// Skips code pairs until one matching the predicate is found and puts it back.  Returns
+    /// `false` if the end of the input or an error was reached first.
+    pub fn skip_until<F>(&mut self, predicate: F) -> bool
+    where
+        F: Fn(&CodePair) -> bool,
+    {
+        loop {
+            match self.next() {
+                Some(Ok(pair)) => {
+                    if predicate(&pair) {
+                        self.put_back(Ok(pair));
+                        return true;
+                    }
+                }
+                Some(Err(_)) | None => return false,
+            }
+        }
+    }
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
 }
 
 impl<T: Read> Iterator for CodePairPutBack<T> {
     type Item = DxfResult<CodePair>;
 
     fn next(&mut self) -> Option<DxfResult<CodePair>> {
-        if self.top.is_empty() {
-            self.iter.next()
-        } else {
-            Some(self.top.pop().unwrap())
+        loop {
+            let item = if self.top.is_empty() {
+                self.iter.next()
+            } else {
+                self.top.pop()
+            };
+            match item {
+                Some(Ok(ref pair)) => {
+                    // keep track of where we are for diagnostics
+                    self.offset = pair.offset;
+                    match pair.code {
+                        0 => self.handle = Handle::empty(),
+                        5 | 105 if self.section != "HEADER" => {
+                            self.handle = pair.as_handle().unwrap_or_else(|_| Handle::empty());
+                        }
+                        _ => (),
+                    }
+                }
+                Some(Err(e)) if self.recovery && e.is_value_error() => {
+                    // skip the malformed code pair; the value keeps its default
+                    self.recover(e).ok();
+                    continue;
+                }
+                _ => (),
+            }
+
+            return item;
         }
     }
 }
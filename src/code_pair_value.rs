@@ -1,3 +1,4 @@
+use encoding_rs::{EncoderResult, Encoding};
 use std::borrow::Cow;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
@@ -219,6 +220,45 @@ fn test_unicode_escape_2() {
     );
 }
 
+pub(crate) fn encode_with_unicode_escapes(val: &str, encoding: &'static Encoding) -> Vec<u8> {
+    // characters that can't be represented in `encoding` are written as `\U+XXXX`
+    let mut encoder = encoding.new_encoder();
+    let mut result = Vec::with_capacity(val.len());
+    let mut buffer = [0u8; 256];
+    let mut remaining = val;
+    loop {
+        let (status, read, written) =
+            encoder.encode_from_utf8_without_replacement(remaining, &mut buffer, true);
+        result.extend_from_slice(&buffer[..written]);
+        remaining = &remaining[read..];
+        match status {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => (), // the buffer was drained, keep going
+            EncoderResult::Unmappable(c) => {
+                result.extend_from_slice(escape_unicode_to_ascii(&c.to_string()).as_bytes())
+            }
+        }
+    }
+
+    result
+}
+
+#[test]
+fn test_encode_with_unicode_escapes() {
+    assert_eq!(
+        vec![b'R', b'e', b'p', 0xE8, b'r', b'e'],
+        encode_with_unicode_escapes("Repère", encoding_rs::WINDOWS_1252)
+    );
+    assert_eq!(
+        b"\\U+4F60\\U+597D".to_vec(),
+        encode_with_unicode_escapes("你好", encoding_rs::WINDOWS_1252)
+    );
+    assert_eq!(
+        vec![0xC0, 0xE0, b' ', b'\\', b'U', b'+', b'4', b'F', b'6', b'0'],
+        encode_with_unicode_escapes("Аа 你", encoding_rs::WINDOWS_1251)
+    );
+}
+
 pub(crate) fn un_escape_ascii_to_unicode(val: &str) -> String {
     let mut result = String::from("");
     let mut seq = String::from("");
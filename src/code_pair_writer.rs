@@ -3,9 +3,10 @@ use std::io::Write;
 extern crate byteorder;
 use self::byteorder::{LittleEndian, WriteBytesExt};
 
-use crate::code_pair_value::{escape_control_characters, escape_unicode_to_ascii};
+use crate::code_pair_value::{encode_with_unicode_escapes, escape_control_characters};
 use crate::enums::AcadVersion;
 use crate::{CodePair, CodePairValue, DxfResult};
+use encoding_rs::Encoding;
 
 pub(crate) struct CodePairWriter<'a, T>
 where
@@ -13,24 +14,37 @@ where
 {
     writer: &'a mut T,
     as_text: bool,
-    text_as_ascii: bool,
+    text_encoding: &'static Encoding,
     version: AcadVersion,
+    /// When set, code pairs that were introduced in R13, e.g., subclass markers, aren't written
+    /// for older versions.
+    is_strict: bool,
 }
 
 impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
     pub fn new(
         writer: &'a mut T,
         as_text: bool,
-        text_as_ascii: bool,
+        text_encoding: &'static Encoding,
         version: AcadVersion,
     ) -> Self {
         CodePairWriter {
             writer,
             as_text,
-            text_as_ascii,
+            text_encoding,
             version,
+            is_strict: false,
         }
     }
+    pub fn is_strict(&self) -> bool {
+        self.is_strict
+    }
+    pub fn set_strict(&mut self, is_strict: bool) {
+        self.is_strict = is_strict;
+    }
+    pub fn get_mut(&mut self) -> &mut T {
+        self.writer
+    }
     pub fn write_prelude(&mut self) -> DxfResult<()> {
         if !self.as_text {
             self.writer
@@ -42,6 +56,12 @@ impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
         Ok(())
     }
     pub fn write_code_pair(&mut self, pair: &CodePair) -> DxfResult<()> {
+        if self.is_strict
+            && self.version < AcadVersion::R13
+            && matches!(pair.code, 100 | 102 | 330 | 360)
+        {
+            return Ok(());
+        }
         if self.as_text {
             self.write_ascii_code_pair(pair)
         } else {
@@ -53,13 +73,8 @@ impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
             .write_fmt(format_args!("{: >3}\r\n", pair.code))?;
         match pair.value {
             CodePairValue::Str(ref s) => {
-                let s = escape_control_characters(&s);
-                let s = if self.text_as_ascii {
-                    escape_unicode_to_ascii(&s)
-                } else {
-                    s
-                };
-                self.writer.write_fmt(format_args!("{}\r\n", s))?;
+                self.write_string(s)?;
+                self.writer.write_all(b"\r\n")?;
             }
             _ => self.writer.write_fmt(format_args!("{}\r\n", &pair.value))?,
         };
@@ -90,10 +105,7 @@ impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
             CodePairValue::Short(s) => self.writer.write_i16::<LittleEndian>(s)?,
             CodePairValue::Double(d) => self.writer.write_f64::<LittleEndian>(d)?,
             CodePairValue::Str(ref s) => {
-                for &b in escape_control_characters(s).as_bytes() {
-                    self.writer.write_u8(b)?;
-                }
-
+                self.write_string(s)?;
                 self.writer.write_u8(0)?;
             }
             CodePairValue::Binary(ref buf) => {
@@ -106,6 +118,16 @@ impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
 
         Ok(())
     }
+    fn write_string(&mut self, s: &str) -> DxfResult<()> {
+        let s = escape_control_characters(s);
+        if self.text_encoding == encoding_rs::UTF_8 {
+            self.writer.write_all(s.as_bytes())?;
+        } else {
+            self.writer
+                .write_all(&encode_with_unicode_escapes(&s, self.text_encoding))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -120,8 +142,9 @@ mod tests {
         let mut writer = CodePairWriter {
             writer: &mut buf,
             as_text: false,
-            text_as_ascii: true,
+            text_encoding: encoding_rs::WINDOWS_1252,
             version: AcadVersion::R2004,
+            is_strict: false,
         };
         writer
             .write_binary_code_pair(&pair)
@@ -136,8 +159,9 @@ mod tests {
         let mut writer = CodePairWriter {
             writer: &mut buf,
             as_text: true,
-            text_as_ascii: true,
+            text_encoding: encoding_rs::WINDOWS_1252,
             version: AcadVersion::R2004,
+            is_strict: false,
         };
         writer
             .write_ascii_code_pair(&pair)
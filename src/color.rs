@@ -1,5 +1,265 @@
 use crate::tables::Layer;
 
+/// The RGB values of the AutoCAD Color Index.  Index 0 is unused; it represents BYBLOCK.
+const ACI_PALETTE: [(u8, u8, u8); 256] = [
+    (0, 0, 0),
+    (255, 0, 0),
+    (255, 255, 0),
+    (0, 255, 0),
+    (0, 255, 255),
+    (0, 0, 255),
+    (255, 0, 255),
+    (255, 255, 255),
+    (128, 128, 128),
+    (192, 192, 192),
+    (255, 0, 0),
+    (255, 170, 170),
+    (189, 0, 0),
+    (189, 126, 126),
+    (129, 0, 0),
+    (129, 86, 86),
+    (104, 0, 0),
+    (104, 69, 69),
+    (79, 0, 0),
+    (79, 53, 53),
+    (255, 63, 0),
+    (255, 191, 170),
+    (189, 47, 0),
+    (189, 142, 126),
+    (129, 32, 0),
+    (129, 97, 86),
+    (104, 26, 0),
+    (104, 78, 69),
+    (79, 20, 0),
+    (79, 59, 53),
+    (255, 127, 0),
+    (255, 212, 170),
+    (189, 94, 0),
+    (189, 157, 126),
+    (129, 64, 0),
+    (129, 107, 86),
+    (104, 52, 0),
+    (104, 86, 69),
+    (79, 39, 0),
+    (79, 66, 53),
+    (255, 191, 0),
+    (255, 234, 170),
+    (189, 142, 0),
+    (189, 173, 126),
+    (129, 97, 0),
+    (129, 118, 86),
+    (104, 78, 0),
+    (104, 95, 69),
+    (79, 59, 0),
+    (79, 72, 53),
+    (255, 255, 0),
+    (255, 255, 170),
+    (189, 189, 0),
+    (189, 189, 126),
+    (129, 129, 0),
+    (129, 129, 86),
+    (104, 104, 0),
+    (104, 104, 69),
+    (79, 79, 0),
+    (79, 79, 53),
+    (191, 255, 0),
+    (234, 255, 170),
+    (142, 189, 0),
+    (173, 189, 126),
+    (97, 129, 0),
+    (118, 129, 86),
+    (78, 104, 0),
+    (95, 104, 69),
+    (59, 79, 0),
+    (72, 79, 53),
+    (127, 255, 0),
+    (212, 255, 170),
+    (94, 189, 0),
+    (157, 189, 126),
+    (64, 129, 0),
+    (107, 129, 86),
+    (52, 104, 0),
+    (86, 104, 69),
+    (39, 79, 0),
+    (66, 79, 53),
+    (63, 255, 0),
+    (191, 255, 170),
+    (47, 189, 0),
+    (142, 189, 126),
+    (32, 129, 0),
+    (97, 129, 86),
+    (26, 104, 0),
+    (78, 104, 69),
+    (20, 79, 0),
+    (59, 79, 53),
+    (0, 255, 0),
+    (170, 255, 170),
+    (0, 189, 0),
+    (126, 189, 126),
+    (0, 129, 0),
+    (86, 129, 86),
+    (0, 104, 0),
+    (69, 104, 69),
+    (0, 79, 0),
+    (53, 79, 53),
+    (0, 255, 63),
+    (170, 255, 191),
+    (0, 189, 47),
+    (126, 189, 142),
+    (0, 129, 32),
+    (86, 129, 97),
+    (0, 104, 26),
+    (69, 104, 78),
+    (0, 79, 20),
+    (53, 79, 59),
+    (0, 255, 127),
+    (170, 255, 212),
+    (0, 189, 94),
+    (126, 189, 157),
+    (0, 129, 64),
+    (86, 129, 107),
+    (0, 104, 52),
+    (69, 104, 86),
+    (0, 79, 39),
+    (53, 79, 66),
+    (0, 255, 191),
+    (170, 255, 234),
+    (0, 189, 142),
+    (126, 189, 173),
+    (0, 129, 97),
+    (86, 129, 118),
+    (0, 104, 78),
+    (69, 104, 95),
+    (0, 79, 59),
+    (53, 79, 72),
+    (0, 255, 255),
+    (170, 255, 255),
+    (0, 189, 189),
+    (126, 189, 189),
+    (0, 129, 129),
+    (86, 129, 129),
+    (0, 104, 104),
+    (69, 104, 104),
+    (0, 79, 79),
+    (53, 79, 79),
+    (0, 191, 255),
+    (170, 234, 255),
+    (0, 142, 189),
+    (126, 173, 189),
+    (0, 97, 129),
+    (86, 118, 129),
+    (0, 78, 104),
+    (69, 95, 104),
+    (0, 59, 79),
+    (53, 72, 79),
+    (0, 127, 255),
+    (170, 212, 255),
+    (0, 94, 189),
+    (126, 157, 189),
+    (0, 64, 129),
+    (86, 107, 129),
+    (0, 52, 104),
+    (69, 86, 104),
+    (0, 39, 79),
+    (53, 66, 79),
+    (0, 63, 255),
+    (170, 191, 255),
+    (0, 47, 189),
+    (126, 142, 189),
+    (0, 32, 129),
+    (86, 97, 129),
+    (0, 26, 104),
+    (69, 78, 104),
+    (0, 20, 79),
+    (53, 59, 79),
+    (0, 0, 255),
+    (170, 170, 255),
+    (0, 0, 189),
+    (126, 126, 189),
+    (0, 0, 129),
+    (86, 86, 129),
+    (0, 0, 104),
+    (69, 69, 104),
+    (0, 0, 79),
+    (53, 53, 79),
+    (63, 0, 255),
+    (191, 170, 255),
+    (47, 0, 189),
+    (142, 126, 189),
+    (32, 0, 129),
+    (97, 86, 129),
+    (26, 0, 104),
+    (78, 69, 104),
+    (20, 0, 79),
+    (59, 53, 79),
+    (127, 0, 255),
+    (212, 170, 255),
+    (94, 0, 189),
+    (157, 126, 189),
+    (64, 0, 129),
+    (107, 86, 129),
+    (52, 0, 104),
+    (86, 69, 104),
+    (39, 0, 79),
+    (66, 53, 79),
+    (191, 0, 255),
+    (234, 170, 255),
+    (142, 0, 189),
+    (173, 126, 189),
+    (97, 0, 129),
+    (118, 86, 129),
+    (78, 0, 104),
+    (95, 69, 104),
+    (59, 0, 79),
+    (72, 53, 79),
+    (255, 0, 255),
+    (255, 170, 255),
+    (189, 0, 189),
+    (189, 126, 189),
+    (129, 0, 129),
+    (129, 86, 129),
+    (104, 0, 104),
+    (104, 69, 104),
+    (79, 0, 79),
+    (79, 53, 79),
+    (255, 0, 191),
+    (255, 170, 234),
+    (189, 0, 142),
+    (189, 126, 173),
+    (129, 0, 97),
+    (129, 86, 118),
+    (104, 0, 78),
+    (104, 69, 95),
+    (79, 0, 59),
+    (79, 53, 72),
+    (255, 0, 127),
+    (255, 170, 212),
+    (189, 0, 94),
+    (189, 126, 157),
+    (129, 0, 64),
+    (129, 86, 107),
+    (104, 0, 52),
+    (104, 69, 86),
+    (79, 0, 39),
+    (79, 53, 66),
+    (255, 0, 63),
+    (255, 170, 191),
+    (189, 0, 47),
+    (189, 126, 142),
+    (129, 0, 32),
+    (129, 86, 97),
+    (104, 0, 26),
+    (104, 69, 78),
+    (79, 0, 20),
+    (79, 53, 59),
+    (51, 51, 51),
+    (80, 80, 80),
+    (105, 105, 105),
+    (130, 130, 130),
+    (190, 190, 190),
+    (255, 255, 255),
+];
+
 /// Represents an indexed color.
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
@@ -76,6 +336,23 @@ impl Color {
             raw_value: i16::from(i),
         }
     }
+    /// Gets the RGB value of an index color, or `None` if the color isn't an index.
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        self.index().map(|i| ACI_PALETTE[i as usize])
+    }
+    /// Creates the index color closest to the specified RGB value.
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Color {
+        let distance = |(pr, pg, pb): (u8, u8, u8)| {
+            let dr = i32::from(pr) - i32::from(r);
+            let dg = i32::from(pg) - i32::from(g);
+            let db = i32::from(pb) - i32::from(b);
+            dr * dr + dg * dg + db * db
+        };
+        let index = (1..=255u8)
+            .min_by_key(|i| distance(ACI_PALETTE[*i as usize]))
+            .unwrap_or(7);
+        Color::from_index(index)
+    }
     pub(crate) fn get_writable_color_value(&self, layer: &Layer) -> i16 {
         let value = self.get_raw_value().abs();
         if layer.is_layer_on {
@@ -85,3 +362,29 @@ impl Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::Color;
+
+    #[test]
+    fn index_color_to_rgb() {
+        assert_eq!(Some((255, 0, 0)), Color::from_index(1).to_rgb());
+        assert_eq!(Some((255, 255, 255)), Color::from_index(7).to_rgb());
+        assert_eq!(Some((255, 127, 0)), Color::from_index(30).to_rgb());
+        assert_eq!(None, Color::by_layer().to_rgb());
+        assert_eq!(None, Color::by_block().to_rgb());
+    }
+
+    #[test]
+    fn nearest_index_color_from_rgb() {
+        assert_eq!(Color::from_index(1), Color::from_rgb(255, 0, 0));
+        assert_eq!(Color::from_index(5), Color::from_rgb(0, 0, 250));
+        // white is both 7 and 255; the lower index wins
+        assert_eq!(Color::from_index(7), Color::from_rgb(255, 255, 255));
+        for i in 1..=255 {
+            let (r, g, b) = Color::from_index(i).to_rgb().unwrap();
+            assert_eq!(Some((r, g, b)), Color::from_rgb(r, g, b).to_rgb());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+use crate::Handle;
+
+/// A problem encountered while loading a drawing in recovery mode.  The offending code pair or
+/// item was skipped.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    /// The line number for text files or the byte offset for binary files.
+    pub offset: usize,
+    /// The name of the section being read, e.g., `ENTITIES`, or an empty string if the problem
+    /// occurred outside of a section.
+    pub section: String,
+    /// The handle of the item being read, or an empty handle if not known.
+    pub handle: Handle,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "line/offset {}", self.offset)?;
+        if !self.section.is_empty() {
+            write!(formatter, ", section {}", self.section)?;
+        }
+        if !self.handle.is_empty() {
+            write!(formatter, ", handle {}", self.handle.as_string())?;
+        }
+        write!(formatter, ": {}", self.message)
+    }
+}
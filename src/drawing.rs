@@ -4,15 +4,22 @@ use self::encoding_rs::Encoding;
 extern crate image;
 use self::image::DynamicImage;
 
+use crate::code_page::get_encoding_from_code_page;
 use crate::code_pair_put_back::CodePairPutBack;
-use crate::drawing_item::{DrawingItem, DrawingItemMut};
+use crate::drawing_item::{
+    DrawingItem, DrawingItemMut, EntityLocation, ItemCollection, ItemLocation,
+};
 use crate::entities::*;
 use crate::enums::*;
 use crate::header::*;
 use crate::objects::*;
 use crate::tables::*;
 
-use crate::{CodePair, CodePairValue, DxfError, DxfResult, Handle};
+use crate::{
+    AcDsData, AuditProblem, AuditProblemKind, AuditReport, BoundingBox, CodePair, CodePairValue,
+    Diagnostic, DxfError, DxfResult, ExtraSection, Handle, LoadOptions, PurgeOptions, PurgeReport,
+    SaveOptions, TransformationMatrix, Vector, XData, XDataItem,
+};
 
 use crate::dxb_reader::DxbReader;
 use crate::dxb_writer::DxbWriter;
@@ -32,13 +39,17 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 
 use itertools::put_back;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::Iterator;
 use std::path::Path;
 
 pub(crate) const AUTO_REPLACE_HANDLE: Handle = Handle(0xFFFF_FFFF_FFFF_FFFF);
 
+/// The maximum depth of nested block references that are followed.
+pub(crate) const MAX_INSERT_DEPTH: usize = 16;
+
 /// Represents a DXF drawing.
+#[derive(Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Drawing {
     /// The drawing's header.  Contains various drawing-specific values and settings.
@@ -77,6 +88,15 @@ pub struct Drawing {
     /// The thumbnail image preview of the drawing.
     #[cfg_attr(feature = "serialize", serde(skip))]
     pub thumbnail: Option<DynamicImage>,
+
+    /// Sections not otherwise supported, e.g., `ACDSDATA`.  These are written back unchanged.
+    pub extra_sections: Vec<ExtraSection>,
+
+    /// Internal map of handles to where the items are stored.  Entries are verified on lookup and
+    /// missing ones are searched for because handles can be changed through the mutable iterators
+    /// and the map isn't serialized.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    __handle_index: HashMap<Handle, ItemLocation>,
 }
 
 // public implementation
@@ -99,19 +119,48 @@ impl Drawing {
             __entities: vec![],
             __objects: vec![],
             thumbnail: None,
+            extra_sections: vec![],
+            __handle_index: HashMap::new(),
         };
         drawing.normalize();
         drawing
     }
-    /// Loads a `Drawing` from anything that implements the `Read` trait.
+    /// Loads a `Drawing` from anything that implements the `Read` trait.  The text encoding is
+    /// determined by the drawing's `$DWGCODEPAGE` header variable, falling back to Windows-1252.
     pub fn load<T>(reader: &mut T) -> DxfResult<Drawing>
     where
         T: Read + ?Sized,
     {
-        Drawing::load_with_encoding(reader, encoding_rs::WINDOWS_1252)
+        let (drawing, _) = Drawing::load_internal(reader, encoding_rs::WINDOWS_1252, true, None)?;
+        Ok(drawing)
     }
     /// Loads a `Drawing` from anything that implements the `Read` trait using the specified text encoding.
     pub fn load_with_encoding<T>(reader: &mut T, encoding: &'static Encoding) -> DxfResult<Drawing>
+    where
+        T: Read + ?Sized,
+    {
+        let (drawing, _) = Drawing::load_internal(reader, encoding, false, None)?;
+        Ok(drawing)
+    }
+    /// Loads a `Drawing` from anything that implements the `Read` trait using the specified
+    /// options.  In recovery mode, the problems that were skipped over are returned as
+    /// `Diagnostic`s; otherwise the returned list is always empty and an entity or object that
+    /// can't be read fails the load.
+    pub fn load_with_options<T>(
+        reader: &mut T,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<Diagnostic>)>
+    where
+        T: Read + ?Sized,
+    {
+        Drawing::load_internal(reader, encoding_rs::WINDOWS_1252, true, Some(options))
+    }
+    fn load_internal<T>(
+        reader: &mut T,
+        encoding: &'static Encoding,
+        detect_encoding: bool,
+        options: Option<&LoadOptions>,
+    ) -> DxfResult<(Drawing, Vec<Diagnostic>)>
     where
         T: Read + ?Sized,
     {
@@ -123,33 +172,54 @@ impl Drawing {
         match &*first_line {
             "AutoCAD DXB 1.0" => {
                 let mut reader = DxbReader::new(reader);
-                reader.load()
+                Ok((reader.load()?, vec![]))
             }
             _ => {
-                let reader = CodePairIter::new(reader, encoding, first_line);
+                let reader = CodePairIter::new(reader, encoding, detect_encoding, first_line);
                 let mut drawing = Drawing::new();
                 drawing.clear();
                 let mut iter = CodePairPutBack::from_code_pair_iter(reader);
+                // without options a broken entity or object ends its section early, as it always has
+                if let Some(options) = options {
+                    iter.set_recovery(options.recovery);
+                    iter.set_strict(!options.recovery);
+                }
                 Drawing::read_sections(&mut drawing, &mut iter)?;
+                drawing.ensure_line_types_are_present_for_layers();
                 match iter.next() {
                     Some(Ok(CodePair {
                         code: 0,
                         value: CodePairValue::Str(ref s),
                         ..
-                    })) if s == "EOF" => Ok(drawing),
-                    Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                    })) if s == "EOF" => (),
+                    Some(Ok(pair)) => iter.recover(DxfError::UnexpectedCodePair(
                         pair,
                         String::from("expected 0/EOF"),
-                    )),
-                    Some(Err(e)) => Err(e),
-                    None => Ok(drawing),
+                    ))?,
+                    Some(Err(e)) => return Err(e),
+                    None => (),
                 }
+
+                drawing.rebuild_handle_index();
+                Ok((drawing, iter.take_diagnostics()))
             }
         }
     }
-    /// Loads a `Drawing` from disk, using a `BufReader`.
+    /// Loads a `Drawing` from disk, using a `BufReader`.  The text encoding is determined by the
+    /// drawing's `$DWGCODEPAGE` header variable, falling back to Windows-1252.
     pub fn load_file(path: impl AsRef<Path>) -> DxfResult<Drawing> {
-        Drawing::load_file_with_encoding(path, encoding_rs::WINDOWS_1252)
+        let file = File::open(&path)?;
+        let mut buf_reader = BufReader::new(file);
+        Drawing::load(&mut buf_reader)
+    }
+    /// Loads a `Drawing` from disk, using a `BufReader` and the specified options.
+    pub fn load_file_with_options(
+        path: impl AsRef<Path>,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<Diagnostic>)> {
+        let file = File::open(&path)?;
+        let mut buf_reader = BufReader::new(file);
+        Drawing::load_with_options(&mut buf_reader, options)
     }
     /// Loads a `Drawing` from disk, using a `BufReader` with the specified text encoding.
     pub fn load_file_with_encoding(
@@ -165,31 +235,55 @@ impl Drawing {
     where
         T: Write + ?Sized,
     {
-        self.save_internal(writer, true)
+        self.save_internal(writer, true, false)
+    }
+    /// Writes a `Drawing` to anything that implements the `Write` trait using the specified
+    /// options.
+    pub fn save_with_options<T>(&self, writer: &mut T, options: &SaveOptions) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        if options.downgrade {
+            let mut drawing = self.clone();
+            drawing.convert_to(self.header.version);
+            drawing.save_internal(writer, true, true)
+        } else {
+            self.save_internal(writer, true, false)
+        }
     }
     /// Writes a `Drawing` as binary to anything that implements the `Write` trait.
     pub fn save_binary<T>(&self, writer: &mut T) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
-        self.save_internal(writer, false)
+        self.save_internal(writer, false, false)
     }
-    fn save_internal<T>(&self, writer: &mut T, as_ascii: bool) -> DxfResult<()>
+    fn save_internal<T>(&self, writer: &mut T, as_ascii: bool, is_strict: bool) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
-        let text_as_ascii = self.header.version <= AcadVersion::R2004;
         let mut code_pair_writer =
-            CodePairWriter::new(writer, as_ascii, text_as_ascii, self.header.version);
-        let write_handles = self.header.version >= AcadVersion::R13 || self.header.handles_enabled;
+            CodePairWriter::new(writer, as_ascii, self.text_encoding(), self.header.version);
+        code_pair_writer.set_strict(is_strict);
+        let write_handles = self.write_handles();
         code_pair_writer.write_prelude()?;
+        self.write_extra_sections("", &mut code_pair_writer)?;
         self.header.write(&mut code_pair_writer)?;
+        self.write_extra_sections("HEADER", &mut code_pair_writer)?;
         self.write_classes(&mut code_pair_writer)?;
+        self.write_extra_sections("CLASSES", &mut code_pair_writer)?;
         self.write_tables(write_handles, &mut code_pair_writer)?;
+        self.write_extra_sections("TABLES", &mut code_pair_writer)?;
         self.write_blocks(write_handles, &mut code_pair_writer)?;
+        self.write_extra_sections("BLOCKS", &mut code_pair_writer)?;
         self.write_entities(write_handles, &mut code_pair_writer)?;
-        self.write_objects(&mut code_pair_writer)?;
+        self.write_extra_sections("ENTITIES", &mut code_pair_writer)?;
+        if !is_strict || self.header.version >= AcadVersion::R13 {
+            self.write_objects(&mut code_pair_writer)?;
+        }
+        self.write_extra_sections("OBJECTS", &mut code_pair_writer)?;
         self.write_thumbnail(&mut code_pair_writer)?;
+        self.write_extra_sections("THUMBNAILIMAGE", &mut code_pair_writer)?;
         code_pair_writer.write_code_pair(&CodePair::new_str(0, "EOF"))?;
         Ok(())
     }
@@ -197,6 +291,16 @@ impl Drawing {
     pub fn save_file(&self, path: impl AsRef<Path>) -> DxfResult<()> {
         self.save_file_internal(path, true)
     }
+    /// Writes a `Drawing` to disk, using a `BufWriter` and the specified options.
+    pub fn save_file_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: &SaveOptions,
+    ) -> DxfResult<()> {
+        let file = File::create(&path)?;
+        let mut writer = BufWriter::new(file);
+        self.save_with_options(&mut writer, options)
+    }
     /// Writes a `Drawing` as binary to disk, using a `BufWriter`.
     pub fn save_file_binary(&self, path: impl AsRef<Path>) -> DxfResult<()> {
         self.save_file_internal(path, false)
@@ -204,7 +308,7 @@ impl Drawing {
     fn save_file_internal(&self, path: impl AsRef<Path>, as_ascii: bool) -> DxfResult<()> {
         let file = File::create(&path)?;
         let mut writer = BufWriter::new(file);
-        self.save_internal(&mut writer, as_ascii)
+        self.save_internal(&mut writer, as_ascii, false)
     }
     /// Writes a `Drawing` as DXB to anything that implements the `Write` trait.
     pub fn save_dxb<T>(&self, writer: &mut T) -> DxfResult<()>
@@ -235,7 +339,9 @@ impl Drawing {
     }
     /// Removes the specified `AppId` from the `Drawing`.
     pub fn remove_app_id(&mut self, index: usize) -> Option<AppId> {
-        Drawing::remove_item(&mut self.__app_ids, index)
+        let item = Drawing::remove_item(&mut self.__app_ids, index)?;
+        self.reindex_handles(ItemCollection::AppIds, index);
+        Some(item)
     }
     /// Returns an iterator for all block records.
     pub fn block_records(&self) -> impl Iterator<Item = &BlockRecord> {
@@ -252,7 +358,9 @@ impl Drawing {
     }
     /// Removes the specified `BlockRecord` from the `Drawing`.
     pub fn remove_block_record(&mut self, index: usize) -> Option<BlockRecord> {
-        Drawing::remove_item(&mut self.__block_records, index)
+        let item = Drawing::remove_item(&mut self.__block_records, index)?;
+        self.reindex_handles(ItemCollection::BlockRecords, index);
+        Some(item)
     }
     /// Returns an iterator for all dimension styles.
     pub fn dim_styles(&self) -> impl Iterator<Item = &DimStyle> {
@@ -269,7 +377,9 @@ impl Drawing {
     }
     /// Removes the specified `DimStyle` from the `Drawing`.
     pub fn remove_dim_style(&mut self, index: usize) -> Option<DimStyle> {
-        Drawing::remove_item(&mut self.__dim_styles, index)
+        let item = Drawing::remove_item(&mut self.__dim_styles, index)?;
+        self.reindex_handles(ItemCollection::DimStyles, index);
+        Some(item)
     }
     /// Returns an iterator for all layers.
     pub fn layers(&self) -> impl Iterator<Item = &Layer> {
@@ -282,11 +392,14 @@ impl Drawing {
     /// Adds a layer to the `Drawing`.
     pub fn add_layer(&mut self, mut layer: Layer) -> &Layer {
         layer.handle = self.next_handle();
+        self.ensure_line_type_is_present(&layer.line_type_name);
         self.add_layer_no_handle_set(layer)
     }
     /// Removes the specified `Layer` from the `Drawing`.
     pub fn remove_layer(&mut self, index: usize) -> Option<Layer> {
-        Drawing::remove_item(&mut self.__layers, index)
+        let item = Drawing::remove_item(&mut self.__layers, index)?;
+        self.reindex_handles(ItemCollection::Layers, index);
+        Some(item)
     }
     /// Returns an iterator for all line types.
     pub fn line_types(&self) -> impl Iterator<Item = &LineType> {
@@ -303,7 +416,9 @@ impl Drawing {
     }
     /// Removes the specified `LineType` from the `Drawing`.
     pub fn remove_line_type(&mut self, index: usize) -> Option<LineType> {
-        Drawing::remove_item(&mut self.__line_types, index)
+        let item = Drawing::remove_item(&mut self.__line_types, index)?;
+        self.reindex_handles(ItemCollection::LineTypes, index);
+        Some(item)
     }
     /// Returns an iterator for all styles.
     pub fn styles(&self) -> impl Iterator<Item = &Style> {
@@ -320,7 +435,9 @@ impl Drawing {
     }
     /// Removes the specified `Style` from the `Drawing`.
     pub fn remove_style(&mut self, index: usize) -> Option<Style> {
-        Drawing::remove_item(&mut self.__styles, index)
+        let item = Drawing::remove_item(&mut self.__styles, index)?;
+        self.reindex_handles(ItemCollection::Styles, index);
+        Some(item)
     }
     /// Returns an iterator for all ucss.
     pub fn ucss(&self) -> impl Iterator<Item = &Ucs> {
@@ -337,7 +454,9 @@ impl Drawing {
     }
     /// Removes the specified `Ucs` from the `Drawing`.
     pub fn remove_ucs(&mut self, index: usize) -> Option<Ucs> {
-        Drawing::remove_item(&mut self.__ucss, index)
+        let item = Drawing::remove_item(&mut self.__ucss, index)?;
+        self.reindex_handles(ItemCollection::Ucss, index);
+        Some(item)
     }
     /// Returns an iterator for all views.
     pub fn views(&self) -> impl Iterator<Item = &View> {
@@ -354,7 +473,9 @@ impl Drawing {
     }
     /// Removes the specified `View` from the `Drawing`.
     pub fn remove_view(&mut self, index: usize) -> Option<View> {
-        Drawing::remove_item(&mut self.__views, index)
+        let item = Drawing::remove_item(&mut self.__views, index)?;
+        self.reindex_handles(ItemCollection::Views, index);
+        Some(item)
     }
     /// Returns an iterator for all view ports.
     pub fn view_ports(&self) -> impl Iterator<Item = &ViewPort> {
@@ -371,7 +492,9 @@ impl Drawing {
     }
     /// Removes the specified `ViewPort` from the `Drawing`.
     pub fn remove_view_port(&mut self, index: usize) -> Option<ViewPort> {
-        Drawing::remove_item(&mut self.__view_ports, index)
+        let item = Drawing::remove_item(&mut self.__view_ports, index)?;
+        self.reindex_handles(ItemCollection::ViewPorts, index);
+        Some(item)
     }
     /// Returns an iterator for all blocks.
     pub fn blocks(&self) -> impl Iterator<Item = &Block> {
@@ -384,11 +507,19 @@ impl Drawing {
     /// Add a block to the `Drawing`.
     pub fn add_block(&mut self, mut block: Block) -> &Block {
         block.handle = self.next_handle();
+        for entity in block.entities.iter_mut() {
+            if entity.common.handle.is_empty() {
+                entity.common.handle = self.next_handle();
+            }
+            self.assign_child_handles(entity);
+        }
         self.add_block_no_handle_set(block)
     }
     /// Removes the specified `Block` from the `Drawing`.
     pub fn remove_block(&mut self, index: usize) -> Option<Block> {
-        Drawing::remove_item(&mut self.__blocks, index)
+        let item = Drawing::remove_item(&mut self.__blocks, index)?;
+        self.reindex_handles(ItemCollection::Blocks, index);
+        Some(item)
     }
     /// Returns an iterator for all contained entities.
     pub fn entities(&self) -> impl Iterator<Item = &Entity> {
@@ -400,33 +531,16 @@ impl Drawing {
     }
     /// Adds an entity to the `Drawing`.
     pub fn add_entity(&mut self, mut entity: Entity) -> &Entity {
-        entity.common.handle = self.next_handle();
-
-        // set child handles
-        match entity.specific {
-            EntityType::Insert(ref mut ins) => {
-                for a in ins.__attributes_and_handles.iter_mut() {
-                    if a.1 == AUTO_REPLACE_HANDLE {
-                        a.1 = self.next_handle();
-                    }
-                }
-            }
-            EntityType::Polyline(ref mut poly) => {
-                for v in poly.__vertices_and_handles.iter_mut() {
-                    if v.1 == AUTO_REPLACE_HANDLE {
-                        v.1 = self.next_handle();
-                    }
-                }
-            }
-            _ => (),
-        }
+        self.assign_entity_handles(&mut entity);
 
         // ensure invariants
         self.add_entity_no_handle_set(entity)
     }
     /// Removes the specified `Entity` from the `Drawing`.
     pub fn remove_entity(&mut self, index: usize) -> Option<Entity> {
-        Drawing::remove_item(&mut self.__entities, index)
+        let item = Drawing::remove_item(&mut self.__entities, index)?;
+        self.reindex_handles(ItemCollection::Entities, index);
+        Some(item)
     }
     /// Returns an iterator for all contained objects.
     pub fn objects(&self) -> impl Iterator<Item = &Object> {
@@ -447,7 +561,16 @@ impl Drawing {
     }
     /// Removes the specified `Object` from the `Drawing`.
     pub fn remove_object(&mut self, index: usize) -> Option<Object> {
-        Drawing::remove_item(&mut self.__objects, index)
+        let item = Drawing::remove_item(&mut self.__objects, index)?;
+        self.reindex_handles(ItemCollection::Objects, index);
+        Some(item)
+    }
+    /// Returns the parsed contents of the `ACDSDATA` section, if present.
+    pub fn acds_data(&self) -> DxfResult<Option<AcDsData>> {
+        match self.extra_sections.iter().find(|s| s.name == "ACDSDATA") {
+            Some(section) => Ok(Some(AcDsData::from_section(section)?)),
+            None => Ok(None),
+        }
     }
     /// Clears all items from the `Drawing`.
     pub fn clear(&mut self) {
@@ -465,12 +588,14 @@ impl Drawing {
         self.__entities.clear();
         self.__objects.clear();
         self.thumbnail = None;
+        self.extra_sections.clear();
+        self.__handle_index.clear();
 
         self.header.next_available_handle = Handle(1);
     }
     /// Normalizes the `Drawing` by ensuring expected items are present.
     pub fn normalize(&mut self) {
-        // TODO: check for duplicates
+        // duplicates are found by `audit`
         self.header.normalize();
         self.normalize_blocks();
         self.normalize_entities();
@@ -497,136 +622,384 @@ impl Drawing {
         self.__ucss.sort_by(|a, b| a.name.cmp(&b.name));
         self.__views.sort_by(|a, b| a.name.cmp(&b.name));
         self.__view_ports.sort_by(|a, b| a.name.cmp(&b.name));
+        self.rebuild_handle_index();
+    }
+    /// Checks the `Drawing` for problems similar to those found by AutoCAD's `AUDIT` command:
+    /// duplicate handles, handles at or above `$HANDSEED`, pointers to items that don't exist,
+    /// entities and blocks referring to missing layers, line types, text styles, and dimension
+    /// styles, blocks without a `BlockRecord`, and table entries with the same name.  Names are
+    /// compared ignoring case.  Nothing is changed; see `audit_and_fix`.
+    pub fn audit(&self) -> AuditReport {
+        AuditReport {
+            problems: self
+                .find_audit_problems()
+                .into_iter()
+                .map(|(_, p)| p)
+                .collect(),
+        }
     }
-    /// Gets a `DrawingItem` with the appropriate handle or `None`.
-    pub fn get_item_by_handle(&'_ self, handle: Handle) -> Option<DrawingItem<'_>> {
-        for item in &self.__app_ids {
-            if item.handle == handle {
-                return Some(DrawingItem::AppId(item));
-            }
+    /// Finds the problems described by `audit` and fixes them as described by each
+    /// `AuditProblemKind`.
+    pub fn audit_and_fix(&mut self) -> AuditReport {
+        let mut problems = self.find_audit_problems();
+
+        // raise the seed first so the handles assigned below are unique
+        let max_handle = problems
+            .iter()
+            .filter(|(_, p)| p.kind == AuditProblemKind::HandleNotBelowSeed)
+            .map(|(_, p)| p.handle)
+            .filter(|h| *h != AUTO_REPLACE_HANDLE)
+            .map(|h| h.0)
+            .max();
+        if let Some(max_handle) = max_handle {
+            self.header.next_available_handle = Handle(max_handle).next_handle_value();
         }
-        for item in &self.__blocks {
-            if item.handle == handle {
-                return Some(DrawingItem::Block(item));
+
+        let mut reassigned = HashSet::new();
+        for (location, problem) in problems.iter_mut() {
+            match problem.kind.clone() {
+                AuditProblemKind::DuplicateHandle | AuditProblemKind::HandleNotBelowSeed => {
+                    let needs_new_handle = problem.kind == AuditProblemKind::DuplicateHandle
+                        || problem.handle == AUTO_REPLACE_HANDLE;
+                    if needs_new_handle && reassigned.insert(*location) {
+                        let handle = self.next_handle();
+                        if let Some(mut item) = self.item_at_mut(*location) {
+                            item.set_handle(handle);
+                        }
+                    }
+                }
+                AuditProblemKind::DanglingPointer { name, pointer } => {
+                    self.clear_pointer(*location, &name, pointer)
+                }
+                AuditProblemKind::MissingLayer(name) => self.ensure_layer_is_present(&name),
+                AuditProblemKind::MissingLineType(name) => self.ensure_line_type_is_present(&name),
+                AuditProblemKind::MissingTextStyle(name) => {
+                    self.ensure_text_style_is_present(&name)
+                }
+                AuditProblemKind::MissingDimStyle(name) => {
+                    self.ensure_dimension_style_is_present(&name)
+                }
+                AuditProblemKind::MissingBlockRecord(name) => {
+                    self.ensure_block_record_is_present(&name)
+                }
+                AuditProblemKind::DuplicateTableName { name, .. } => {
+                    self.rename_table_entry(*location, &name)
+                }
             }
+            problem.fixed = true;
         }
-        for item in &self.__block_records {
-            if item.handle == handle {
-                return Some(DrawingItem::BlockRecord(item));
-            }
+
+        self.rebuild_handle_index();
+        AuditReport {
+            problems: problems.into_iter().map(|(_, p)| p).collect(),
         }
-        for item in &self.__dim_styles {
-            if item.handle == handle {
-                return Some(DrawingItem::DimStyle(item));
-            }
+    }
+    /// Converts the content of the `Drawing` to what the specified version supports and sets
+    /// `$ACADVER` to it.  Entities are converted as needed, e.g., an `LwPolyline` becomes a
+    /// `Polyline`, an `MText` becomes one `Text` per line, curves such as an `Ellipse` or `Spline`
+    /// become tessellated polylines, and true colors become the closest index colors before R2004.
+    /// Entities and objects that can't be converted are removed.  Before R13 the objects,
+    /// classes, block records, unsupported sections, and the pointers that refer to them are
+    /// removed as well.
+    pub fn convert_to(&mut self, version: AcadVersion) {
+        let entities = std::mem::take(&mut self.__entities);
+        let converted = entities
+            .iter()
+            .flat_map(|e| e.converted_to(version, self))
+            .collect::<Vec<_>>();
+        self.__entities = self.with_converted_handles(converted);
+        for i in 0..self.__blocks.len() {
+            let entities = std::mem::take(&mut self.__blocks[i].entities);
+            let converted = entities
+                .iter()
+                .flat_map(|e| e.converted_to(version, self))
+                .collect::<Vec<_>>();
+            self.__blocks[i].entities = self.with_converted_handles(converted);
         }
-        for item in &self.__entities {
-            if item.common.handle == handle {
-                return Some(DrawingItem::Entity(item));
+
+        if version < AcadVersion::R13 {
+            self.classes.clear();
+            self.__objects.clear();
+            self.__block_records.clear();
+            self.extra_sections.clear();
+            for layer in self.__layers.iter_mut() {
+                layer.__material_handle = Handle::empty();
+                layer.__plot_style_handle = Handle::empty();
             }
-        }
-        for item in &self.__layers {
-            if item.handle == handle {
-                return Some(DrawingItem::Layer(item));
+            for block in self.__blocks.iter_mut() {
+                block.__owner_handle = Handle::empty();
             }
-        }
-        for item in &self.__line_types {
-            if item.handle == handle {
-                return Some(DrawingItem::LineType(item));
+            let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+            for entity in self.__entities.iter_mut().chain(block_entities) {
+                entity.common.__owner_handle = Handle::empty();
+                entity.common.__material_handle = Handle::empty();
+                entity.common.__plot_style_handle = Handle::empty();
             }
+        } else {
+            self.__objects
+                .retain(|o| o.specific.is_supported_on_version(version));
         }
-        for item in &self.__objects {
-            if item.common.handle == handle {
-                return Some(DrawingItem::Object(item));
-            }
+
+        self.header.version = version;
+        self.rebuild_handle_index();
+    }
+    /// Removes the layers, line types, text styles, dimension styles, blocks and their block
+    /// records, and `MLineStyle` objects that nothing refers to, as selected by `options`.
+    /// References come from entities, blocks, inserts, dimensions, layers, dimension styles, line
+    /// types, objects, pointers, XData layer names, and the header's current values.  The removal
+    /// is repeated until nothing else becomes unreferenced, e.g., a layer that was only used inside
+    /// a purged block.  The layer `0`, the line types `BYLAYER`, `BYBLOCK`, and `CONTINUOUS`, the
+    /// `STANDARD` styles, and the model and paper space blocks are always kept.  Names are compared
+    /// ignoring case.
+    pub fn purge(&mut self, options: PurgeOptions) -> PurgeReport {
+        let mut report = PurgeReport::default();
+        while self.purge_unreferenced(&options, &mut report) {}
+        self.rebuild_handle_index();
+        report
+    }
+    /// Renames the layer and updates everything that refers to it: entities, blocks, layer
+    /// filters and indexes, `$CLAYER`, and XData layer names.  Names are compared ignoring case.
+    /// Returns `false`, changing nothing, if there's no such layer, it's the layer `0`, or another
+    /// layer already has the new name.
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !Drawing::can_rename(self.layers().map(|l| &l.name), &["0"], old_name, new_name) {
+            return false;
         }
-        for item in &self.__styles {
-            if item.handle == handle {
-                return Some(DrawingItem::Style(item));
-            }
+
+        let rename = |name: &mut String| Drawing::rename_reference(name, old_name, new_name);
+        for layer in self.__layers.iter_mut() {
+            rename(&mut layer.name);
         }
-        for item in &self.__ucss {
-            if item.handle == handle {
-                return Some(DrawingItem::Ucs(item));
-            }
+        rename(&mut self.header.current_layer);
+        for block in self.__blocks.iter_mut() {
+            rename(&mut block.layer);
         }
-        for item in &self.__views {
-            if item.handle == handle {
-                return Some(DrawingItem::View(item));
-            }
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(block_entities) {
+            rename(&mut entity.common.layer);
         }
-        for item in &self.__view_ports {
-            if item.handle == handle {
-                return Some(DrawingItem::ViewPort(item));
+        for obj in self.__objects.iter_mut() {
+            match obj.specific {
+                ObjectType::LayerFilter(ref mut l) => l.layer_names.iter_mut().for_each(rename),
+                ObjectType::LayerIndex(ref mut l) => l.layer_names.iter_mut().for_each(rename),
+                _ => (),
             }
         }
+        for x_data in self.x_data_mut() {
+            Drawing::rename_x_data_layers(&mut x_data.items, &rename);
+        }
+        true
+    }
+    /// Renames the line type and updates everything that refers to it: entities, layers,
+    /// `MLineStyle` elements, and the header's current values such as `$CELTYPE`.  Names are
+    /// compared ignoring case.  Returns `false`, changing nothing, if there's no such line type,
+    /// it's `CONTINUOUS`, `BYLAYER`, or `BYBLOCK`, or another line type already has the new name.
+    pub fn rename_line_type(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !Drawing::can_rename(
+            self.line_types().map(|l| &l.name),
+            &["continuous", "bylayer", "byblock"],
+            old_name,
+            new_name,
+        ) {
+            return false;
+        }
 
-        None
-    }
-    /// Gets a `DrawingItemMut` with the appropriate handle or `None`.
-    pub fn get_item_by_handle_mut(&'_ mut self, handle: Handle) -> Option<DrawingItemMut<'_>> {
-        for item in &mut self.__app_ids {
-            if item.handle == handle {
-                return Some(DrawingItemMut::AppId(item));
-            }
+        let rename = |name: &mut String| Drawing::rename_reference(name, old_name, new_name);
+        for line_type in self.__line_types.iter_mut() {
+            rename(&mut line_type.name);
         }
-        for item in &mut self.__blocks {
-            if item.handle == handle {
-                return Some(DrawingItemMut::Block(item));
-            }
+        rename(&mut self.header.current_entity_line_type);
+        rename(&mut self.header.dimension_line_type);
+        rename(&mut self.header.dimension_first_extension_line_type);
+        rename(&mut self.header.dimension_second_extension_line_type);
+        for layer in self.__layers.iter_mut() {
+            rename(&mut layer.line_type_name);
         }
-        for item in &mut self.__block_records {
-            if item.handle == handle {
-                return Some(DrawingItemMut::BlockRecord(item));
-            }
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(block_entities) {
+            rename(&mut entity.common.line_type_name);
         }
-        for item in &mut self.__dim_styles {
-            if item.handle == handle {
-                return Some(DrawingItemMut::DimStyle(item));
+        for obj in self.__objects.iter_mut() {
+            if let ObjectType::MLineStyle(ref mut style) = obj.specific {
+                for element in style.elements.iter_mut() {
+                    rename(&mut element.line_type);
+                }
             }
         }
-        for item in &mut self.__entities {
-            if item.common.handle == handle {
-                return Some(DrawingItemMut::Entity(item));
-            }
+        true
+    }
+    /// Renames the text style and updates everything that refers to it: text entities,
+    /// attributes, dimension styles, `$TEXTSTYLE`, and `$DIMTXSTY`.  Names are compared ignoring
+    /// case.  Returns `false`, changing nothing, if there's no such style or another style
+    /// already has the new name.
+    pub fn rename_text_style(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !Drawing::can_rename(self.styles().map(|s| &s.name), &[], old_name, new_name) {
+            return false;
         }
-        for item in &mut self.__layers {
-            if item.handle == handle {
-                return Some(DrawingItemMut::Layer(item));
-            }
+
+        let rename = |name: &mut String| Drawing::rename_reference(name, old_name, new_name);
+        for style in self.__styles.iter_mut() {
+            rename(&mut style.name);
         }
-        for item in &mut self.__line_types {
-            if item.handle == handle {
-                return Some(DrawingItemMut::LineType(item));
-            }
+        rename(&mut self.header.text_style);
+        rename(&mut self.header.dimension_text_style);
+        for dim_style in self.__dim_styles.iter_mut() {
+            rename(&mut dim_style.dimension_text_style);
         }
-        for item in &mut self.__objects {
-            if item.common.handle == handle {
-                return Some(DrawingItemMut::Object(item));
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(block_entities) {
+            if let Some(name) = Drawing::text_style_name_mut(entity) {
+                rename(name);
             }
-        }
-        for item in &mut self.__styles {
-            if item.handle == handle {
-                return Some(DrawingItemMut::Style(item));
+            if let EntityType::Insert(ref mut ins) = entity.specific {
+                for att in ins.attributes_mut() {
+                    rename(&mut att.text_style_name);
+                }
             }
         }
-        for item in &mut self.__ucss {
-            if item.handle == handle {
-                return Some(DrawingItemMut::Ucs(item));
-            }
+        true
+    }
+    /// Renames the dimension style and updates everything that refers to it: dimensions,
+    /// leaders, tolerances, and `$DIMSTYLE`.  Names are compared ignoring case.  Returns `false`,
+    /// changing nothing, if there's no such dimension style or another one already has the new
+    /// name.
+    pub fn rename_dim_style(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !Drawing::can_rename(self.dim_styles().map(|d| &d.name), &[], old_name, new_name) {
+            return false;
+        }
+
+        let rename = |name: &mut String| Drawing::rename_reference(name, old_name, new_name);
+        for dim_style in self.__dim_styles.iter_mut() {
+            rename(&mut dim_style.name);
         }
-        for item in &mut self.__views {
-            if item.handle == handle {
-                return Some(DrawingItemMut::View(item));
+        rename(&mut self.header.dimension_style_name);
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(block_entities) {
+            if let Some(name) = Drawing::dimension_style_name_mut(entity) {
+                rename(name);
             }
         }
-        for item in &mut self.__view_ports {
-            if item.handle == handle {
-                return Some(DrawingItemMut::ViewPort(item));
+        true
+    }
+    /// Renames the block and its `BlockRecord` and updates everything that refers to it: inserts,
+    /// dimensions, tables, and the arrow blocks of the dimension styles and the header.  Names
+    /// are compared ignoring case.  Returns `false`, changing nothing, if there's no such block,
+    /// it's `*Model_Space` or `*Paper_Space`, or another block already has the new name.
+    pub fn rename_block(&mut self, old_name: &str, new_name: &str) -> bool {
+        if !Drawing::can_rename(
+            self.blocks().map(|b| &b.name),
+            &["*model_space", "*paper_space"],
+            old_name,
+            new_name,
+        ) {
+            return false;
+        }
+
+        let rename = |name: &mut String| Drawing::rename_reference(name, old_name, new_name);
+        for block in self.__blocks.iter_mut() {
+            rename(&mut block.name);
+        }
+        for block_record in self.__block_records.iter_mut() {
+            rename(&mut block_record.name);
+        }
+        rename(&mut self.header.arrow_block_name);
+        rename(&mut self.header.first_arrow_block_name);
+        rename(&mut self.header.second_arrow_block_name);
+        rename(&mut self.header.dimension_leader_block_name);
+        for dim_style in self.__dim_styles.iter_mut() {
+            rename(&mut dim_style.arrow_block_name);
+            rename(&mut dim_style.first_arrow_block_name);
+            rename(&mut dim_style.second_arrow_block_name);
+            rename(&mut dim_style.dimension_leader_block_name);
+        }
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(block_entities) {
+            if let Some(name) = Drawing::block_name_mut(entity) {
+                rename(name);
             }
         }
+        true
+    }
+    /// Computes the extents of the model space entities and stores them in the header's
+    /// `$EXTMIN` and `$EXTMAX` values.  If no entity has extents the header is left unchanged and
+    /// `None` is returned.
+    pub fn compute_extents(&mut self) -> Option<BoundingBox> {
+        let extents = self
+            .entities()
+            .filter(|e| !e.common.is_in_paper_space)
+            .filter_map(|e| e.bounding_box(self))
+            .reduce(|a, b| a.union(&b))?;
+        self.header.minimum_drawing_extents = extents.min.clone();
+        self.header.maximum_drawing_extents = extents.max.clone();
+        Some(extents)
+    }
+    /// Returns the entities of the block referenced by the specified `Insert` entity, transformed
+    /// to where the insert places them, or `None` if the entity isn't an `Insert` or its block
+    /// doesn't exist.
+    ///
+    /// Every cell of the insert's row/column array gets its own copies.  BYBLOCK colors, line
+    /// types, and line weights and entities on layer `0` take the values of the insert.  Nested
+    /// inserts are exploded as well.  The visible attributes of the insert and the visible
+    /// constant attribute definitions of the block become `Text` entities.  The returned entities
+    /// don't have handles; `add_entity` assigns them.
+    pub fn explode_insert(&self, insert: &Entity) -> Option<Vec<Entity>> {
+        let specific = match insert.specific {
+            EntityType::Insert(ref ins) => ins,
+            _ => return None,
+        };
+        if !self.blocks().any(|b| b.name == specific.name) {
+            return None;
+        }
 
-        None
+        let mut entities = vec![];
+        self.explode_insert_into(
+            &insert.common,
+            specific,
+            &TransformationMatrix::identity(),
+            0,
+            &mut entities,
+        );
+        Some(entities)
+    }
+    /// Replaces every `Insert` entity in model and paper space with the entities of its block as
+    /// described by `explode_insert`.  Inserts of blocks that don't exist are kept.
+    pub fn flatten_inserts(&mut self) {
+        let entities = std::mem::take(&mut self.__entities);
+        for entity in entities {
+            match self.explode_insert(&entity) {
+                Some(exploded) => {
+                    for e in exploded {
+                        self.add_entity(e);
+                    }
+                }
+                None => self.__entities.push(entity),
+            }
+        }
+        self.rebuild_handle_index();
+    }
+    /// Gets a `DrawingItem` with the appropriate handle or `None`.  This includes the entities of
+    /// blocks, the vertices of polylines, and the attributes of inserts.
+    pub fn get_item_by_handle(&'_ self, handle: Handle) -> Option<DrawingItem<'_>> {
+        let location = match self.indexed_location(handle) {
+            Some(location) => location,
+            None => self.find_location(handle)?,
+        };
+        self.item_at(location)
+    }
+    /// Gets a `DrawingItemMut` with the appropriate handle or `None`.  This includes the entities
+    /// of blocks, the vertices of polylines, and the attributes of inserts.
+    pub fn get_item_by_handle_mut(&'_ mut self, handle: Handle) -> Option<DrawingItemMut<'_>> {
+        let location = match self.indexed_location(handle) {
+            Some(location) => location,
+            None => {
+                // the index is missing or out of date, e.g., after deserializing or changing a
+                // handle through a mutable iterator
+                let location = self.find_location(handle)?;
+                self.__handle_index.insert(handle, location);
+                location
+            }
+        };
+        self.item_at_mut(location)
     }
     pub(crate) fn assign_and_get_handle(&mut self, item: &mut DrawingItemMut) -> Handle {
         if item.get_handle().is_empty() {
@@ -641,6 +1014,132 @@ impl Drawing {
 
 // private implementation
 impl Drawing {
+    pub(crate) fn assign_entity_handles(&mut self, entity: &mut Entity) {
+        entity.common.handle = self.next_handle();
+        self.assign_child_handles(entity);
+    }
+    pub(crate) fn assign_child_handles(&mut self, entity: &mut Entity) {
+        match entity.specific {
+            EntityType::Insert(ref mut ins) => {
+                for a in ins.__attributes_and_handles.iter_mut() {
+                    if a.1 == AUTO_REPLACE_HANDLE {
+                        a.1 = self.next_handle();
+                    }
+                }
+            }
+            EntityType::Polyline(ref mut poly) => {
+                for v in poly.__vertices_and_handles.iter_mut() {
+                    if v.1 == AUTO_REPLACE_HANDLE {
+                        v.1 = self.next_handle();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    /// Assigns handles to converted entities and their children that don't have them yet.
+    fn with_converted_handles(&mut self, mut entities: Vec<Entity>) -> Vec<Entity> {
+        for entity in entities.iter_mut() {
+            if entity.common.handle.is_empty() {
+                entity.common.handle = self.next_handle();
+            }
+            self.assign_child_handles(entity);
+        }
+        entities
+    }
+    fn explode_insert_into(
+        &self,
+        common: &EntityCommon,
+        insert: &Insert,
+        transform: &TransformationMatrix,
+        depth: usize,
+        entities: &mut Vec<Entity>,
+    ) {
+        let block = match self.blocks().find(|b| b.name == insert.name) {
+            Some(block) => block,
+            None => return,
+        };
+
+        // attributes are already placed in the insert's coordinate system
+        for att in insert.attributes().filter(|a| !a.get_is_invisible()) {
+            let mut text = Entity {
+                common: common.clone(),
+                specific: EntityType::Text(Text::from_attribute(att)),
+            };
+            text.transform(transform);
+            Drawing::reset_exploded_handles(&mut text);
+            entities.push(text);
+        }
+
+        let insert_to_world = *transform
+            * TransformationMatrix::from_ocs_normal(&insert.extrusion_direction)
+            * TransformationMatrix::translation(&insert.location.to_vector())
+            * TransformationMatrix::rotation(&Vector::z_axis(), insert.rotation.to_radians());
+        let block_to_cell = TransformationMatrix::scale(
+            insert.x_scale_factor,
+            insert.y_scale_factor,
+            insert.z_scale_factor,
+        ) * TransformationMatrix::translation(&-block.base_point.to_vector());
+        for row in 0..insert.row_count.max(1) {
+            for column in 0..insert.column_count.max(1) {
+                let cell_offset = Vector::new(
+                    f64::from(column) * insert.column_spacing,
+                    f64::from(row) * insert.row_spacing,
+                    0.0,
+                );
+                let cell_transform = insert_to_world
+                    * TransformationMatrix::translation(&cell_offset)
+                    * block_to_cell;
+                for block_entity in &block.entities {
+                    let mut entity = block_entity.clone();
+                    entity.common.resolve_by_block(common);
+                    match entity.specific {
+                        EntityType::Insert(ref nested)
+                            if depth + 1 < MAX_INSERT_DEPTH
+                                && self.blocks().any(|b| b.name == nested.name) =>
+                        {
+                            self.explode_insert_into(
+                                &entity.common,
+                                nested,
+                                &cell_transform,
+                                depth + 1,
+                                entities,
+                            );
+                            continue;
+                        }
+                        EntityType::AttributeDefinition(ref att) => {
+                            if !att.get_is_constant() || att.get_is_invisible() {
+                                continue;
+                            }
+                            entity.specific =
+                                EntityType::Text(Text::from_attribute_definition(att));
+                        }
+                        _ => (),
+                    }
+                    entity.transform(&cell_transform);
+                    Drawing::reset_exploded_handles(&mut entity);
+                    entities.push(entity);
+                }
+            }
+        }
+    }
+    fn reset_exploded_handles(entity: &mut Entity) {
+        entity.common.handle = Handle::empty();
+        entity.common.__owner_handle = Handle::empty();
+        match entity.specific {
+            EntityType::Insert(ref mut ins) => {
+                for a in ins.__attributes_and_handles.iter_mut() {
+                    a.1 = AUTO_REPLACE_HANDLE;
+                }
+            }
+            EntityType::Polyline(ref mut poly) => {
+                for v in poly.__vertices_and_handles.iter_mut() {
+                    v.1 = AUTO_REPLACE_HANDLE;
+                }
+            }
+            _ => (),
+        }
+    }
     pub(crate) fn next_handle(&mut self) -> Handle {
         let result = self.header.next_available_handle;
         self.header.next_available_handle = self.header.next_available_handle.next_handle_value();
@@ -653,75 +1152,932 @@ impl Drawing {
             None
         }
     }
-    pub(crate) fn add_block_no_handle_set(&mut self, block: Block) -> &Block {
-        self.ensure_layer_is_present_for_block(&block);
-        self.ensure_line_type_is_present_for_block(&block);
-        self.__blocks.push(block);
-        self.__blocks.last().unwrap()
+    /// The location of the item with the specified handle if the index has a valid entry for it.
+    fn indexed_location(&self, handle: Handle) -> Option<ItemLocation> {
+        if handle.is_empty() {
+            return None;
+        }
+        let location = *self.__handle_index.get(&handle)?;
+        match self.item_at(location) {
+            Some(item) if item.get_handle() == handle => Some(location),
+            _ => None,
+        }
     }
-    fn add_entity_no_handle_set(&mut self, entity: Entity) -> &Entity {
-        self.ensure_mline_style_is_present_for_entity(&entity);
-        self.ensure_dimension_style_is_present_for_entity(&entity);
-        self.ensure_layer_is_present(&entity.common.layer);
-        self.ensure_line_type_is_present(&entity.common.line_type_name);
-        self.ensure_text_style_is_present_for_entity(&entity);
-        self.__entities.push(entity);
-        self.__entities.last().unwrap()
+    /// Searches every collection for the item with the specified handle.
+    fn find_location(&self, handle: Handle) -> Option<ItemLocation> {
+        if handle.is_empty() || handle == AUTO_REPLACE_HANDLE {
+            return None;
+        }
+        let mut found = None;
+        for collection in ItemCollection::ALL.iter() {
+            self.visit_locations(*collection, 0, &mut |h, location| {
+                if found.is_none() && h == handle {
+                    found = Some(location);
+                }
+            });
+            if found.is_some() {
+                break;
+            }
+        }
+        found
     }
-    fn add_object_no_handle_set(&mut self, obj: Object) -> &Object {
-        self.ensure_layer_is_present_for_object(&obj);
-        self.ensure_line_type_is_present_for_object(&obj);
-        self.ensure_text_style_is_present_for_object(&obj);
-        self.ensure_view_is_present(&obj);
-        self.__objects.push(obj);
-        self.__objects.last().unwrap()
+    fn rebuild_handle_index(&mut self) {
+        self.__handle_index.clear();
+        for collection in ItemCollection::ALL.iter() {
+            self.index_handles(*collection, 0);
+        }
     }
-    pub(crate) fn add_app_id_no_handle_set(&mut self, app_id: AppId) -> &AppId {
-        // TODO: ensure invariants
-        self.__app_ids.push(app_id);
-        self.__app_ids.last().unwrap()
+    /// Adds the handles of the items in the collection from `start` on to the index.
+    fn index_handles(&mut self, collection: ItemCollection, start: usize) {
+        let mut index = std::mem::take(&mut self.__handle_index);
+        self.visit_locations(collection, start, &mut |handle, location| {
+            if !handle.is_empty() && handle != AUTO_REPLACE_HANDLE {
+                index.entry(handle).or_insert(location);
+            }
+        });
+        self.__handle_index = index;
     }
-    pub(crate) fn add_block_record_no_handle_set(
-        &mut self,
-        block_record: BlockRecord,
-    ) -> &BlockRecord {
-        // TODO: ensure invariants
-        self.__block_records.push(block_record);
-        self.__block_records.last().unwrap()
+    /// Updates the index after the item at `start` was removed from the collection.
+    fn reindex_handles(&mut self, collection: ItemCollection, start: usize) {
+        self.__handle_index.retain(|_, location| {
+            let (c, i) = location.position();
+            c != collection || i < start
+        });
+        self.index_handles(collection, start);
     }
-    pub(crate) fn add_dim_style_no_handle_set(&mut self, dim_style: DimStyle) -> &DimStyle {
-        // TODO: ensure invariants
-        self.__dim_styles.push(dim_style);
-        self.__dim_styles.last().unwrap()
+    fn visit_locations(
+        &self,
+        collection: ItemCollection,
+        start: usize,
+        f: &mut dyn FnMut(Handle, ItemLocation),
+    ) {
+        fn visit_entity(
+            entity: &Entity,
+            location: EntityLocation,
+            f: &mut dyn FnMut(Handle, ItemLocation),
+        ) {
+            f(entity.common.handle, ItemLocation::Entity(location));
+            match entity.specific {
+                EntityType::Insert(ref ins) => {
+                    for (i, (_, handle)) in ins.__attributes_and_handles.iter().enumerate() {
+                        f(*handle, ItemLocation::Attribute(location, i));
+                    }
+                }
+                EntityType::Polyline(ref poly) => {
+                    for (i, (_, handle)) in poly.__vertices_and_handles.iter().enumerate() {
+                        f(*handle, ItemLocation::Vertex(location, i));
+                    }
+                }
+                _ => (),
+            }
+        }
+        macro_rules! visit_table {
+            ($items:expr, $location:path) => {
+                for (i, item) in $items.iter().enumerate().skip(start) {
+                    f(item.handle, $location(i));
+                }
+            };
+        }
+
+        match collection {
+            ItemCollection::AppIds => visit_table!(self.__app_ids, ItemLocation::AppId),
+            ItemCollection::Blocks => {
+                for (b, block) in self.__blocks.iter().enumerate().skip(start) {
+                    f(block.handle, ItemLocation::Block(b));
+                    for (i, entity) in block.entities.iter().enumerate() {
+                        let location = EntityLocation {
+                            block: Some(b),
+                            index: i,
+                        };
+                        visit_entity(entity, location, f);
+                    }
+                }
+            }
+            ItemCollection::BlockRecords => {
+                visit_table!(self.__block_records, ItemLocation::BlockRecord)
+            }
+            ItemCollection::DimStyles => visit_table!(self.__dim_styles, ItemLocation::DimStyle),
+            ItemCollection::Entities => {
+                for (i, entity) in self.__entities.iter().enumerate().skip(start) {
+                    let location = EntityLocation {
+                        block: None,
+                        index: i,
+                    };
+                    visit_entity(entity, location, f);
+                }
+            }
+            ItemCollection::Layers => visit_table!(self.__layers, ItemLocation::Layer),
+            ItemCollection::LineTypes => visit_table!(self.__line_types, ItemLocation::LineType),
+            ItemCollection::Objects => {
+                for (i, obj) in self.__objects.iter().enumerate().skip(start) {
+                    f(obj.common.handle, ItemLocation::Object(i));
+                }
+            }
+            ItemCollection::Styles => visit_table!(self.__styles, ItemLocation::Style),
+            ItemCollection::Ucss => visit_table!(self.__ucss, ItemLocation::Ucs),
+            ItemCollection::Views => visit_table!(self.__views, ItemLocation::View),
+            ItemCollection::ViewPorts => visit_table!(self.__view_ports, ItemLocation::ViewPort),
+        }
     }
-    pub(crate) fn add_layer_no_handle_set(&mut self, layer: Layer) -> &Layer {
-        self.ensure_line_type_is_present(&layer.line_type_name);
-        self.__layers.push(layer);
-        self.__layers.last().unwrap()
+    fn entity_at(&self, location: EntityLocation) -> Option<&Entity> {
+        match location.block {
+            Some(block) => self.__blocks.get(block)?.entities.get(location.index),
+            None => self.__entities.get(location.index),
+        }
     }
-    pub(crate) fn add_line_type_no_handle_set(&mut self, line_type: LineType) -> &LineType {
-        // TODO: ensure invariants
-        self.__line_types.push(line_type);
-        self.__line_types.last().unwrap()
+    fn entity_at_mut(&mut self, location: EntityLocation) -> Option<&mut Entity> {
+        match location.block {
+            Some(block) => self
+                .__blocks
+                .get_mut(block)?
+                .entities
+                .get_mut(location.index),
+            None => self.__entities.get_mut(location.index),
+        }
     }
-    pub(crate) fn add_style_no_handle_set(&mut self, style: Style) -> &Style {
-        // TODO: ensure invariants
-        self.__styles.push(style);
-        self.__styles.last().unwrap()
+    fn item_at(&self, location: ItemLocation) -> Option<DrawingItem<'_>> {
+        match location {
+            ItemLocation::AppId(i) => self.__app_ids.get(i).map(DrawingItem::AppId),
+            ItemLocation::Attribute(entity, i) => match self.entity_at(entity)?.specific {
+                EntityType::Insert(ref ins) => ins
+                    .__attributes_and_handles
+                    .get(i)
+                    .map(|(a, handle)| DrawingItem::Attribute(a, *handle)),
+                _ => None,
+            },
+            ItemLocation::Block(i) => self.__blocks.get(i).map(DrawingItem::Block),
+            ItemLocation::BlockRecord(i) => {
+                self.__block_records.get(i).map(DrawingItem::BlockRecord)
+            }
+            ItemLocation::DimStyle(i) => self.__dim_styles.get(i).map(DrawingItem::DimStyle),
+            ItemLocation::Entity(entity) => self.entity_at(entity).map(DrawingItem::Entity),
+            ItemLocation::Layer(i) => self.__layers.get(i).map(DrawingItem::Layer),
+            ItemLocation::LineType(i) => self.__line_types.get(i).map(DrawingItem::LineType),
+            ItemLocation::Object(i) => self.__objects.get(i).map(DrawingItem::Object),
+            ItemLocation::Style(i) => self.__styles.get(i).map(DrawingItem::Style),
+            ItemLocation::Ucs(i) => self.__ucss.get(i).map(DrawingItem::Ucs),
+            ItemLocation::Vertex(entity, i) => match self.entity_at(entity)?.specific {
+                EntityType::Polyline(ref poly) => poly
+                    .__vertices_and_handles
+                    .get(i)
+                    .map(|(v, handle)| DrawingItem::Vertex(v, *handle)),
+                _ => None,
+            },
+            ItemLocation::View(i) => self.__views.get(i).map(DrawingItem::View),
+            ItemLocation::ViewPort(i) => self.__view_ports.get(i).map(DrawingItem::ViewPort),
+        }
     }
-    pub(crate) fn add_ucs_no_handle_set(&mut self, ucs: Ucs) -> &Ucs {
-        // TODO: ensure invariants
-        self.__ucss.push(ucs);
-        self.__ucss.last().unwrap()
+    fn item_at_mut(&mut self, location: ItemLocation) -> Option<DrawingItemMut<'_>> {
+        match location {
+            ItemLocation::AppId(i) => self.__app_ids.get_mut(i).map(DrawingItemMut::AppId),
+            ItemLocation::Attribute(entity, i) => match self.entity_at_mut(entity)?.specific {
+                EntityType::Insert(ref mut ins) => ins
+                    .__attributes_and_handles
+                    .get_mut(i)
+                    .map(|(a, handle)| DrawingItemMut::Attribute(a, handle)),
+                _ => None,
+            },
+            ItemLocation::Block(i) => self.__blocks.get_mut(i).map(DrawingItemMut::Block),
+            ItemLocation::BlockRecord(i) => self
+                .__block_records
+                .get_mut(i)
+                .map(DrawingItemMut::BlockRecord),
+            ItemLocation::DimStyle(i) => self.__dim_styles.get_mut(i).map(DrawingItemMut::DimStyle),
+            ItemLocation::Entity(entity) => self.entity_at_mut(entity).map(DrawingItemMut::Entity),
+            ItemLocation::Layer(i) => self.__layers.get_mut(i).map(DrawingItemMut::Layer),
+            ItemLocation::LineType(i) => self.__line_types.get_mut(i).map(DrawingItemMut::LineType),
+            ItemLocation::Object(i) => self.__objects.get_mut(i).map(DrawingItemMut::Object),
+            ItemLocation::Style(i) => self.__styles.get_mut(i).map(DrawingItemMut::Style),
+            ItemLocation::Ucs(i) => self.__ucss.get_mut(i).map(DrawingItemMut::Ucs),
+            ItemLocation::Vertex(entity, i) => match self.entity_at_mut(entity)?.specific {
+                EntityType::Polyline(ref mut poly) => poly
+                    .__vertices_and_handles
+                    .get_mut(i)
+                    .map(|(v, handle)| DrawingItemMut::Vertex(v, handle)),
+                _ => None,
+            },
+            ItemLocation::View(i) => self.__views.get_mut(i).map(DrawingItemMut::View),
+            ItemLocation::ViewPort(i) => self.__view_ports.get_mut(i).map(DrawingItemMut::ViewPort),
+        }
+    }
+    /// Returns `true` if `old_name` is one of the names and `new_name` isn't, other than by
+    /// changing the case of `old_name`.  The lowercase `mandatory` names can't be renamed.
+    fn can_rename<'a>(
+        names: impl Iterator<Item = &'a String>,
+        mandatory: &[&str],
+        old_name: &str,
+        new_name: &str,
+    ) -> bool {
+        let old_name = old_name.to_lowercase();
+        let new_name = new_name.to_lowercase();
+        let names = names.map(|n| n.to_lowercase()).collect::<HashSet<_>>();
+        !new_name.is_empty()
+            && !mandatory.contains(&old_name.as_str())
+            && names.contains(&old_name)
+            && (new_name == old_name || !names.contains(&new_name))
+    }
+    fn rename_reference(name: &mut String, old_name: &str, new_name: &str) {
+        if name.to_lowercase() == old_name.to_lowercase() {
+            *name = String::from(new_name);
+        }
+    }
+    fn x_data_layers<'a>(items: &'a [XDataItem], layers: &mut Vec<&'a String>) {
+        for item in items {
+            match item {
+                XDataItem::LayerName(ref name) => layers.push(name),
+                XDataItem::ControlGroup(ref items) => Drawing::x_data_layers(items, layers),
+                _ => (),
+            }
+        }
+    }
+    fn rename_x_data_layers(items: &mut [XDataItem], rename: &impl Fn(&mut String)) {
+        for item in items.iter_mut() {
+            match item {
+                XDataItem::LayerName(ref mut name) => rename(name),
+                XDataItem::ControlGroup(ref mut items) => {
+                    Drawing::rename_x_data_layers(items, rename)
+                }
+                _ => (),
+            }
+        }
+    }
+    /// Returns the XData of every entity, block, table entry, and object.
+    fn x_data(&self) -> impl Iterator<Item = &XData> {
+        let block_entities = self.__blocks.iter().flat_map(|b| b.entities.iter());
+        let entity_x_data = self
+            .__entities
+            .iter()
+            .chain(block_entities)
+            .flat_map(|e| e.common.x_data.iter());
+        entity_x_data
+            .chain(self.__app_ids.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__block_records.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__dim_styles.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__layers.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__line_types.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__styles.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__ucss.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__views.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__view_ports.iter().flat_map(|t| t.x_data.iter()))
+            .chain(self.__objects.iter().flat_map(|o| o.common.x_data.iter()))
+    }
+    /// Returns the XData of every entity, block, table entry, and object.
+    fn x_data_mut(&mut self) -> impl Iterator<Item = &mut XData> {
+        let block_entities = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        let entity_x_data = self
+            .__entities
+            .iter_mut()
+            .chain(block_entities)
+            .flat_map(|e| e.common.x_data.iter_mut());
+        entity_x_data
+            .chain(self.__app_ids.iter_mut().flat_map(|t| t.x_data.iter_mut()))
+            .chain(
+                self.__block_records
+                    .iter_mut()
+                    .flat_map(|t| t.x_data.iter_mut()),
+            )
+            .chain(
+                self.__dim_styles
+                    .iter_mut()
+                    .flat_map(|t| t.x_data.iter_mut()),
+            )
+            .chain(self.__layers.iter_mut().flat_map(|t| t.x_data.iter_mut()))
+            .chain(
+                self.__line_types
+                    .iter_mut()
+                    .flat_map(|t| t.x_data.iter_mut()),
+            )
+            .chain(self.__styles.iter_mut().flat_map(|t| t.x_data.iter_mut()))
+            .chain(self.__ucss.iter_mut().flat_map(|t| t.x_data.iter_mut()))
+            .chain(self.__views.iter_mut().flat_map(|t| t.x_data.iter_mut()))
+            .chain(
+                self.__view_ports
+                    .iter_mut()
+                    .flat_map(|t| t.x_data.iter_mut()),
+            )
+            .chain(
+                self.__objects
+                    .iter_mut()
+                    .flat_map(|o| o.common.x_data.iter_mut()),
+            )
+    }
+    /// Removes one round of unreferenced items and returns `true` if anything was removed.
+    fn purge_unreferenced(&mut self, options: &PurgeOptions, report: &mut PurgeReport) -> bool {
+        let refs = self.purge_references();
+        let removed_count = report.count();
+        fn purge<T>(
+            items: &mut Vec<T>,
+            is_enabled: bool,
+            name: impl Fn(&T) -> &String,
+            is_referenced: impl Fn(&T) -> bool,
+            removed: &mut Vec<String>,
+        ) {
+            if is_enabled {
+                items.retain(|item| {
+                    let keep = is_referenced(item);
+                    if !keep {
+                        removed.push(name(item).clone());
+                    }
+                    keep
+                });
+            }
+        }
+
+        purge(
+            &mut self.__layers,
+            options.layers,
+            |l| &l.name,
+            |l| refs.layers.contains(&l.name.to_lowercase()) || refs.handles.contains(&l.handle),
+            &mut report.layers,
+        );
+        purge(
+            &mut self.__line_types,
+            options.line_types,
+            |l| &l.name,
+            |l| {
+                refs.line_types.contains(&l.name.to_lowercase()) || refs.handles.contains(&l.handle)
+            },
+            &mut report.line_types,
+        );
+        purge(
+            &mut self.__styles,
+            options.text_styles,
+            |s| &s.name,
+            |s| {
+                // shape files are loaded through styles without a name and `Shape` entities only
+                // refer to their shapes by name
+                refs.text_styles.contains(&s.name.to_lowercase())
+                    || refs.handles.contains(&s.handle)
+                    || (refs.has_shapes && s.name.is_empty())
+            },
+            &mut report.text_styles,
+        );
+        purge(
+            &mut self.__dim_styles,
+            options.dim_styles,
+            |d| &d.name,
+            |d| {
+                refs.dim_styles.contains(&d.name.to_lowercase()) || refs.handles.contains(&d.handle)
+            },
+            &mut report.dim_styles,
+        );
+
+        let block_count = report.blocks.len();
+        purge(
+            &mut self.__blocks,
+            options.blocks,
+            |b| &b.name,
+            |b| {
+                let name = b.name.to_lowercase();
+                refs.blocks.contains(&name)
+                    || name.starts_with("*model_space")
+                    || name.starts_with("*paper_space")
+            },
+            &mut report.blocks,
+        );
+        let purged_blocks = report.blocks[block_count..]
+            .iter()
+            .map(|n| n.to_lowercase())
+            .collect::<HashSet<_>>();
+        self.__block_records
+            .retain(|b| !purged_blocks.contains(&b.name.to_lowercase()));
+
+        if options.mline_styles {
+            let mut purged_handles = HashSet::new();
+            self.__objects.retain(|o| match o.specific {
+                ObjectType::MLineStyle(ref style)
+                    if !refs.mline_styles.contains(&style.style_name.to_lowercase())
+                        && !refs.handles.contains(&o.common.handle) =>
+                {
+                    report.mline_styles.push(style.style_name.clone());
+                    purged_handles.insert(o.common.handle);
+                    false
+                }
+                _ => true,
+            });
+
+            // remove the dictionary entries of the purged styles
+            for o in self.__objects.iter_mut() {
+                match o.specific {
+                    ObjectType::Dictionary(ref mut d) => {
+                        d.value_handles.retain(|_, h| !purged_handles.contains(h))
+                    }
+                    ObjectType::DictionaryWithDefault(ref mut d) => {
+                        d.value_handles.retain(|_, h| !purged_handles.contains(h))
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        report.count() > removed_count
+    }
+    /// Gathers the lowercase names, and the handles, of everything that's referenced.
+    fn purge_references(&self) -> PurgeReferences {
+        let mut refs = PurgeReferences::default();
+        fn add(names: &mut HashSet<String>, name: &str) {
+            // an empty name doesn't refer to anything, so it can't keep an unnamed item alive
+            if !name.is_empty() {
+                names.insert(name.to_lowercase());
+            }
+        }
+
+        add(&mut refs.layers, "0");
+        add(&mut refs.layers, &self.header.current_layer);
+        for name in ["BYLAYER", "BYBLOCK", "CONTINUOUS"] {
+            add(&mut refs.line_types, name);
+        }
+        for name in [
+            &self.header.current_entity_line_type,
+            &self.header.dimension_line_type,
+            &self.header.dimension_first_extension_line_type,
+            &self.header.dimension_second_extension_line_type,
+        ] {
+            add(&mut refs.line_types, name);
+        }
+        add(&mut refs.text_styles, "STANDARD");
+        add(&mut refs.text_styles, &self.header.text_style);
+        add(&mut refs.text_styles, &self.header.dimension_text_style);
+        add(&mut refs.dim_styles, "STANDARD");
+        add(&mut refs.dim_styles, &self.header.dimension_style_name);
+        add(&mut refs.mline_styles, "STANDARD");
+        add(&mut refs.mline_styles, &self.header.current_multiline_style);
+        for name in [
+            &self.header.arrow_block_name,
+            &self.header.first_arrow_block_name,
+            &self.header.second_arrow_block_name,
+            &self.header.dimension_leader_block_name,
+        ] {
+            add(&mut refs.blocks, name);
+        }
+
+        for block in &self.__blocks {
+            add(&mut refs.layers, &block.layer);
+        }
+        let block_entities = self.__blocks.iter().flat_map(|b| b.entities.iter());
+        for entity in self.__entities.iter().chain(block_entities) {
+            add(&mut refs.layers, &entity.common.layer);
+            add(&mut refs.line_types, &entity.common.line_type_name);
+            if let Some(name) = Drawing::text_style_name(entity) {
+                add(&mut refs.text_styles, name);
+            }
+            if let Some(name) = Drawing::dimension_style_name(entity) {
+                add(&mut refs.dim_styles, name);
+            }
+            if let Some(name) = Drawing::block_name(entity) {
+                add(&mut refs.blocks, name);
+            }
+            match entity.specific {
+                EntityType::Insert(ref ins) => {
+                    for att in ins.attributes() {
+                        add(&mut refs.text_styles, &att.text_style_name);
+                    }
+                }
+                EntityType::Shape(_) => refs.has_shapes = true,
+                EntityType::MLine(ref ml) => {
+                    add(&mut refs.mline_styles, &ml.style_name);
+                    if !ml.__style_handle.is_empty() {
+                        refs.handles.insert(ml.__style_handle);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for layer in &self.__layers {
+            add(&mut refs.line_types, &layer.line_type_name);
+        }
+        for x_data in self.x_data() {
+            let mut layers = vec![];
+            Drawing::x_data_layers(&x_data.items, &mut layers);
+            for name in layers {
+                add(&mut refs.layers, name);
+            }
+        }
+        for dim_style in &self.__dim_styles {
+            add(&mut refs.text_styles, &dim_style.dimension_text_style);
+            for name in [
+                &dim_style.arrow_block_name,
+                &dim_style.first_arrow_block_name,
+                &dim_style.second_arrow_block_name,
+                &dim_style.dimension_leader_block_name,
+            ] {
+                add(&mut refs.blocks, name);
+            }
+        }
+        for line_type in &self.__line_types {
+            refs.handles
+                .extend(line_type.__styles_handle.iter().cloned());
+        }
+        let mut locations = vec![];
+        for collection in ItemCollection::ALL.iter() {
+            self.visit_locations(*collection, 0, &mut |_, location| locations.push(location));
+        }
+        for location in locations {
+            if let Some(item) = self.item_at(location) {
+                // owners and dictionary entries don't keep an item alive
+                refs.handles.extend(
+                    Drawing::pointers(&item)
+                        .into_iter()
+                        .filter(|(name, _)| *name != "owner" && !name.starts_with("dictionary"))
+                        .map(|(_, handle)| handle)
+                        .filter(|handle| !handle.is_empty()),
+                );
+            }
+        }
+        for block_record in &self.__block_records {
+            if refs.handles.contains(&block_record.handle) {
+                add(&mut refs.blocks, &block_record.name);
+            }
+        }
+        for obj in &self.__objects {
+            match obj.specific {
+                ObjectType::LayerFilter(ref l) => {
+                    for name in &l.layer_names {
+                        add(&mut refs.layers, name);
+                    }
+                }
+                ObjectType::LayerIndex(ref l) => {
+                    for name in &l.layer_names {
+                        add(&mut refs.layers, name);
+                    }
+                }
+                ObjectType::MLineStyle(ref style) => {
+                    for element in &style.elements {
+                        add(&mut refs.line_types, &element.line_type);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        refs
+    }
+    fn find_audit_problems(&self) -> Vec<(ItemLocation, AuditProblem)> {
+        let mut problems = vec![];
+        let mut add_problem = |location: ItemLocation, handle: Handle, kind: AuditProblemKind| {
+            problems.push((
+                location,
+                AuditProblem {
+                    handle,
+                    kind,
+                    fixed: false,
+                },
+            ))
+        };
+
+        let mut locations = vec![];
+        for collection in ItemCollection::ALL.iter() {
+            self.visit_locations(*collection, 0, &mut |handle, location| {
+                locations.push((handle, location))
+            });
+        }
+
+        // handles
+        let mut handles = HashSet::new();
+        for (handle, location) in &locations {
+            if handle.is_empty() {
+                continue;
+            }
+            if !handles.insert(*handle) {
+                add_problem(*location, *handle, AuditProblemKind::DuplicateHandle);
+            }
+            if handle.0 >= self.header.next_available_handle.0 {
+                add_problem(*location, *handle, AuditProblemKind::HandleNotBelowSeed);
+            }
+        }
+
+        // pointers and names
+        let lower_case_names = |names: &mut dyn Iterator<Item = &String>| -> HashSet<String> {
+            names.map(|n| n.to_lowercase()).collect()
+        };
+        let layers = lower_case_names(&mut self.layers().map(|l| &l.name));
+        let line_types = lower_case_names(&mut self.line_types().map(|l| &l.name));
+        let styles = lower_case_names(&mut self.styles().map(|s| &s.name));
+        let dim_styles = lower_case_names(&mut self.dim_styles().map(|d| &d.name));
+        let block_records = lower_case_names(&mut self.block_records().map(|b| &b.name));
+        let mut table_names = HashSet::new();
+        for (handle, location) in &locations {
+            let item = match self.item_at(*location) {
+                Some(item) => item,
+                None => continue,
+            };
+            for (name, pointer) in Drawing::pointers(&item) {
+                if !pointer.is_empty() && !handles.contains(&pointer) {
+                    let kind = AuditProblemKind::DanglingPointer {
+                        name: String::from(name),
+                        pointer,
+                    };
+                    add_problem(*location, *handle, kind);
+                }
+            }
+
+            match item {
+                DrawingItem::Block(block) => {
+                    if !layers.contains(&block.layer.to_lowercase()) {
+                        let kind = AuditProblemKind::MissingLayer(block.layer.clone());
+                        add_problem(*location, *handle, kind);
+                    }
+                    if self.header.version >= AcadVersion::R13
+                        && !block_records.contains(&block.name.to_lowercase())
+                    {
+                        let kind = AuditProblemKind::MissingBlockRecord(block.name.clone());
+                        add_problem(*location, *handle, kind);
+                    }
+                }
+                DrawingItem::Entity(entity) => {
+                    let references = [
+                        (
+                            &layers,
+                            Some(&entity.common.layer),
+                            AuditProblemKind::MissingLayer as fn(String) -> AuditProblemKind,
+                        ),
+                        (
+                            &line_types,
+                            Some(&entity.common.line_type_name),
+                            AuditProblemKind::MissingLineType,
+                        ),
+                        (
+                            &styles,
+                            Drawing::text_style_name(entity),
+                            AuditProblemKind::MissingTextStyle,
+                        ),
+                        (
+                            &dim_styles,
+                            Drawing::dimension_style_name(entity),
+                            AuditProblemKind::MissingDimStyle,
+                        ),
+                    ];
+                    for (names, name, kind) in references.iter() {
+                        if let Some(name) = name {
+                            if !name.is_empty() && !names.contains(&name.to_lowercase()) {
+                                add_problem(*location, *handle, kind((*name).clone()));
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    if let Some((table, name)) = Drawing::table_entry_name(item) {
+                        if !table_names.insert((table, name.to_lowercase())) {
+                            let kind = AuditProblemKind::DuplicateTableName {
+                                table: String::from(table),
+                                name: String::from(name),
+                            };
+                            add_problem(*location, *handle, kind);
+                        }
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+    /// The named pointers of an item that are checked by `audit` and keep items from being purged.
+    /// Table entries are skipped because their owners are the tables themselves, which aren't
+    /// kept.
+    fn pointers(item: &DrawingItem) -> Vec<(&'static str, Handle)> {
+        match item {
+            DrawingItem::Block(block) => vec![("owner", block.__owner_handle)],
+            DrawingItem::Entity(entity) => {
+                let mut pointers = vec![
+                    ("owner", entity.common.__owner_handle),
+                    ("material", entity.common.__material_handle),
+                    ("plot style", entity.common.__plot_style_handle),
+                ];
+                match entity.specific {
+                    EntityType::MLeader(ref ml) => {
+                        pointers.push(("mleader style", ml.__style_handle));
+                        pointers.push(("leader line type", ml.__leader_line_type_handle));
+                        pointers.push(("arrowhead", ml.__arrowhead_handle));
+                        pointers.push(("text style", ml.__text_style_handle));
+                        pointers.push(("block content", ml.__block_content_handle));
+                        if let Some(ref content) = ml.context_data.block_content {
+                            pointers.push(("block record", content.block_record_handle));
+                        }
+                    }
+                    EntityType::Table(ref table) => {
+                        pointers.push(("table style", table.__table_style_handle));
+                        pointers.push(("block record", table.__block_record_handle));
+                    }
+                    _ => (),
+                }
+                pointers
+            }
+            DrawingItem::Layer(layer) => vec![
+                ("material", layer.__material_handle),
+                ("plot style", layer.__plot_style_handle),
+            ],
+            DrawingItem::Object(obj) => {
+                let mut pointers = vec![("owner", obj.common.__owner_handle)];
+                let value_handles = match obj.specific {
+                    ObjectType::Dictionary(ref d) => Some(&d.value_handles),
+                    ObjectType::DictionaryWithDefault(ref d) => {
+                        pointers.push(("dictionary default", d.default_handle));
+                        Some(&d.value_handles)
+                    }
+                    ObjectType::MLeaderStyle(ref style) => {
+                        pointers.push(("leader line type", style.__line_leader_type_handle));
+                        pointers.push(("arrowhead", style.__arrowhead_handle));
+                        pointers.push(("text style", style.__m_text_style_handle));
+                        pointers.push(("block content", style.__block_content_handle));
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(value_handles) = value_handles {
+                    let mut entries = value_handles.iter().collect::<Vec<_>>();
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                    pointers.extend(entries.iter().map(|(_, h)| ("dictionary value", **h)));
+                }
+                pointers
+            }
+            _ => vec![],
+        }
+    }
+    fn clear_pointer(&mut self, location: ItemLocation, name: &str, pointer: Handle) {
+        match (self.item_at_mut(location), name) {
+            (Some(DrawingItemMut::Block(block)), "owner") => block.__owner_handle = Handle::empty(),
+            (Some(DrawingItemMut::Entity(entity)), "owner") => {
+                entity.common.__owner_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Entity(entity)), "material") => {
+                entity.common.__material_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Entity(entity)), "plot style") => {
+                entity.common.__plot_style_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Entity(entity)), _) => match entity.specific {
+                EntityType::MLeader(ref mut ml) => match name {
+                    "mleader style" => ml.__style_handle = Handle::empty(),
+                    "leader line type" => ml.__leader_line_type_handle = Handle::empty(),
+                    "arrowhead" => ml.__arrowhead_handle = Handle::empty(),
+                    "text style" => ml.__text_style_handle = Handle::empty(),
+                    "block content" => ml.__block_content_handle = Handle::empty(),
+                    "block record" => {
+                        if let Some(ref mut content) = ml.context_data.block_content {
+                            content.block_record_handle = Handle::empty();
+                        }
+                    }
+                    _ => (),
+                },
+                EntityType::Table(ref mut table) => match name {
+                    "table style" => table.__table_style_handle = Handle::empty(),
+                    "block record" => table.__block_record_handle = Handle::empty(),
+                    _ => (),
+                },
+                _ => (),
+            },
+            (Some(DrawingItemMut::Layer(layer)), "material") => {
+                layer.__material_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Layer(layer)), "plot style") => {
+                layer.__plot_style_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Object(obj)), "owner") => {
+                obj.common.__owner_handle = Handle::empty()
+            }
+            (Some(DrawingItemMut::Object(obj)), _) => match obj.specific {
+                ObjectType::MLeaderStyle(ref mut style) => match name {
+                    "leader line type" => style.__line_leader_type_handle = Handle::empty(),
+                    "arrowhead" => style.__arrowhead_handle = Handle::empty(),
+                    "text style" => style.__m_text_style_handle = Handle::empty(),
+                    "block content" => style.__block_content_handle = Handle::empty(),
+                    _ => (),
+                },
+                ObjectType::Dictionary(ref mut d) => d.value_handles.retain(|_, h| *h != pointer),
+                ObjectType::DictionaryWithDefault(ref mut d) => {
+                    if name == "dictionary default" {
+                        d.default_handle = Handle::empty();
+                    } else {
+                        d.value_handles.retain(|_, h| *h != pointer);
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+    /// The table and name of a table entry.  View ports are skipped because several can share a
+    /// name to describe a multiple view port configuration.
+    fn table_entry_name(item: DrawingItem<'_>) -> Option<(&'static str, &str)> {
+        match item {
+            DrawingItem::AppId(a) => Some(("APPID", &a.name)),
+            DrawingItem::BlockRecord(b) => Some(("BLOCK_RECORD", &b.name)),
+            DrawingItem::DimStyle(d) => Some(("DIMSTYLE", &d.name)),
+            DrawingItem::Layer(l) => Some(("LAYER", &l.name)),
+            DrawingItem::LineType(l) => Some(("LTYPE", &l.name)),
+            DrawingItem::Style(s) => Some(("STYLE", &s.name)),
+            DrawingItem::Ucs(u) => Some(("UCS", &u.name)),
+            DrawingItem::View(v) => Some(("VIEW", &v.name)),
+            _ => None,
+        }
+    }
+    /// Renames the table entry to `name$1`, `name$2`, etc., whichever is first unused.
+    fn rename_table_entry(&mut self, location: ItemLocation, name: &str) {
+        let (collection, _) = location.position();
+        let mut locations = vec![];
+        self.visit_locations(collection, 0, &mut |_, l| locations.push(l));
+        let taken = locations
+            .into_iter()
+            .filter_map(|l| self.item_at(l).and_then(Drawing::table_entry_name))
+            .map(|(_, n)| n.to_lowercase())
+            .collect::<HashSet<_>>();
+        let new_name = (1..)
+            .map(|i| format!("{}${}", name, i))
+            .find(|n| !taken.contains(&n.to_lowercase()))
+            .unwrap();
+        match self.item_at_mut(location) {
+            Some(DrawingItemMut::AppId(a)) => a.name = new_name,
+            Some(DrawingItemMut::BlockRecord(b)) => b.name = new_name,
+            Some(DrawingItemMut::DimStyle(d)) => d.name = new_name,
+            Some(DrawingItemMut::Layer(l)) => l.name = new_name,
+            Some(DrawingItemMut::LineType(l)) => l.name = new_name,
+            Some(DrawingItemMut::Style(s)) => s.name = new_name,
+            Some(DrawingItemMut::Ucs(u)) => u.name = new_name,
+            Some(DrawingItemMut::View(v)) => v.name = new_name,
+            _ => (),
+        }
+    }
+    pub(crate) fn add_block_no_handle_set(&mut self, block: Block) -> &Block {
+        self.ensure_layer_is_present_for_block(&block);
+        self.ensure_line_type_is_present_for_block(&block);
+        self.__blocks.push(block);
+        self.index_handles(ItemCollection::Blocks, self.__blocks.len() - 1);
+        self.__blocks.last().unwrap()
+    }
+    fn add_entity_no_handle_set(&mut self, entity: Entity) -> &Entity {
+        self.ensure_mline_style_is_present_for_entity(&entity);
+        self.ensure_dimension_style_is_present_for_entity(&entity);
+        self.ensure_layer_is_present(&entity.common.layer);
+        self.ensure_line_type_is_present(&entity.common.line_type_name);
+        self.ensure_text_style_is_present_for_entity(&entity);
+        self.__entities.push(entity);
+        self.index_handles(ItemCollection::Entities, self.__entities.len() - 1);
+        self.__entities.last().unwrap()
+    }
+    fn add_object_no_handle_set(&mut self, obj: Object) -> &Object {
+        self.ensure_layer_is_present_for_object(&obj);
+        self.ensure_line_type_is_present_for_object(&obj);
+        self.ensure_text_style_is_present_for_object(&obj);
+        self.ensure_view_is_present(&obj);
+        self.__objects.push(obj);
+        self.index_handles(ItemCollection::Objects, self.__objects.len() - 1);
+        self.__objects.last().unwrap()
+    }
+    pub(crate) fn add_app_id_no_handle_set(&mut self, app_id: AppId) -> &AppId {
+        // TODO: ensure invariants
+        self.__app_ids.push(app_id);
+        self.index_handles(ItemCollection::AppIds, self.__app_ids.len() - 1);
+        self.__app_ids.last().unwrap()
+    }
+    pub(crate) fn add_block_record_no_handle_set(
+        &mut self,
+        block_record: BlockRecord,
+    ) -> &BlockRecord {
+        // TODO: ensure invariants
+        self.__block_records.push(block_record);
+        self.index_handles(ItemCollection::BlockRecords, self.__block_records.len() - 1);
+        self.__block_records.last().unwrap()
+    }
+    pub(crate) fn add_dim_style_no_handle_set(&mut self, dim_style: DimStyle) -> &DimStyle {
+        // TODO: ensure invariants
+        self.__dim_styles.push(dim_style);
+        self.index_handles(ItemCollection::DimStyles, self.__dim_styles.len() - 1);
+        self.__dim_styles.last().unwrap()
+    }
+    pub(crate) fn add_layer_no_handle_set(&mut self, layer: Layer) -> &Layer {
+        self.__layers.push(layer);
+        self.index_handles(ItemCollection::Layers, self.__layers.len() - 1);
+        self.__layers.last().unwrap()
+    }
+    pub(crate) fn add_line_type_no_handle_set(&mut self, line_type: LineType) -> &LineType {
+        // TODO: ensure invariants
+        self.__line_types.push(line_type);
+        self.index_handles(ItemCollection::LineTypes, self.__line_types.len() - 1);
+        self.__line_types.last().unwrap()
+    }
+    pub(crate) fn add_style_no_handle_set(&mut self, style: Style) -> &Style {
+        // TODO: ensure invariants
+        self.__styles.push(style);
+        self.index_handles(ItemCollection::Styles, self.__styles.len() - 1);
+        self.__styles.last().unwrap()
+    }
+    pub(crate) fn add_ucs_no_handle_set(&mut self, ucs: Ucs) -> &Ucs {
+        // TODO: ensure invariants
+        self.__ucss.push(ucs);
+        self.index_handles(ItemCollection::Ucss, self.__ucss.len() - 1);
+        self.__ucss.last().unwrap()
     }
     pub(crate) fn add_view_no_handle_set(&mut self, view: View) -> &View {
         // TODO: ensure invariants
         self.__views.push(view);
+        self.index_handles(ItemCollection::Views, self.__views.len() - 1);
         self.__views.last().unwrap()
     }
     pub(crate) fn add_view_port_no_handle_set(&mut self, view_port: ViewPort) -> &ViewPort {
         // TODO: ensure invariants
         self.__view_ports.push(view_port);
+        self.index_handles(ItemCollection::ViewPorts, self.__view_ports.len() - 1);
         self.__view_ports.last().unwrap()
     }
     fn ensure_app_id_is_present(&mut self, name: &str) {
@@ -755,7 +2111,12 @@ impl Drawing {
     }
     fn ensure_dimension_style_is_present_for_entity(&mut self, entity: &Entity) {
         // ensure corresponding dimension style is present
-        let dim_style_name = match &entity.specific {
+        if let Some(dim_style_name) = Drawing::dimension_style_name(entity) {
+            self.ensure_dimension_style_is_present(&dim_style_name);
+        }
+    }
+    fn dimension_style_name(entity: &Entity) -> Option<&String> {
+        match &entity.specific {
             EntityType::RotatedDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
             EntityType::RadialDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
             EntityType::DiameterDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
@@ -766,9 +2127,54 @@ impl Drawing {
             EntityType::Leader(ref l) => Some(&l.dimension_style_name),
             EntityType::Tolerance(ref t) => Some(&t.dimension_style_name),
             _ => None,
-        };
-        if let Some(dim_style_name) = dim_style_name {
-            self.ensure_dimension_style_is_present(&dim_style_name);
+        }
+    }
+    fn dimension_style_name_mut(entity: &mut Entity) -> Option<&mut String> {
+        match entity.specific {
+            EntityType::RotatedDimension(ref mut d) => {
+                Some(&mut d.dimension_base.dimension_style_name)
+            }
+            EntityType::RadialDimension(ref mut d) => {
+                Some(&mut d.dimension_base.dimension_style_name)
+            }
+            EntityType::DiameterDimension(ref mut d) => {
+                Some(&mut d.dimension_base.dimension_style_name)
+            }
+            EntityType::AngularThreePointDimension(ref mut d) => {
+                Some(&mut d.dimension_base.dimension_style_name)
+            }
+            EntityType::OrdinateDimension(ref mut d) => {
+                Some(&mut d.dimension_base.dimension_style_name)
+            }
+            EntityType::Leader(ref mut l) => Some(&mut l.dimension_style_name),
+            EntityType::Tolerance(ref mut t) => Some(&mut t.dimension_style_name),
+            _ => None,
+        }
+    }
+    fn block_name(entity: &Entity) -> Option<&String> {
+        match &entity.specific {
+            EntityType::Insert(ref i) => Some(&i.name),
+            EntityType::RotatedDimension(ref d) => Some(&d.dimension_base.block_name),
+            EntityType::RadialDimension(ref d) => Some(&d.dimension_base.block_name),
+            EntityType::DiameterDimension(ref d) => Some(&d.dimension_base.block_name),
+            EntityType::AngularThreePointDimension(ref d) => Some(&d.dimension_base.block_name),
+            EntityType::OrdinateDimension(ref d) => Some(&d.dimension_base.block_name),
+            EntityType::Table(ref t) => Some(&t.block_name),
+            _ => None,
+        }
+    }
+    fn block_name_mut(entity: &mut Entity) -> Option<&mut String> {
+        match entity.specific {
+            EntityType::Insert(ref mut i) => Some(&mut i.name),
+            EntityType::RotatedDimension(ref mut d) => Some(&mut d.dimension_base.block_name),
+            EntityType::RadialDimension(ref mut d) => Some(&mut d.dimension_base.block_name),
+            EntityType::DiameterDimension(ref mut d) => Some(&mut d.dimension_base.block_name),
+            EntityType::AngularThreePointDimension(ref mut d) => {
+                Some(&mut d.dimension_base.block_name)
+            }
+            EntityType::OrdinateDimension(ref mut d) => Some(&mut d.dimension_base.block_name),
+            EntityType::Table(ref mut t) => Some(&mut t.block_name),
+            _ => None,
         }
     }
     fn ensure_dimension_style_is_present(&mut self, dim_style_name: &str) {
@@ -818,6 +2224,18 @@ impl Drawing {
             self.ensure_line_type_is_present(&style.style_name);
         }
     }
+    /// Line types named by layers are only checked after the tables are read because the `LAYER`
+    /// table can come before the `LTYPE` table.
+    fn ensure_line_types_are_present_for_layers(&mut self) {
+        let names = self
+            .__layers
+            .iter()
+            .map(|l| l.line_type_name.clone())
+            .collect::<Vec<_>>();
+        for name in names {
+            self.ensure_line_type_is_present(&name);
+        }
+    }
     fn ensure_line_type_is_present(&mut self, line_type_name: &str) {
         if !self.line_types().any(|lt| lt.name == *line_type_name) {
             self.add_line_type(LineType {
@@ -827,16 +2245,28 @@ impl Drawing {
         }
     }
     fn ensure_text_style_is_present_for_entity(&mut self, entity: &Entity) {
-        let text_style_name = match &entity.specific {
+        if let Some(text_style_name) = Drawing::text_style_name(entity) {
+            self.ensure_text_style_is_present(&text_style_name);
+        }
+    }
+    fn text_style_name(entity: &Entity) -> Option<&String> {
+        match &entity.specific {
             EntityType::ArcAlignedText(ref e) => Some(&e.text_style_name),
             EntityType::Attribute(ref e) => Some(&e.text_style_name),
             EntityType::AttributeDefinition(ref e) => Some(&e.text_style_name),
             EntityType::MText(ref e) => Some(&e.text_style_name),
             EntityType::Text(ref e) => Some(&e.text_style_name),
             _ => None,
-        };
-        if let Some(text_style_name) = text_style_name {
-            self.ensure_text_style_is_present(&text_style_name);
+        }
+    }
+    fn text_style_name_mut(entity: &mut Entity) -> Option<&mut String> {
+        match entity.specific {
+            EntityType::ArcAlignedText(ref mut e) => Some(&mut e.text_style_name),
+            EntityType::Attribute(ref mut e) => Some(&mut e.text_style_name),
+            EntityType::AttributeDefinition(ref mut e) => Some(&mut e.text_style_name),
+            EntityType::MText(ref mut e) => Some(&mut e.text_style_name),
+            EntityType::Text(ref mut e) => Some(&mut e.text_style_name),
+            _ => None,
         }
     }
     fn ensure_text_style_is_present_for_object(&mut self, obj: &Object) {
@@ -878,7 +2308,19 @@ impl Drawing {
             });
         }
     }
-    fn write_classes<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    pub(crate) fn text_encoding(&self) -> &'static Encoding {
+        if self.header.version <= AcadVersion::R2004 {
+            // anything not representable in the code page is written as a `\U+XXXX` escape
+            get_encoding_from_code_page(&self.header.drawing_code_page)
+                .unwrap_or(encoding_rs::WINDOWS_1252)
+        } else {
+            encoding_rs::UTF_8
+        }
+    }
+    pub(crate) fn write_handles(&self) -> bool {
+        self.header.version >= AcadVersion::R13 || self.header.handles_enabled
+    }
+    pub(crate) fn write_classes<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
@@ -895,7 +2337,11 @@ impl Drawing {
         writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
         Ok(())
     }
-    fn write_tables<T>(&self, write_handles: bool, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    pub(crate) fn write_tables<T>(
+        &self,
+        write_handles: bool,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
@@ -905,7 +2351,11 @@ impl Drawing {
         writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
         Ok(())
     }
-    fn write_blocks<T>(&self, write_handles: bool, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    pub(crate) fn write_blocks<T>(
+        &self,
+        write_handles: bool,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
@@ -922,7 +2372,7 @@ impl Drawing {
         writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
         Ok(())
     }
-    fn write_entities<T>(
+    pub(crate) fn write_entities<T>(
         &self,
         write_handles: bool,
         writer: &mut CodePairWriter<T>,
@@ -939,7 +2389,7 @@ impl Drawing {
         writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
         Ok(())
     }
-    fn write_objects<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    pub(crate) fn write_objects<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
@@ -952,7 +2402,7 @@ impl Drawing {
         writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
         Ok(())
     }
-    fn write_thumbnail<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    pub(crate) fn write_thumbnail<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
     where
         T: Write + ?Sized,
     {
@@ -973,13 +2423,30 @@ impl Drawing {
         }
         Ok(())
     }
-    fn read_sections<T>(drawing: &mut Drawing, iter: &mut CodePairPutBack<T>) -> DxfResult<()>
+    pub(crate) fn write_extra_sections<T>(
+        &self,
+        preceding_section: &str,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
     where
-        T: Read,
+        T: Write + ?Sized,
     {
-        loop {
-            match iter.next() {
-                Some(Ok(pair @ CodePair { code: 0, .. })) => match &*pair.assert_string()? {
+        for section in &self.extra_sections {
+            if section.preceding_section == preceding_section {
+                section.write(writer)?;
+            }
+        }
+        Ok(())
+    }
+    fn read_sections<T>(drawing: &mut Drawing, iter: &mut CodePairPutBack<T>) -> DxfResult<()>
+    where
+        T: Read,
+    {
+        // unsupported sections are written back after the last supported section that preceded them
+        let mut preceding_section = String::new();
+        loop {
+            match iter.next() {
+                Some(Ok(pair @ CodePair { code: 0, .. })) => match &*pair.assert_string()? {
                     "EOF" => {
                         iter.put_back(Ok(pair));
                         break;
@@ -990,61 +2457,60 @@ impl Drawing {
                             value: CodePairValue::Str(s),
                             ..
                         })) => {
-                            match &*s {
-                                "HEADER" => drawing.header = Header::read(iter)?,
-                                "CLASSES" => Class::read_classes(drawing, iter)?,
+                            iter.set_section(&s);
+                            let mut is_supported = true;
+                            let result = match &*s {
+                                "HEADER" => Header::read(iter).map(|h| drawing.header = h),
+                                "CLASSES" => Class::read_classes(drawing, iter),
                                 "TABLES" => {
-                                    drawing.read_section_item(iter, "TABLE", read_specific_table)?
+                                    drawing.read_section_item(iter, "TABLE", read_specific_table)
                                 }
                                 "BLOCKS" => {
-                                    drawing.read_section_item(iter, "BLOCK", Block::read_block)?
+                                    drawing.read_section_item(iter, "BLOCK", Block::read_block)
                                 }
-                                "ENTITIES" => drawing.read_entities(iter)?,
-                                "OBJECTS" => drawing.read_objects(iter)?,
+                                "ENTITIES" => drawing.read_entities(iter),
+                                "OBJECTS" => drawing.read_objects(iter),
                                 "THUMBNAILIMAGE" => {
-                                    drawing.thumbnail = thumbnail::read_thumbnail(iter)?;
+                                    thumbnail::read_thumbnail(iter).map(|t| drawing.thumbnail = t)
+                                }
+                                _ => {
+                                    is_supported = false;
+                                    ExtraSection::read(s.clone(), preceding_section.clone(), iter)
+                                        .map(|section| drawing.extra_sections.push(section))
                                 }
-                                _ => Drawing::swallow_section(iter)?,
+                            };
+
+                            if is_supported {
+                                preceding_section = s;
                             }
 
-                            match iter.next() {
-                                Some(Ok(CodePair {
-                                    code: 0,
-                                    value: CodePairValue::Str(ref s),
-                                    ..
-                                })) if s == "ENDSEC" => (),
-                                Some(Ok(pair)) => {
-                                    return Err(DxfError::UnexpectedCodePair(
-                                        pair,
-                                        String::from("expected 0/ENDSEC"),
-                                    ))
+                            if let Err(e) = result.and_then(|_| Drawing::read_end_of_section(iter))
+                            {
+                                // in recovery mode the rest of the section is dropped
+                                iter.recover(e)?;
+                                if !Drawing::skip_past_end_of_section(iter) {
+                                    break;
                                 }
-                                Some(Err(e)) => return Err(e),
-                                None => return Err(DxfError::UnexpectedEndOfInput),
                             }
+
+                            iter.set_section("");
                         }
-                        Some(Ok(pair)) => {
-                            return Err(DxfError::UnexpectedCodePair(
-                                pair,
-                                String::from("expected 2/<section-name>"),
-                            ))
-                        }
+                        Some(Ok(pair)) => iter.recover(DxfError::UnexpectedCodePair(
+                            pair,
+                            String::from("expected 2/<section-name>"),
+                        ))?,
                         Some(Err(e)) => return Err(e),
                         None => return Err(DxfError::UnexpectedEndOfInput),
                     },
-                    _ => {
-                        return Err(DxfError::UnexpectedCodePair(
-                            pair,
-                            String::from("expected 0/SECTION"),
-                        ))
-                    }
-                },
-                Some(Ok(pair)) => {
-                    return Err(DxfError::UnexpectedCodePair(
+                    _ => iter.recover(DxfError::UnexpectedCodePair(
                         pair,
-                        String::from("expected 0/SECTION or 0/EOF"),
-                    ))
-                }
+                        String::from("expected 0/SECTION"),
+                    ))?,
+                },
+                Some(Ok(pair)) => iter.recover(DxfError::UnexpectedCodePair(
+                    pair,
+                    String::from("expected 0/SECTION or 0/EOF"),
+                ))?,
                 Some(Err(e)) => return Err(e),
                 None => break, // ideally should have been 0/EOF
             }
@@ -1052,30 +2518,51 @@ impl Drawing {
 
         Ok(())
     }
-    fn swallow_section<T>(iter: &mut CodePairPutBack<T>) -> DxfResult<()>
+    fn read_end_of_section<T>(iter: &mut CodePairPutBack<T>) -> DxfResult<()>
     where
         T: Read,
     {
-        loop {
-            match iter.next() {
-                Some(Ok(pair)) => {
-                    if pair.code == 0 && pair.assert_string()? == "ENDSEC" {
-                        iter.put_back(Ok(pair));
-                        break;
-                    }
+        match iter.next() {
+            Some(Ok(CodePair {
+                code: 0,
+                value: CodePairValue::Str(ref s),
+                ..
+            })) if s == "ENDSEC" => Ok(()),
+            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                pair,
+                String::from("expected 0/ENDSEC"),
+            )),
+            Some(Err(e)) => Err(e),
+            None => Err(DxfError::UnexpectedEndOfInput),
+        }
+    }
+    /// Skips to just past the next `0/ENDSEC`, or to the next `0/SECTION` or `0/EOF` if the
+    /// section wasn't terminated.  Returns `false` if the end of the input was reached.
+    fn skip_past_end_of_section<T>(iter: &mut CodePairPutBack<T>) -> bool
+    where
+        T: Read,
+    {
+        let found = iter.skip_until(|pair| match pair.value {
+            CodePairValue::Str(ref s) if pair.code == 0 => {
+                s == "ENDSEC" || s == "SECTION" || s == "EOF"
+            }
+            _ => false,
+        });
+        if found {
+            if let Some(Ok(pair)) = iter.next() {
+                if pair.code != 0 || pair.assert_string().ok().as_deref() != Some("ENDSEC") {
+                    iter.put_back(Ok(pair));
                 }
-                Some(Err(e)) => return Err(e),
-                None => break,
             }
         }
 
-        Ok(())
+        found
     }
     fn read_entities<T>(&mut self, iter: &mut CodePairPutBack<T>) -> DxfResult<()>
     where
         T: Read,
     {
-        let mut iter = EntityIter { iter };
+        let mut iter = EntityIter::new(iter);
         let mut entities = vec![];
         iter.read_entities_into_vec(&mut entities)?;
         for e in entities {
@@ -1091,8 +2578,8 @@ impl Drawing {
     where
         T: Read,
     {
-        let iter = put_back(ObjectIter { iter });
-        for o in iter {
+        let mut objects = ObjectIter::new(iter);
+        for o in put_back(&mut objects) {
             if o.common.handle.is_empty() {
                 self.add_object(o);
             } else {
@@ -1100,7 +2587,10 @@ impl Drawing {
             }
         }
 
-        Ok(())
+        match objects.error.take() {
+            Some(e) if objects.iter.is_strict() => Err(e),
+            _ => Ok(()),
+        }
     }
     fn read_section_item<I, F>(
         &mut self,
@@ -1123,7 +2613,19 @@ impl Drawing {
                             }
                             val => {
                                 if val == item_type {
-                                    callback(self, iter)?;
+                                    if let Err(e) = callback(self, iter) {
+                                        // in recovery mode the broken item is dropped
+                                        iter.recover(e)?;
+                                        let found = iter.skip_until(|pair| match pair.value {
+                                            CodePairValue::Str(ref s) if pair.code == 0 => {
+                                                s == item_type || s == "ENDSEC"
+                                            }
+                                            _ => false,
+                                        });
+                                        if !found {
+                                            return Err(DxfError::UnexpectedEndOfInput);
+                                        }
+                                    }
                                 } else {
                                     return Err(DxfError::UnexpectedCodePair(pair, String::new()));
                                 }
@@ -1254,14 +2756,32 @@ impl Drawing {
     }
 }
 
+/// The items that `Drawing::purge` has to keep.
+#[derive(Default)]
+struct PurgeReferences {
+    layers: HashSet<String>,
+    line_types: HashSet<String>,
+    text_styles: HashSet<String>,
+    dim_styles: HashSet<String>,
+    blocks: HashSet<String>,
+    mline_styles: HashSet<String>,
+    handles: HashSet<Handle>,
+    has_shapes: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::entities::*;
+    use crate::enums::*;
     use crate::helper_functions::tests::*;
     use crate::objects::*;
     use crate::tables::*;
     use crate::*;
 
+    use crate::drawing::AUTO_REPLACE_HANDLE;
+    use crate::drawing_item::{EntityLocation, ItemLocation};
+    use std::collections::HashMap;
+
     #[test]
     fn default_layers_are_present() {
         let drawing = Drawing::new();
@@ -1944,6 +3464,72 @@ mod tests {
         assert_eq!(1, line_types.count());
     }
 
+    #[test]
+    fn line_type_is_added_with_layer_on_file_read_in_any_table_order() {
+        let drawing = from_section(
+            "TABLES",
+            vec![
+                "  0",
+                "TABLE",
+                "  2",
+                "LAYER",
+                "  0",
+                "LAYER",
+                "  2",
+                "walls",
+                "  6",
+                "dashed",
+                "  0",
+                "LAYER",
+                "  2",
+                "doors",
+                "  6",
+                "missing",
+                "  0",
+                "ENDTAB",
+                "  0",
+                "TABLE",
+                "  2",
+                "LTYPE",
+                "  0",
+                "LTYPE",
+                "  2",
+                "dashed",
+                "  3",
+                "from the file",
+                "  0",
+                "ENDTAB",
+            ]
+            .join("\r\n")
+            .as_str(),
+        );
+        let dashed = drawing
+            .line_types()
+            .filter(|lt| lt.name == "dashed")
+            .collect::<Vec<_>>();
+        assert_eq!(1, dashed.len());
+        assert_eq!("from the file", dashed[0].description);
+        assert_eq!(
+            1,
+            drawing
+                .line_types()
+                .filter(|lt| lt.name == "missing")
+                .count()
+        );
+    }
+
+    #[test]
+    fn save_keeps_the_table_order() {
+        let drawing = Drawing::new();
+        let contents = to_test_string(&drawing);
+        let table_position = |name: &str| {
+            contents
+                .find(&format!("TABLE\r\n  2\r\n{}\r\n", name))
+                .unwrap()
+        };
+        assert!(table_position("LAYER") < table_position("LTYPE"));
+    }
+
     #[test]
     fn line_type_is_added_with_object_on_file_read() {
         let drawing = parse_drawing(
@@ -2191,4 +3777,1273 @@ mod tests {
         let views = drawing.views().filter(|&v| v.name == "some-view");
         assert_eq!(1, views.count());
     }
+
+    #[test]
+    fn read_acds_data() {
+        let drawing = parse_drawing(
+            vec![
+                "  0",
+                "SECTION",
+                "  2",
+                "ENTITIES",
+                "  0",
+                "3DSOLID",
+                "  5",
+                "2A",
+                "  0",
+                "ENDSEC",
+                "  0",
+                "SECTION",
+                "  2",
+                "ACDSDATA",
+                " 70",
+                "     2",
+                " 71",
+                "     2",
+                "  0",
+                "ACDSSCHEMA",
+                " 90",
+                "        0",
+                "  1",
+                "AcDb3DSolid_ASM_Data",
+                "  2",
+                "AcDbDs::ID",
+                "280",
+                "    10",
+                " 91",
+                "        8",
+                "  0",
+                "ACDSRECORD",
+                " 90",
+                "        0",
+                "  2",
+                "AcDbDs::ID",
+                "280",
+                "    10",
+                "320",
+                "2A",
+                "  2",
+                "ASM_Data",
+                "280",
+                "    15",
+                " 94",
+                "        4",
+                "310",
+                "0102",
+                "310",
+                "0304",
+                "  0",
+                "ENDSEC",
+                "  0",
+                "EOF",
+            ]
+            .join("\r\n")
+            .as_str(),
+        );
+        let acds = drawing.acds_data().unwrap().unwrap();
+        assert_eq!(2, acds.version);
+        assert_eq!(1, acds.records.len());
+        let record = acds.get_record_for_entity(Handle(0x2A)).unwrap();
+        assert_eq!(0, record.schema_index);
+        assert_eq!(vec![0x01, 0x02, 0x03, 0x04], record.data);
+        match record.get_entity(&drawing).unwrap().specific {
+            EntityType::Solid3D(_) => (),
+            _ => panic!("expected a 3DSOLID"),
+        }
+    }
+
+    fn load_with_recovery(lines: &[&str]) -> (Drawing, Vec<Diagnostic>) {
+        let options = LoadOptions { recovery: true };
+        Drawing::load_with_options(&mut lines.join("\n").as_bytes(), &options).unwrap()
+    }
+
+    #[test]
+    fn recover_from_malformed_value() {
+        let lines = [
+            "0", "SECTION", "2", "ENTITIES", "0", "LINE", "5", "2A", "10", "one", "20", "2.0", "0",
+            "CIRCLE", "0", "ENDSEC", "0", "EOF",
+        ];
+        assert!(Drawing::load(&mut lines.join("\n").as_bytes()).is_err());
+
+        let (drawing, diagnostics) = load_with_recovery(&lines);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Line(ref line) => assert_eq!(Point::new(0.0, 2.0, 0.0), line.p1),
+            _ => panic!("expected a line"),
+        }
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(10, diagnostics[0].offset);
+        assert_eq!("ENTITIES", diagnostics[0].section);
+        assert_eq!(Handle(0x2A), diagnostics[0].handle);
+    }
+
+    #[test]
+    fn recover_from_unknown_code() {
+        let lines = [
+            "0",
+            "SECTION",
+            "2",
+            "HEADER",
+            "9",
+            "$ACADVER",
+            "1",
+            "AC1015",
+            "9",
+            "$CLAYER",
+            "12345",
+            "garbage",
+            "8",
+            "some-layer",
+            "0",
+            "ENDSEC",
+            "0",
+            "EOF",
+        ];
+        let (drawing, diagnostics) = load_with_recovery(&lines);
+        assert_eq!(enums::AcadVersion::R2000, drawing.header.version);
+        assert_eq!("some-layer", drawing.header.current_layer);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("HEADER", diagnostics[0].section);
+        assert_eq!(Handle::empty(), diagnostics[0].handle);
+    }
+
+    #[test]
+    fn recover_from_broken_entity() {
+        let lines = [
+            "0",
+            "SECTION",
+            "2",
+            "ENTITIES",
+            "0",
+            "LINE",
+            "5",
+            "10",
+            "0",
+            "LINE",
+            "5",
+            "11",
+            "330",
+            "not-a-handle",
+            "0",
+            "CIRCLE",
+            "5",
+            "12",
+            "0",
+            "ENDSEC",
+            "0",
+            "EOF",
+        ];
+        let (drawing, diagnostics) = load_with_recovery(&lines);
+        let handles = drawing
+            .entities()
+            .map(|e| e.common.handle)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Handle(0x10), Handle(0x12)], handles);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Handle(0x11), diagnostics[0].handle);
+    }
+
+    #[test]
+    fn strict_load_fails_on_broken_entities_and_objects() {
+        let options = LoadOptions::default();
+        for section in ["ENTITIES", "OBJECTS"] {
+            let item = if section == "ENTITIES" {
+                "LINE"
+            } else {
+                "DICTIONARY"
+            };
+            let lines = [
+                "0",
+                "SECTION",
+                "2",
+                section,
+                "0",
+                item,
+                "5",
+                "10",
+                "330",
+                "not-a-handle",
+                "0",
+                "ENDSEC",
+                "0",
+                "EOF",
+            ]
+            .join("\n");
+            assert!(Drawing::load_with_options(&mut lines.as_bytes(), &options).is_err());
+
+            // loading without options still stops at the broken item
+            assert!(Drawing::load(&mut lines.as_bytes()).is_ok());
+        }
+    }
+
+    #[test]
+    fn recover_from_truncated_file() {
+        let lines = [
+            "0", "SECTION", "2", "ENTITIES", "0", "LINE", "5", "10", "0", "CIRCLE", "5", "11",
+        ];
+        let (drawing, diagnostics) = load_with_recovery(&lines);
+        assert_eq!(1, drawing.entities().count());
+        assert!(!diagnostics.is_empty());
+    }
+
+    #[test]
+    fn no_diagnostics_for_well_formed_file() {
+        let lines = [
+            "0", "SECTION", "2", "ENTITIES", "0", "LINE", "0", "ENDSEC", "0", "EOF",
+        ];
+        let (drawing, diagnostics) = load_with_recovery(&lines);
+        assert_eq!(1, drawing.entities().count());
+        assert!(diagnostics.is_empty());
+    }
+
+    fn drawing_with_block(name: &str, entities: Vec<Entity>) -> Drawing {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from(name),
+            ..Default::default()
+        };
+        block.entities = entities;
+        drawing.add_block(block);
+        drawing
+    }
+
+    fn insert_entity(insert: Insert) -> Entity {
+        Entity::new(EntityType::Insert(insert))
+    }
+
+    fn line_points(entity: &Entity) -> (Point, Point) {
+        match entity.specific {
+            EntityType::Line(ref line) => (line.p1.clone(), line.p2.clone()),
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn explode_insert_applies_transform() {
+        let line = Entity::new(EntityType::Line(Line::new(
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        )));
+        let mut drawing = drawing_with_block("b", vec![line]);
+        drawing.blocks_mut().next().unwrap().base_point = Point::new(1.0, 0.0, 0.0);
+        let insert = insert_entity(Insert {
+            name: String::from("b"),
+            location: Point::new(10.0, 0.0, 0.0),
+            x_scale_factor: 2.0,
+            y_scale_factor: 2.0,
+            rotation: 90.0,
+            ..Default::default()
+        });
+
+        let exploded = drawing.explode_insert(&insert).unwrap();
+        assert_eq!(1, exploded.len());
+        let (p1, p2) = line_points(&exploded[0]);
+        assert!(p1.is_close_to(&Point::new(10.0, 0.0, 0.0), 1.0e-12));
+        assert!(p2.is_close_to(&Point::new(10.0, 2.0, 0.0), 1.0e-12));
+    }
+
+    #[test]
+    fn explode_insert_array() {
+        let point = Entity::new(EntityType::ModelPoint(ModelPoint::new(Point::origin())));
+        let drawing = drawing_with_block("b", vec![point]);
+        let insert = insert_entity(Insert {
+            name: String::from("b"),
+            column_count: 3,
+            row_count: 2,
+            column_spacing: 5.0,
+            row_spacing: 4.0,
+            ..Default::default()
+        });
+
+        let locations = drawing
+            .explode_insert(&insert)
+            .unwrap()
+            .iter()
+            .map(|e| match e.specific {
+                EntityType::ModelPoint(ref p) => (p.location.x, p.location.y),
+                _ => panic!("expected a point"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (0.0, 0.0),
+                (5.0, 0.0),
+                (10.0, 0.0),
+                (0.0, 4.0),
+                (5.0, 4.0),
+                (10.0, 4.0)
+            ],
+            locations
+        );
+    }
+
+    #[test]
+    fn explode_insert_resolves_by_block_values() {
+        let mut by_block = Entity::new(EntityType::Line(Line::default()));
+        by_block.common.color = Color::by_block();
+        by_block.common.line_type_name = String::from("BYBLOCK");
+        let mut explicit = Entity::new(EntityType::Line(Line::default()));
+        explicit.common.color = Color::from_index(3);
+        explicit.common.layer = String::from("other-layer");
+        let drawing = drawing_with_block("b", vec![by_block, explicit]);
+        let mut insert = insert_entity(Insert {
+            name: String::from("b"),
+            ..Default::default()
+        });
+        insert.common.color = Color::from_index(1);
+        insert.common.line_type_name = String::from("DASHED");
+        insert.common.layer = String::from("insert-layer");
+
+        let exploded = drawing.explode_insert(&insert).unwrap();
+        assert_eq!(Color::from_index(1), exploded[0].common.color);
+        assert_eq!("DASHED", exploded[0].common.line_type_name);
+        assert_eq!("insert-layer", exploded[0].common.layer);
+        assert_eq!(Color::from_index(3), exploded[1].common.color);
+        assert_eq!("BYLAYER", exploded[1].common.line_type_name);
+        assert_eq!("other-layer", exploded[1].common.layer);
+    }
+
+    #[test]
+    fn explode_insert_converts_attributes_to_text() {
+        let mut drawing = drawing_with_block("b", vec![]);
+        let mut ins = Insert {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        ins.add_attribute(
+            &mut drawing,
+            Attribute {
+                value: String::from("visible"),
+                location: Point::new(1.0, 2.0, 0.0),
+                ..Default::default()
+            },
+        );
+        let mut invisible = Attribute {
+            value: String::from("invisible"),
+            ..Default::default()
+        };
+        invisible.set_is_invisible(true);
+        ins.add_attribute(&mut drawing, invisible);
+
+        let exploded = drawing.explode_insert(&insert_entity(ins)).unwrap();
+        assert_eq!(1, exploded.len());
+        match exploded[0].specific {
+            EntityType::Text(ref text) => {
+                assert_eq!("visible", text.value);
+                assert_eq!(Point::new(1.0, 2.0, 0.0), text.location);
+            }
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn explode_nested_inserts() {
+        let line = Entity::new(EntityType::Line(Line::new(
+            Point::origin(),
+            Point::new(1.0, 0.0, 0.0),
+        )));
+        let mut drawing = drawing_with_block("inner", vec![line]);
+        let mut outer = Block {
+            name: String::from("outer"),
+            ..Default::default()
+        };
+        outer.entities.push(insert_entity(Insert {
+            name: String::from("inner"),
+            location: Point::new(1.0, 0.0, 0.0),
+            ..Default::default()
+        }));
+        drawing.add_block(outer);
+        let insert = insert_entity(Insert {
+            name: String::from("outer"),
+            location: Point::new(0.0, 5.0, 0.0),
+            x_scale_factor: 2.0,
+            y_scale_factor: 2.0,
+            ..Default::default()
+        });
+
+        let exploded = drawing.explode_insert(&insert).unwrap();
+        assert_eq!(1, exploded.len());
+        let (p1, p2) = line_points(&exploded[0]);
+        assert!(p1.is_close_to(&Point::new(2.0, 5.0, 0.0), 1.0e-12));
+        assert!(p2.is_close_to(&Point::new(4.0, 5.0, 0.0), 1.0e-12));
+    }
+
+    #[test]
+    fn explode_non_insert_or_missing_block() {
+        let drawing = Drawing::new();
+        let line = Entity::new(EntityType::Line(Line::default()));
+        assert!(drawing.explode_insert(&line).is_none());
+        let insert = insert_entity(Insert {
+            name: String::from("missing"),
+            ..Default::default()
+        });
+        assert!(drawing.explode_insert(&insert).is_none());
+    }
+
+    #[test]
+    fn flatten_inserts_replaces_inserts() {
+        let circle = Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0)));
+        let mut drawing = drawing_with_block("b", vec![circle]);
+        drawing.add_entity(insert_entity(Insert {
+            name: String::from("b"),
+            location: Point::new(3.0, 0.0, 0.0),
+            ..Default::default()
+        }));
+        drawing.add_entity(insert_entity(Insert {
+            name: String::from("missing"),
+            ..Default::default()
+        }));
+
+        drawing.flatten_inserts();
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[1].specific {
+            EntityType::Insert(ref ins) => assert_eq!("missing", ins.name),
+            _ => panic!("expected the unresolved insert"),
+        }
+        match entities[0].specific {
+            EntityType::Circle(ref circle) => {
+                assert_eq!(Point::new(3.0, 0.0, 0.0), circle.center)
+            }
+            _ => panic!("expected a circle"),
+        }
+        assert!(!entities[0].common.handle.is_empty());
+        assert_ne!(entities[0].common.handle, entities[1].common.handle);
+    }
+
+    #[test]
+    fn get_item_by_handle_finds_block_entities() {
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.handle = Handle(0x4242);
+        let drawing = drawing_with_block("b", vec![line]);
+        match drawing.get_item_by_handle(Handle(0x4242)) {
+            Some(DrawingItem::Entity(entity)) => match entity.specific {
+                EntityType::Line(_) => (),
+                _ => panic!("expected a line"),
+            },
+            _ => panic!("expected an entity"),
+        }
+    }
+
+    #[test]
+    fn get_item_by_handle_finds_vertices_and_attributes() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline::default();
+        poly.add_vertex(&mut drawing, Vertex::new(Point::new(1.0, 2.0, 0.0)));
+        let mut ins = Insert::default();
+        ins.add_attribute(
+            &mut drawing,
+            Attribute {
+                value: String::from("value"),
+                ..Default::default()
+            },
+        );
+        let vertex_handle = poly.__vertices_and_handles[0].1;
+        let attribute_handle = ins.__attributes_and_handles[0].1;
+        drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+        drawing.add_entity(insert_entity(ins));
+
+        match drawing.get_item_by_handle(vertex_handle) {
+            Some(DrawingItem::Vertex(v, handle)) => {
+                assert_eq!(Point::new(1.0, 2.0, 0.0), v.location);
+                assert_eq!(vertex_handle, handle);
+            }
+            _ => panic!("expected a vertex"),
+        }
+        match drawing.get_item_by_handle_mut(attribute_handle) {
+            Some(DrawingItemMut::Attribute(a, _)) => a.value = String::from("changed"),
+            _ => panic!("expected an attribute"),
+        }
+        match drawing.get_item_by_handle(attribute_handle) {
+            Some(DrawingItem::Attribute(a, _)) => assert_eq!("changed", a.value),
+            _ => panic!("expected an attribute"),
+        }
+    }
+
+    #[test]
+    fn get_item_by_handle_after_removal() {
+        let mut drawing = Drawing::new();
+        let first = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::default())))
+            .common
+            .handle;
+        let second = drawing
+            .add_entity(Entity::new(EntityType::Circle(Circle::default())))
+            .common
+            .handle;
+        drawing.remove_entity(0);
+        assert!(drawing.get_item_by_handle(first).is_none());
+        match drawing.get_item_by_handle(second) {
+            Some(DrawingItem::Entity(entity)) => match entity.specific {
+                EntityType::Circle(_) => (),
+                _ => panic!("expected a circle"),
+            },
+            _ => panic!("expected an entity"),
+        }
+        assert_eq!(
+            Some(ItemLocation::Entity(EntityLocation {
+                block: None,
+                index: 0
+            })),
+            drawing.__handle_index.get(&second).copied()
+        );
+    }
+
+    #[test]
+    fn get_item_by_handle_after_handle_change() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        let old_handle = drawing.entities().next().unwrap().common.handle;
+        for entity in drawing.entities_mut() {
+            entity.common.handle = Handle(0x9999);
+        }
+        assert!(drawing.get_item_by_handle(old_handle).is_none());
+        assert!(drawing.get_item_by_handle(Handle(0x9999)).is_some());
+        assert!(drawing.get_item_by_handle_mut(Handle(0x9999)).is_some());
+        assert!(drawing.__handle_index.contains_key(&Handle(0x9999)));
+        assert!(drawing.get_item_by_handle(Handle::empty()).is_none());
+    }
+
+    #[test]
+    fn get_item_by_handle_without_index() {
+        // e.g., after deserializing
+        let mut drawing = Drawing::new();
+        let handle = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::default())))
+            .common
+            .handle;
+        drawing.__handle_index.clear();
+        assert!(drawing.get_item_by_handle(handle).is_some());
+        assert!(drawing.get_item_by_handle_mut(handle).is_some());
+        assert!(drawing.__handle_index.contains_key(&handle));
+    }
+
+    #[test]
+    fn get_item_by_handle_finds_entities_added_with_block() {
+        let mut drawing = Drawing::new();
+        let poly = LwPolyline {
+            vertices: vec![LwPolylineVertex::default()],
+            ..Default::default()
+        }
+        .to_polyline(0.0);
+        drawing.add_block(Block {
+            name: String::from("b"),
+            entities: vec![
+                Entity::new(EntityType::Line(Line::default())),
+                Entity::new(EntityType::Polyline(poly)),
+            ],
+            ..Default::default()
+        });
+        let block = drawing.blocks().find(|b| b.name == "b").unwrap();
+        let line_handle = block.entities[0].common.handle;
+        assert!(!line_handle.is_empty());
+        match drawing.get_item_by_handle(line_handle) {
+            Some(DrawingItem::Entity(entity)) => match entity.specific {
+                EntityType::Line(_) => (),
+                _ => panic!("expected a line"),
+            },
+            _ => panic!("expected an entity"),
+        }
+        assert!(drawing.__handle_index.contains_key(&line_handle));
+        match block.entities[1].specific {
+            EntityType::Polyline(ref poly) => {
+                let vertex_handle = poly.__vertices_and_handles[0].1;
+                assert_ne!(AUTO_REPLACE_HANDLE, vertex_handle);
+                assert!(drawing.__handle_index.contains_key(&vertex_handle));
+            }
+            _ => panic!("expected a polyline"),
+        }
+        assert!(!drawing.__handle_index.contains_key(&AUTO_REPLACE_HANDLE));
+    }
+
+    #[test]
+    fn handle_index_is_built_on_load() {
+        let drawing = from_section("ENTITIES", ["  0", "LINE", "  5", "AB"].join("\n").as_str());
+        assert_eq!(
+            Some(ItemLocation::Entity(EntityLocation {
+                block: None,
+                index: 0
+            })),
+            drawing.__handle_index.get(&Handle(0xAB)).copied()
+        );
+    }
+
+    #[test]
+    fn vertex_and_attribute_handles_are_kept_on_load() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                "  0", "POLYLINE", "  5", "A1", " 66", "1", "  0", "VERTEX", "  5", "A2", " 10",
+                "1.0", " 20", "2.0", "  0", "SEQEND", "  5", "A3", "  0", "INSERT", "  5", "B1",
+                " 66", "1", "  0", "ATTRIB", "  5", "B2", "  1", "value", "  0", "SEQEND", "  5",
+                "B3",
+            ]
+            .join("\n")
+            .as_str(),
+        );
+        match drawing.get_item_by_handle(Handle(0xA2)) {
+            Some(DrawingItem::Vertex(v, _)) => assert_eq!(Point::new(1.0, 2.0, 0.0), v.location),
+            _ => panic!("expected a vertex"),
+        }
+        match drawing.get_item_by_handle(Handle(0xB2)) {
+            Some(DrawingItem::Attribute(a, _)) => assert_eq!("value", a.value),
+            _ => panic!("expected an attribute"),
+        }
+    }
+
+    #[test]
+    fn audit_new_drawing_is_clean() {
+        let drawing = Drawing::new();
+        assert_eq!(Vec::<AuditProblem>::new(), drawing.audit().problems);
+    }
+
+    #[test]
+    fn audit_r12_blocks_dont_need_block_records() {
+        // block records were introduced in R13
+        let mut drawing = Drawing::new();
+        assert_eq!(AcadVersion::R12, drawing.header.version);
+        drawing.add_block(Block {
+            name: String::from("b"),
+            ..Default::default()
+        });
+        assert!(!drawing.block_records().any(|b| b.name == "b"));
+        assert_eq!(Vec::<AuditProblem>::new(), drawing.audit().problems);
+    }
+
+    #[test]
+    fn audit_handles() {
+        let mut drawing = Drawing::new();
+        let first = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::default())))
+            .common
+            .handle;
+        drawing.add_entity(Entity::new(EntityType::Circle(Circle::default())));
+        for entity in drawing.entities_mut() {
+            entity.common.handle = first;
+        }
+        drawing.header.next_available_handle = first;
+
+        let report = drawing.audit();
+        let kinds = report
+            .problems
+            .iter()
+            .map(|p| (p.handle, p.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (first, AuditProblemKind::HandleNotBelowSeed),
+                (first, AuditProblemKind::DuplicateHandle),
+                (first, AuditProblemKind::HandleNotBelowSeed),
+            ],
+            kinds
+        );
+
+        let report = drawing.audit_and_fix();
+        assert_eq!(0, report.unfixed().count());
+        let handles = drawing
+            .entities()
+            .map(|e| e.common.handle)
+            .collect::<Vec<_>>();
+        assert_eq!(first, handles[0]);
+        assert!(handles[1].0 > first.0);
+        assert!(drawing.header.next_available_handle.0 > handles[1].0);
+        assert!(drawing.audit().is_clean());
+    }
+
+    #[test]
+    fn audit_dangling_pointers() {
+        let mut drawing = Drawing::new();
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.__owner_handle = Handle(0x7777);
+        drawing.add_entity(line);
+        let mut dict = Dictionary::default();
+        dict.value_handles
+            .insert(String::from("missing"), Handle(0x8888));
+        drawing.add_object(Object::new(ObjectType::Dictionary(dict)));
+
+        let report = drawing.audit_and_fix();
+        let kinds = report.problems.iter().map(|p| &p.kind).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                &AuditProblemKind::DanglingPointer {
+                    name: String::from("owner"),
+                    pointer: Handle(0x7777),
+                },
+                &AuditProblemKind::DanglingPointer {
+                    name: String::from("dictionary value"),
+                    pointer: Handle(0x8888),
+                },
+            ],
+            kinds
+        );
+        assert!(drawing.audit().is_clean());
+        let entity = drawing.entities().next().unwrap();
+        assert_eq!(Handle::empty(), entity.common.__owner_handle);
+        match drawing.objects().last().unwrap().specific {
+            ObjectType::Dictionary(ref d) => assert!(d.value_handles.is_empty()),
+            _ => panic!("expected a dictionary"),
+        }
+    }
+
+    #[test]
+    fn audit_missing_references_and_duplicate_names() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_entity(Entity {
+            common: EntityCommon {
+                layer: String::from("walls"),
+                ..Default::default()
+            },
+            specific: EntityType::Text(Text {
+                text_style_name: String::from("fancy"),
+                ..Default::default()
+            }),
+        });
+        let index = drawing.layers().position(|l| l.name == "walls").unwrap();
+        drawing.remove_layer(index);
+        let index = drawing.styles().position(|s| s.name == "fancy").unwrap();
+        drawing.remove_style(index);
+        drawing.add_block(Block {
+            name: String::from("b"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("ZERO"),
+            ..Default::default()
+        });
+        for layer in drawing.layers_mut() {
+            if layer.name == "ZERO" {
+                layer.name = String::from("0");
+            }
+        }
+
+        let report = drawing.audit_and_fix();
+        let mut kinds = report
+            .problems
+            .iter()
+            .map(|p| p.kind.to_string())
+            .collect::<Vec<_>>();
+        kinds.sort();
+        assert_eq!(
+            vec![
+                "duplicate LAYER name '0'",
+                "missing block record 'b'",
+                "missing layer 'walls'",
+                "missing text style 'fancy'",
+            ],
+            kinds
+        );
+        assert!(drawing.audit().is_clean());
+        assert!(drawing.layers().any(|l| l.name == "walls"));
+        assert!(drawing.layers().any(|l| l.name == "0$1"));
+        assert!(drawing.styles().any(|s| s.name == "fancy"));
+        assert!(drawing.block_records().any(|b| b.name == "b"));
+    }
+
+    #[test]
+    fn convert_to_r12() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2018;
+        let mut poly = LwPolyline::default();
+        poly.vertices.push(LwPolylineVertex {
+            bulge: 1.0,
+            ..Default::default()
+        });
+        poly.vertices.push(LwPolylineVertex {
+            x: 2.0,
+            ..Default::default()
+        });
+        let mut common = EntityCommon::default();
+        common.set_true_color(Some(TrueColor::from_rgb(255, 0, 0)));
+        drawing.add_entity(Entity {
+            common,
+            specific: EntityType::LwPolyline(poly),
+        });
+        drawing.add_entity(Entity::new(EntityType::Ellipse(Ellipse {
+            major_axis: Vector::new(2.0, 0.0, 0.0),
+            minor_axis_ratio: 0.5,
+            end_parameter: std::f64::consts::PI * 2.0,
+            ..Default::default()
+        })));
+        drawing.add_entity(Entity::new(EntityType::MText(MText {
+            text: String::from("one\\Ptwo"),
+            ..Default::default()
+        })));
+        drawing.add_entity(Entity::new(EntityType::Light(Light::default())));
+
+        drawing.convert_to(AcadVersion::R12);
+        assert_eq!(AcadVersion::R12, drawing.header.version);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(4, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => {
+                let vertices = poly.vertices().collect::<Vec<_>>();
+                assert_eq!(2, vertices.len());
+                assert_eq!(1.0, vertices[0].bulge);
+            }
+            _ => panic!("expected a polyline"),
+        }
+        assert_eq!(None, entities[0].common.get_true_color());
+        assert_eq!(Some(1), entities[0].common.color.index());
+        match entities[1].specific {
+            EntityType::Polyline(ref poly) => {
+                assert!(poly.get_is_closed());
+                assert!(poly.vertices().count() > 10);
+            }
+            _ => panic!("expected a polyline"),
+        }
+        let texts = entities[2..]
+            .iter()
+            .map(|e| match e.specific {
+                EntityType::Text(ref t) => t.value.as_str(),
+                _ => panic!("expected text"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["one", "two"], texts);
+        assert_eq!(0, drawing.objects().count());
+        assert_eq!(0, drawing.block_records().count());
+        assert!(drawing.audit().is_clean());
+    }
+
+    #[test]
+    fn save_with_downgrade_writes_valid_r12() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R12;
+        drawing.add_entity(Entity::new(EntityType::LwPolyline(LwPolyline::default())));
+        let mut buf = vec![];
+        drawing
+            .save_with_options(&mut buf, &SaveOptions { downgrade: true })
+            .unwrap();
+        let contents = String::from_utf8(buf).unwrap();
+        assert!(!contents.contains("AcDbEntity"));
+        assert!(!contents.contains("BLOCK_RECORD"));
+        assert!(!contents.contains("OBJECTS"));
+        assert!(contents.contains("POLYLINE"));
+        assert!(!contents.contains("LWPOLYLINE"));
+        // R12 readers expect line types before the layers that use them
+        let table_position = |contents: &str, name: &str| {
+            contents
+                .find(&format!("TABLE\r\n  2\r\n{}\r\n", name))
+                .unwrap()
+        };
+        assert!(table_position(&contents, "LTYPE") < table_position(&contents, "LAYER"));
+
+        // the original drawing isn't changed
+        assert!(matches!(
+            drawing.entities().next().unwrap().specific,
+            EntityType::LwPolyline(_)
+        ));
+
+        let drawing = Drawing::load(&mut contents.as_bytes()).unwrap();
+        assert!(drawing.audit().is_clean());
+    }
+
+    #[test]
+    fn downgrade_drops_unknown_entities_and_objects() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2018;
+        drawing.add_entity(Entity::new(EntityType::Unknown {
+            type_string: String::from("SURFACE"),
+            pairs: vec![CodePair::new_str(100, "AcDbModelerGeometry")],
+        }));
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        drawing.add_object(Object::new(ObjectType::Unknown {
+            type_string: String::from("ACDBPLACEHOLDER"),
+            pairs: vec![],
+        }));
+        drawing.header.version = AcadVersion::R12;
+
+        let mut buf = vec![];
+        drawing
+            .save_with_options(&mut buf, &SaveOptions { downgrade: true })
+            .unwrap();
+        let contents = String::from_utf8(buf).unwrap();
+        assert!(!contents.contains("SURFACE"));
+        assert!(!contents.contains("AcDbModelerGeometry"));
+        assert!(!contents.contains("ACDBPLACEHOLDER"));
+        assert!(contents.contains("LINE"));
+
+        drawing.convert_to(AcadVersion::R12);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        assert!(matches!(entities[0].specific, EntityType::Line(_)));
+    }
+
+    #[test]
+    fn round_trip_default_drawing_is_clean() {
+        let drawing = Drawing::new();
+        let mut buf = vec![];
+        drawing.save(&mut buf).unwrap();
+        let drawing = Drawing::load(&mut buf.as_slice()).unwrap();
+        assert!(drawing.audit().is_clean());
+    }
+
+    #[test]
+    fn purge_unreferenced_items() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_line_type(LineType {
+            name: String::from("dashed"),
+            ..Default::default()
+        });
+        drawing.add_line_type(LineType {
+            name: String::from("unused-line-type"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("used-layer"),
+            line_type_name: String::from("dashed"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("block-layer"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("unused-layer"),
+            ..Default::default()
+        });
+        drawing.add_style(Style {
+            name: String::from("unused-style"),
+            ..Default::default()
+        });
+        drawing.add_block(Block {
+            name: String::from("inner"),
+            entities: vec![Entity {
+                common: EntityCommon {
+                    layer: String::from("block-layer"),
+                    ..Default::default()
+                },
+                specific: EntityType::Line(Line::default()),
+            }],
+            ..Default::default()
+        });
+        drawing.add_block(Block {
+            name: String::from("outer"),
+            entities: vec![Entity::new(EntityType::Insert(Insert {
+                name: String::from("inner"),
+                ..Default::default()
+            }))],
+            ..Default::default()
+        });
+        drawing.add_block(Block {
+            name: String::from("unused-block"),
+            ..Default::default()
+        });
+        drawing.add_entity(Entity {
+            common: EntityCommon {
+                layer: String::from("used-layer"),
+                ..Default::default()
+            },
+            specific: EntityType::Insert(Insert {
+                name: String::from("outer"),
+                ..Default::default()
+            }),
+        });
+        drawing.audit_and_fix();
+        assert!(drawing.block_records().any(|b| b.name == "unused-block"));
+
+        let report = drawing.purge(PurgeOptions::default());
+        assert_eq!(vec!["unused-layer"], report.layers);
+        assert_eq!(vec!["unused-line-type"], report.line_types);
+        assert!(report.text_styles.contains(&String::from("unused-style")));
+        assert!(!report.text_styles.contains(&String::from("STANDARD")));
+        assert_eq!(vec!["unused-block"], report.blocks);
+        assert!(drawing.layers().any(|l| l.name == "block-layer"));
+        assert!(drawing.line_types().any(|l| l.name == "CONTINUOUS"));
+        assert!(drawing.dim_styles().any(|d| d.name == "STANDARD"));
+        assert!(!drawing.block_records().any(|b| b.name == "unused-block"));
+        assert!(drawing.audit().is_clean());
+
+        // removing the reference lets the nested blocks and their layers go
+        drawing.remove_entity(0);
+        let report = drawing.purge(PurgeOptions::default());
+        let mut blocks = report.blocks.clone();
+        blocks.sort();
+        assert_eq!(vec!["inner", "outer"], blocks);
+        let mut layers = report.layers.clone();
+        layers.sort();
+        assert_eq!(vec!["block-layer", "used-layer"], layers);
+        assert_eq!(vec!["dashed"], report.line_types);
+        assert!(drawing.purge(PurgeOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn purge_keeps_items_referenced_by_handle() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2010;
+        let line_type_handle = drawing
+            .add_line_type(LineType {
+                name: String::from("leader-line-type"),
+                ..Default::default()
+            })
+            .handle;
+        let mut block_record_handles = HashMap::new();
+        for name in ["content", "arrow", "style-content", "unused-block"] {
+            drawing.add_block(Block {
+                name: String::from(name),
+                ..Default::default()
+            });
+            let block_record = drawing.add_block_record(BlockRecord {
+                name: String::from(name),
+                ..Default::default()
+            });
+            block_record_handles.insert(name, block_record.handle);
+        }
+        drawing.add_entity(Entity::new(EntityType::MLeader(MLeader {
+            __leader_line_type_handle: line_type_handle,
+            __arrowhead_handle: block_record_handles["arrow"],
+            __block_content_handle: block_record_handles["content"],
+            ..Default::default()
+        })));
+        drawing.add_object(Object::new(ObjectType::MLeaderStyle(MLeaderStyle {
+            __block_content_handle: block_record_handles["style-content"],
+            ..Default::default()
+        })));
+
+        let report = drawing.purge(PurgeOptions::default());
+        assert_eq!(vec!["unused-block"], report.blocks);
+        assert!(report.line_types.is_empty());
+        for name in ["content", "arrow", "style-content"] {
+            assert!(drawing.blocks().any(|b| b.name == name));
+            assert!(drawing.block_records().any(|b| b.name == name));
+        }
+        assert!(drawing.line_types().any(|l| l.name == "leader-line-type"));
+
+        // only the style's block is still referenced once the leader is gone
+        drawing.remove_entity(0);
+        let report = drawing.purge(PurgeOptions::default());
+        let mut blocks = report.blocks.clone();
+        blocks.sort();
+        assert_eq!(vec!["arrow", "content"], blocks);
+        assert_eq!(vec!["leader-line-type"], report.line_types);
+        assert!(drawing.blocks().any(|b| b.name == "style-content"));
+    }
+
+    #[test]
+    fn purge_keeps_dimension_line_types_from_the_header() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2007;
+        for name in ["center", "hidden", "phantom", "unused-line-type"] {
+            drawing.add_line_type(LineType {
+                name: String::from(name),
+                ..Default::default()
+            });
+        }
+        drawing.header.dimension_line_type = String::from("center");
+        drawing.header.dimension_first_extension_line_type = String::from("hidden");
+        drawing.header.dimension_second_extension_line_type = String::from("PHANTOM");
+
+        let report = drawing.purge(PurgeOptions::default());
+        assert_eq!(vec!["unused-line-type"], report.line_types);
+    }
+
+    #[test]
+    fn purge_keeps_layers_referenced_from_x_data() {
+        let mut drawing = Drawing::new();
+        for name in ["x-data-layer", "unused-layer"] {
+            drawing.add_layer(Layer {
+                name: String::from(name),
+                ..Default::default()
+            });
+        }
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.x_data.push(XData {
+            application_name: String::from("ACAD"),
+            items: vec![XDataItem::ControlGroup(vec![XDataItem::LayerName(
+                String::from("X-DATA-LAYER"),
+            )])],
+        });
+        drawing.add_entity(line);
+
+        let report = drawing.purge(PurgeOptions::default());
+        assert_eq!(vec!["unused-layer"], report.layers);
+        assert!(drawing.layers().any(|l| l.name == "x-data-layer"));
+    }
+
+    #[test]
+    fn purge_keeps_shape_files_used_by_shapes() {
+        let mut drawing = Drawing::new();
+        drawing.add_style(Style {
+            name: String::new(),
+            primary_font_file_name: String::from("ltypeshp.shx"),
+            ..Default::default()
+        });
+        drawing.add_entity(Entity::new(EntityType::Shape(Shape {
+            name: String::from("BOX"),
+            ..Default::default()
+        })));
+        let report = drawing.purge(PurgeOptions::default());
+        assert!(!report.text_styles.contains(&String::new()));
+        assert!(drawing
+            .styles()
+            .any(|s| s.primary_font_file_name == "ltypeshp.shx"));
+
+        // without shapes the shape file isn't needed
+        drawing.remove_entity(0);
+        let report = drawing.purge(PurgeOptions::default());
+        assert_eq!(vec![""], report.text_styles);
+        assert!(!drawing.styles().any(|s| s.name.is_empty()));
+    }
+
+    #[test]
+    fn purge_respects_options() {
+        let mut drawing = Drawing::new();
+        drawing.add_layer(Layer {
+            name: String::from("unused-layer"),
+            ..Default::default()
+        });
+        drawing.add_object(Object::new(ObjectType::MLineStyle(MLineStyle {
+            style_name: String::from("unused-mline-style"),
+            ..Default::default()
+        })));
+        let options = PurgeOptions {
+            layers: false,
+            ..Default::default()
+        };
+        let report = drawing.purge(options);
+        assert!(report.layers.is_empty());
+        assert_eq!(vec!["unused-mline-style"], report.mline_styles);
+        assert!(drawing.layers().any(|l| l.name == "unused-layer"));
+        assert!(!drawing
+            .objects()
+            .any(|o| matches!(o.specific, ObjectType::MLineStyle(_))));
+    }
+
+    #[test]
+    fn rename_layer_updates_references() {
+        let mut drawing = Drawing::new();
+        drawing.header.current_layer = String::from("walls");
+        drawing.add_layer(Layer {
+            name: String::from("walls"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("doors"),
+            ..Default::default()
+        });
+        let mut common = EntityCommon {
+            layer: String::from("WALLS"),
+            ..Default::default()
+        };
+        common.x_data.push(XData {
+            application_name: String::from("ACAD"),
+            items: vec![XDataItem::ControlGroup(vec![XDataItem::LayerName(
+                String::from("walls"),
+            )])],
+        });
+        drawing.add_block(Block {
+            name: String::from("b"),
+            layer: String::from("walls"),
+            entities: vec![Entity {
+                common: common.clone(),
+                specific: EntityType::Line(Line::default()),
+            }],
+            ..Default::default()
+        });
+        drawing.add_entity(Entity {
+            common,
+            specific: EntityType::Line(Line::default()),
+        });
+
+        assert!(!drawing.rename_layer("missing", "new"));
+        assert!(!drawing.rename_layer("walls", "DOORS"));
+        assert!(drawing.rename_layer("Walls", "partitions"));
+        assert_eq!("partitions", drawing.header.current_layer);
+        assert!(drawing.layers().any(|l| l.name == "partitions"));
+        assert!(!drawing.layers().any(|l| l.name == "walls"));
+        let block = drawing.blocks().find(|b| b.name == "b").unwrap();
+        assert_eq!("partitions", block.layer);
+        for entity in drawing.entities().chain(block.entities.iter()) {
+            assert_eq!("partitions", entity.common.layer);
+            assert_eq!(
+                XDataItem::ControlGroup(vec![XDataItem::LayerName(String::from("partitions"))]),
+                entity.common.x_data[0].items[0]
+            );
+        }
+    }
+
+    #[test]
+    fn rename_table_entries_updates_references() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_line_type(LineType {
+            name: String::from("dashed"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("walls"),
+            line_type_name: String::from("dashed"),
+            ..Default::default()
+        });
+        drawing.add_block(Block {
+            name: String::from("door"),
+            entities: vec![Entity::new(EntityType::Text(Text {
+                text_style_name: String::from("STANDARD"),
+                ..Default::default()
+            }))],
+            ..Default::default()
+        });
+        for name in ["*Model_Space", "*Paper_Space"] {
+            drawing.add_block(Block {
+                name: String::from(name),
+                ..Default::default()
+            });
+        }
+        drawing.add_entity(Entity::new(EntityType::Insert(Insert {
+            name: String::from("door"),
+            ..Default::default()
+        })));
+        drawing.add_entity(Entity::new(EntityType::RotatedDimension(
+            RotatedDimension {
+                dimension_base: DimensionBase {
+                    dimension_style_name: String::from("STANDARD"),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )));
+        drawing.audit_and_fix();
+
+        assert!(!drawing.rename_layer("0", "base"));
+        for name in ["CONTINUOUS", "ByLayer", "byblock"] {
+            assert!(drawing
+                .line_types()
+                .any(|l| l.name.eq_ignore_ascii_case(name)));
+            assert!(!drawing.rename_line_type(name, "solid"));
+        }
+        for name in ["*Model_Space", "*PAPER_SPACE"] {
+            assert!(drawing.blocks().any(|b| b.name.eq_ignore_ascii_case(name)));
+            assert!(!drawing.rename_block(name, "space"));
+        }
+
+        assert!(drawing.rename_line_type("dashed", "hidden"));
+        assert!(drawing.rename_text_style("standard", "Standard"));
+        assert!(drawing.rename_dim_style("STANDARD", "arch"));
+        assert!(drawing.rename_block("door", "single-door"));
+
+        let layer = drawing.layers().find(|l| l.name == "walls").unwrap();
+        assert_eq!("hidden", layer.line_type_name);
+        assert_eq!("Standard", drawing.header.text_style);
+        assert_eq!("arch", drawing.header.dimension_style_name);
+        let block = drawing.blocks().find(|b| b.name == "single-door").unwrap();
+        match block.entities[0].specific {
+            EntityType::Text(ref t) => assert_eq!("Standard", t.text_style_name),
+            _ => panic!("expected text"),
+        }
+        assert!(drawing.block_records().any(|b| b.name == "single-door"));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        match entities[0].specific {
+            EntityType::Insert(ref i) => assert_eq!("single-door", i.name),
+            _ => panic!("expected an insert"),
+        }
+        match entities[1].specific {
+            EntityType::RotatedDimension(ref d) => {
+                assert_eq!("arch", d.dimension_base.dimension_style_name)
+            }
+            _ => panic!("expected a dimension"),
+        }
+        assert!(drawing.audit().is_clean());
+    }
 }
@@ -4,8 +4,11 @@ use crate::tables::*;
 use crate::{Block, Handle};
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DrawingItem<'a> {
     AppId(&'a AppId),
+    /// An attribute of an `Insert` entity and its handle.
+    Attribute(&'a Attribute, Handle),
     Block(&'a Block),
     BlockRecord(&'a BlockRecord),
     DimStyle(&'a DimStyle),
@@ -16,6 +19,8 @@ pub enum DrawingItem<'a> {
     Style(&'a Style),
     Ucs(&'a Ucs),
     View(&'a View),
+    /// A vertex of a `Polyline` entity and its handle.
+    Vertex(&'a Vertex, Handle),
     ViewPort(&'a ViewPort),
 }
 
@@ -23,6 +28,7 @@ impl<'a> DrawingItem<'a> {
     pub fn get_handle(&self) -> Handle {
         match self {
             DrawingItem::AppId(ref app_id) => app_id.handle,
+            DrawingItem::Attribute(_, handle) => *handle,
             DrawingItem::Block(ref b) => b.handle,
             DrawingItem::BlockRecord(ref br) => br.handle,
             DrawingItem::DimStyle(ref ds) => ds.handle,
@@ -33,13 +39,17 @@ impl<'a> DrawingItem<'a> {
             DrawingItem::Style(ref s) => s.handle,
             DrawingItem::Ucs(ref u) => u.handle,
             DrawingItem::View(ref v) => v.handle,
+            DrawingItem::Vertex(_, handle) => *handle,
             DrawingItem::ViewPort(ref v) => v.handle,
         }
     }
 }
 
+#[non_exhaustive]
 pub enum DrawingItemMut<'a> {
     AppId(&'a mut AppId),
+    /// An attribute of an `Insert` entity and its handle.
+    Attribute(&'a mut Attribute, &'a mut Handle),
     Block(&'a mut Block),
     BlockRecord(&'a mut BlockRecord),
     DimStyle(&'a mut DimStyle),
@@ -50,6 +60,8 @@ pub enum DrawingItemMut<'a> {
     Style(&'a mut Style),
     Ucs(&'a mut Ucs),
     View(&'a mut View),
+    /// A vertex of a `Polyline` entity and its handle.
+    Vertex(&'a mut Vertex, &'a mut Handle),
     ViewPort(&'a mut ViewPort),
 }
 
@@ -57,6 +69,7 @@ impl<'a> DrawingItemMut<'a> {
     pub fn get_handle(&self) -> Handle {
         match self {
             DrawingItemMut::AppId(ref app_id) => app_id.handle,
+            DrawingItemMut::Attribute(_, ref handle) => **handle,
             DrawingItemMut::Block(ref b) => b.handle,
             DrawingItemMut::BlockRecord(ref br) => br.handle,
             DrawingItemMut::DimStyle(ref ds) => ds.handle,
@@ -67,12 +80,14 @@ impl<'a> DrawingItemMut<'a> {
             DrawingItemMut::Style(ref s) => s.handle,
             DrawingItemMut::Ucs(ref u) => u.handle,
             DrawingItemMut::View(ref v) => v.handle,
+            DrawingItemMut::Vertex(_, ref handle) => **handle,
             DrawingItemMut::ViewPort(ref v) => v.handle,
         }
     }
     pub fn set_handle(&mut self, handle: Handle) {
         match self {
             DrawingItemMut::AppId(ref mut app_id) => app_id.handle = handle,
+            DrawingItemMut::Attribute(_, ref mut h) => **h = handle,
             DrawingItemMut::Block(ref mut b) => b.handle = handle,
             DrawingItemMut::BlockRecord(ref mut br) => br.handle = handle,
             DrawingItemMut::DimStyle(ref mut ds) => ds.handle = handle,
@@ -83,12 +98,14 @@ impl<'a> DrawingItemMut<'a> {
             DrawingItemMut::Style(ref mut s) => s.handle = handle,
             DrawingItemMut::Ucs(ref mut u) => u.handle = handle,
             DrawingItemMut::View(ref mut v) => v.handle = handle,
+            DrawingItemMut::Vertex(_, ref mut h) => **h = handle,
             DrawingItemMut::ViewPort(ref mut v) => v.handle = handle,
         }
     }
     pub fn to_drawing_item(&self) -> DrawingItem {
         match self {
             DrawingItemMut::AppId(ref app_id) => DrawingItem::AppId(app_id),
+            DrawingItemMut::Attribute(ref a, ref handle) => DrawingItem::Attribute(a, **handle),
             DrawingItemMut::Block(ref b) => DrawingItem::Block(b),
             DrawingItemMut::BlockRecord(ref br) => DrawingItem::BlockRecord(br),
             DrawingItemMut::DimStyle(ref ds) => DrawingItem::DimStyle(ds),
@@ -99,7 +116,97 @@ impl<'a> DrawingItemMut<'a> {
             DrawingItemMut::Style(ref s) => DrawingItem::Style(s),
             DrawingItemMut::Ucs(ref u) => DrawingItem::Ucs(u),
             DrawingItemMut::View(ref v) => DrawingItem::View(v),
+            DrawingItemMut::Vertex(ref v, ref handle) => DrawingItem::Vertex(v, **handle),
             DrawingItemMut::ViewPort(ref v) => DrawingItem::ViewPort(v),
         }
     }
 }
+
+/// The collections of a `Drawing` that contain items with handles.  Blocks include their
+/// entities and entities include their attributes and vertices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ItemCollection {
+    AppIds,
+    Blocks,
+    BlockRecords,
+    DimStyles,
+    Entities,
+    Layers,
+    LineTypes,
+    Objects,
+    Styles,
+    Ucss,
+    Views,
+    ViewPorts,
+}
+
+impl ItemCollection {
+    /// All collections in the order they're searched for a handle.
+    pub(crate) const ALL: [ItemCollection; 12] = [
+        ItemCollection::AppIds,
+        ItemCollection::Blocks,
+        ItemCollection::BlockRecords,
+        ItemCollection::DimStyles,
+        ItemCollection::Entities,
+        ItemCollection::Layers,
+        ItemCollection::LineTypes,
+        ItemCollection::Objects,
+        ItemCollection::Styles,
+        ItemCollection::Ucss,
+        ItemCollection::Views,
+        ItemCollection::ViewPorts,
+    ];
+}
+
+/// The position of an entity in either the drawing's entities or the entities of a block.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct EntityLocation {
+    pub block: Option<usize>,
+    pub index: usize,
+}
+
+/// Where an item with a handle is stored in a `Drawing`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum ItemLocation {
+    AppId(usize),
+    Attribute(EntityLocation, usize),
+    Block(usize),
+    BlockRecord(usize),
+    DimStyle(usize),
+    Entity(EntityLocation),
+    Layer(usize),
+    LineType(usize),
+    Object(usize),
+    Style(usize),
+    Ucs(usize),
+    Vertex(EntityLocation, usize),
+    View(usize),
+    ViewPort(usize),
+}
+
+impl ItemLocation {
+    /// The collection containing the item and the index of the top-level item of that
+    /// collection that is or contains it.
+    pub(crate) fn position(&self) -> (ItemCollection, usize) {
+        let entity_position = |location: &EntityLocation| match location.block {
+            Some(block) => (ItemCollection::Blocks, block),
+            None => (ItemCollection::Entities, location.index),
+        };
+        match self {
+            ItemLocation::AppId(i) => (ItemCollection::AppIds, *i),
+            ItemLocation::Attribute(ref location, _) => entity_position(location),
+            ItemLocation::Block(i) => (ItemCollection::Blocks, *i),
+            ItemLocation::BlockRecord(i) => (ItemCollection::BlockRecords, *i),
+            ItemLocation::DimStyle(i) => (ItemCollection::DimStyles, *i),
+            ItemLocation::Entity(ref location) => entity_position(location),
+            ItemLocation::Layer(i) => (ItemCollection::Layers, *i),
+            ItemLocation::LineType(i) => (ItemCollection::LineTypes, *i),
+            ItemLocation::Object(i) => (ItemCollection::Objects, *i),
+            ItemLocation::Style(i) => (ItemCollection::Styles, *i),
+            ItemLocation::Ucs(i) => (ItemCollection::Ucss, *i),
+            ItemLocation::Vertex(ref location, _) => entity_position(location),
+            ItemLocation::View(i) => (ItemCollection::Views, *i),
+            ItemLocation::ViewPort(i) => (ItemCollection::ViewPorts, *i),
+        }
+    }
+}
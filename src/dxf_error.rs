@@ -93,6 +93,37 @@ impl fmt::Display for DxfError {
     }
 }
 
+// internal visibility only
+impl DxfError {
+    /// Returns the line/offset the error occurred at, if known.
+    pub(crate) fn offset(&self) -> Option<usize> {
+        match *self {
+            DxfError::ParseFloatError(_, o)
+            | DxfError::ParseIntError(_, o)
+            | DxfError::ParseError(o)
+            | DxfError::UnexpectedCode(_, o)
+            | DxfError::UnexpectedByte(_, o)
+            | DxfError::UnexpectedEnumValue(o)
+            | DxfError::ExpectedTableType(o)
+            | DxfError::WrongValueType(o) => Some(o),
+            DxfError::UnexpectedCodePair(ref cp, _) => Some(cp.offset),
+            _ => None,
+        }
+    }
+    /// Returns `true` if the error only affects a single code pair value and reading can
+    /// continue with the next code pair.
+    pub(crate) fn is_value_error(&self) -> bool {
+        matches!(
+            *self,
+            DxfError::ParseFloatError(..)
+                | DxfError::ParseIntError(..)
+                | DxfError::ParseError(_)
+                | DxfError::UnexpectedEnumValue(_)
+                | DxfError::MalformedString
+        )
+    }
+}
+
 impl error::Error for DxfError {
     fn cause(&self) -> Option<&dyn error::Error> {
         match *self {
@@ -0,0 +1,290 @@
+use std::io::Read;
+
+use crate::code_pair_iter::CodePairIter;
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::entities::Entity;
+use crate::entity_iter::{CollapsedEntityIter, EntityIter};
+use crate::helper_functions::*;
+use crate::{CodePair, CodePairValue, DxfError, DxfResult, Header};
+
+/// Reads the entities of a DXF file one at a time without building a full `Drawing`, e.g.,
+/// to filter very large files in constant memory.
+///
+/// ``` rust
+/// # use dxf::DxfReader;
+/// # fn example() -> dxf::DxfResult<()> {
+/// let file = std::fs::File::open("path/to/file.dxf")?;
+/// let mut reader = DxfReader::open(std::io::BufReader::new(file))?;
+/// println!("version: {:?}", reader.header().version);
+/// for entity in reader.entities() {
+///     let entity = entity?;
+///     println!("layer: {}", entity.common.layer);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Only the `HEADER` and `ENTITIES` sections are read; all others are skipped.
+pub struct DxfReader<T: Read> {
+    iter: CodePairPutBack<T>,
+    header: Header,
+    at_entities: bool,
+}
+
+impl<T: Read> DxfReader<T> {
+    /// Opens a DXF file and reads everything up to the first entity.  The text encoding is
+    /// determined by the drawing's `$DWGCODEPAGE` header variable, falling back to Windows-1252.
+    pub fn open(mut reader: T) -> DxfResult<DxfReader<T>> {
+        let first_line = match read_line(&mut reader, true, encoding_rs::WINDOWS_1252) {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => return Err(e),
+            None => return Err(DxfError::UnexpectedEndOfInput),
+        };
+        let iter = CodePairIter::new(reader, encoding_rs::WINDOWS_1252, true, first_line);
+        let mut dxf_reader = DxfReader {
+            iter: CodePairPutBack::from_code_pair_iter(iter),
+            header: Header::default(),
+            at_entities: false,
+        };
+        dxf_reader.read_to_entities()?;
+        Ok(dxf_reader)
+    }
+    /// The drawing's header, or the default header if the file doesn't have a `HEADER` section
+    /// before its `ENTITIES` section.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+    /// Reads the entities one at a time.  `POLYLINE`/`VERTEX`/`SEQEND` and `INSERT`/`ATTRIB`/`SEQEND`
+    /// sequences are returned as single entities, just as with `Drawing::load`.  The entities
+    /// can only be iterated once.
+    pub fn entities(&mut self) -> impl Iterator<Item = DxfResult<Entity>> + '_ {
+        let done = !self.at_entities;
+        self.at_entities = false;
+        StreamingEntityIter {
+            entities: CollapsedEntityIter::new(EntityIter::new(&mut self.iter)),
+            done,
+        }
+    }
+}
+
+// private implementation
+impl<T: Read> DxfReader<T> {
+    fn read_to_entities(&mut self) -> DxfResult<()> {
+        loop {
+            match self.iter.next() {
+                Some(Ok(CodePair {
+                    code: 0,
+                    value: CodePairValue::Str(ref s),
+                    ..
+                })) if s == "EOF" => return Ok(()),
+                Some(Ok(CodePair {
+                    code: 0,
+                    value: CodePairValue::Str(ref s),
+                    ..
+                })) if s == "SECTION" => match self.iter.next() {
+                    Some(Ok(CodePair {
+                        code: 2,
+                        value: CodePairValue::Str(s),
+                        ..
+                    })) => match &*s {
+                        "HEADER" => {
+                            self.header = Header::read(&mut self.iter)?;
+                            self.skip_section()?;
+                        }
+                        "ENTITIES" => {
+                            self.at_entities = true;
+                            return Ok(());
+                        }
+                        _ => self.skip_section()?,
+                    },
+                    Some(Ok(pair)) => {
+                        return Err(DxfError::UnexpectedCodePair(
+                            pair,
+                            String::from("expected 2/<section-name>"),
+                        ))
+                    }
+                    Some(Err(e)) => return Err(e),
+                    None => return Err(DxfError::UnexpectedEndOfInput),
+                },
+                Some(Ok(pair)) => {
+                    return Err(DxfError::UnexpectedCodePair(
+                        pair,
+                        String::from("expected 0/SECTION or 0/EOF"),
+                    ))
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()), // ideally should have been 0/EOF
+            }
+        }
+    }
+    fn skip_section(&mut self) -> DxfResult<()> {
+        loop {
+            match self.iter.next() {
+                Some(Ok(CodePair {
+                    code: 0,
+                    value: CodePairValue::Str(ref s),
+                    ..
+                })) if s == "ENDSEC" => return Ok(()),
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            }
+        }
+    }
+}
+
+struct StreamingEntityIter<'a, T: 'a + Read> {
+    entities: CollapsedEntityIter<EntityIter<'a, T>>,
+    done: bool,
+}
+
+impl<'a, T: 'a + Read> Iterator for StreamingEntityIter<'a, T> {
+    type Item = DxfResult<Entity>;
+
+    fn next(&mut self) -> Option<DxfResult<Entity>> {
+        if self.done {
+            return None;
+        }
+
+        if let Some(entity) = self.entities.next() {
+            return Some(Ok(entity));
+        }
+
+        // either the section ended or an entity couldn't be read
+        self.done = true;
+        if let Some(e) = self.entities.iter.error.take() {
+            return Some(Err(e));
+        }
+
+        match self.entities.iter.iter.next() {
+            Some(Ok(CodePair {
+                code: 0,
+                value: CodePairValue::Str(ref s),
+                ..
+            })) if s == "ENDSEC" => None,
+            Some(Ok(pair)) => Some(Err(DxfError::UnexpectedCodePair(
+                pair,
+                String::from("expected 0/ENDSEC"),
+            ))),
+            Some(Err(e)) => Some(Err(e)),
+            None => Some(Err(DxfError::UnexpectedEndOfInput)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::*;
+    use std::io::Cursor;
+
+    fn open(lines: &[&str]) -> DxfReader<Cursor<Vec<u8>>> {
+        DxfReader::open(Cursor::new(lines.join("\r\n").into_bytes())).unwrap()
+    }
+
+    #[test]
+    fn read_header_and_entities() {
+        let mut reader = open(&[
+            "  0",
+            "SECTION",
+            "  2",
+            "HEADER",
+            "  9",
+            "$ACADVER",
+            "  1",
+            "AC1015",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "TABLES",
+            "  0",
+            "TABLE",
+            "  2",
+            "LAYER",
+            "  0",
+            "LAYER",
+            "  2",
+            "some-layer",
+            "  0",
+            "ENDTAB",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "ENTITIES",
+            "  0",
+            "LINE",
+            "  8",
+            "some-layer",
+            "  0",
+            "POLYLINE",
+            " 66",
+            "     1",
+            "  0",
+            "VERTEX",
+            "  0",
+            "VERTEX",
+            "  0",
+            "SEQEND",
+            "  0",
+            "CIRCLE",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "EOF",
+        ]);
+        assert_eq!(AcadVersion::R2000, reader.header().version);
+        let entities = reader.entities().collect::<DxfResult<Vec<_>>>().unwrap();
+        assert_eq!(3, entities.len());
+        assert_eq!("some-layer", entities[0].common.layer);
+        match entities[1].specific {
+            EntityType::Polyline(ref poly) => assert_eq!(2, poly.vertices().count()),
+            _ => panic!("expected a polyline"),
+        }
+        match entities[2].specific {
+            EntityType::Circle(_) => (),
+            _ => panic!("expected a circle"),
+        }
+
+        // entities can only be read once
+        assert_eq!(0, reader.entities().count());
+    }
+
+    #[test]
+    fn read_without_entities_section() {
+        let mut reader = open(&[
+            "  0", "SECTION", "  2", "HEADER", "  9", "$ACADVER", "  1", "AC1015", "  0", "ENDSEC",
+            "  0", "EOF",
+        ]);
+        assert_eq!(AcadVersion::R2000, reader.header().version);
+        assert_eq!(0, reader.entities().count());
+    }
+
+    #[test]
+    fn read_entity_with_error() {
+        let mut reader = open(&[
+            "  0",
+            "SECTION",
+            "  2",
+            "ENTITIES",
+            "  0",
+            "LINE",
+            "  0",
+            "LINE",
+            "330",
+            "not-a-handle",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "EOF",
+        ]);
+        let entities = reader.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        assert!(entities[0].is_ok());
+        assert!(entities[1].is_err());
+    }
+}
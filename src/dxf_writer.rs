@@ -0,0 +1,251 @@
+use std::borrow::Cow;
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::code_pair_writer::CodePairWriter;
+use crate::drawing::AUTO_REPLACE_HANDLE;
+use crate::entities::{Entity, EntityType};
+use crate::{CodePair, Drawing, DxfResult, Handle};
+
+// written in place of `$HANDSEED` and overwritten with the real value by `DxfWriter::finish`
+const HANDLE_SEED_PLACEHOLDER: Handle = Handle(0xFFFF_FFFF_FFFF_FFFF);
+
+/// Writes a DXF file incrementally so that entities don't have to be held in memory.
+///
+/// The header, classes, tables, and blocks of the given `Drawing` are written immediately,
+/// entities are written one at a time by `write_entity`, and the drawing's objects are written
+/// by `finish`, which must be called to complete the file.  Entities without a handle are
+/// assigned one starting at the drawing's `$HANDSEED` and the header's value is patched once the
+/// final value is known.
+///
+/// ``` rust
+/// # use dxf::*;
+/// # use dxf::entities::*;
+/// # fn example() -> DxfResult<()> {
+/// let mut file = std::fs::File::create("path/to/file.dxf")?;
+/// let mut writer = DxfWriter::new(&mut file, Drawing::new())?;
+/// for i in 0..1000 {
+///     let point = ModelPoint::new(Point::new(i as f64, 0.0, 0.0));
+///     writer.write_entity(&Entity::new(EntityType::ModelPoint(point)))?;
+/// }
+/// writer.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Any layers, line types, or text styles referenced by the entities must already be present
+/// in the drawing.
+pub struct DxfWriter<'a, T: Write + Seek + ?Sized> {
+    writer: CodePairWriter<'a, T>,
+    drawing: Drawing,
+    write_handles: bool,
+    handle_seed_position: Option<u64>,
+}
+
+impl<'a, T: Write + Seek + ?Sized> DxfWriter<'a, T> {
+    /// Creates an ASCII DXF writer and writes everything up to the first entity.
+    pub fn new(writer: &'a mut T, drawing: Drawing) -> DxfResult<Self> {
+        DxfWriter::new_internal(writer, drawing, true)
+    }
+    /// Creates a binary DXF writer and writes everything up to the first entity.
+    pub fn new_binary(writer: &'a mut T, drawing: Drawing) -> DxfResult<Self> {
+        DxfWriter::new_internal(writer, drawing, false)
+    }
+    /// Writes the entity and returns its handle.  An entity without a handle is assigned the next
+    /// one, as are its attributes or vertices; an existing handle is kept and must be unique.
+    pub fn write_entity(&mut self, entity: &Entity) -> DxfResult<Handle> {
+        let entity = if DxfWriter::<T>::needs_handles(entity) {
+            let mut entity = entity.clone();
+            if entity.common.handle.is_empty() {
+                entity.common.handle = self.drawing.next_handle();
+            }
+            self.drawing.assign_child_handles(&mut entity);
+            Cow::Owned(entity)
+        } else {
+            Cow::Borrowed(entity)
+        };
+        let handle = entity.common.handle;
+        if handle.0 >= self.drawing.header.next_available_handle.0 {
+            self.drawing.header.next_available_handle = handle.next_handle_value();
+        }
+        entity.write(
+            self.drawing.header.version,
+            self.write_handles,
+            &mut self.writer,
+        )?;
+        Ok(handle)
+    }
+    /// Writes the remaining sections and the final `$HANDSEED` value.
+    pub fn finish(mut self) -> DxfResult<()> {
+        self.writer
+            .write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
+        self.drawing
+            .write_extra_sections("ENTITIES", &mut self.writer)?;
+        self.drawing.write_objects(&mut self.writer)?;
+        self.drawing
+            .write_extra_sections("OBJECTS", &mut self.writer)?;
+        self.drawing.write_thumbnail(&mut self.writer)?;
+        self.drawing
+            .write_extra_sections("THUMBNAILIMAGE", &mut self.writer)?;
+        self.writer.write_code_pair(&CodePair::new_str(0, "EOF"))?;
+
+        if let Some(position) = self.handle_seed_position {
+            // the placeholder is 16 characters wide so the handle is zero-padded to fit
+            let handle_seed = format!("{:016X}", self.drawing.header.next_available_handle.0);
+            let writer = self.writer.get_mut();
+            let end = writer.stream_position()?;
+            writer.seek(SeekFrom::Start(position))?;
+            writer.write_all(handle_seed.as_bytes())?;
+            writer.seek(SeekFrom::Start(end))?;
+        }
+
+        self.writer.get_mut().flush()?;
+        Ok(())
+    }
+}
+
+// private implementation
+impl<'a, T: Write + Seek + ?Sized> DxfWriter<'a, T> {
+    fn needs_handles(entity: &Entity) -> bool {
+        entity.common.handle.is_empty()
+            || match entity.specific {
+                EntityType::Insert(ref ins) => ins
+                    .__attributes_and_handles
+                    .iter()
+                    .any(|a| a.1 == AUTO_REPLACE_HANDLE),
+                EntityType::Polyline(ref poly) => poly
+                    .__vertices_and_handles
+                    .iter()
+                    .any(|v| v.1 == AUTO_REPLACE_HANDLE),
+                _ => false,
+            }
+    }
+    fn new_internal(writer: &'a mut T, mut drawing: Drawing, as_ascii: bool) -> DxfResult<Self> {
+        let version = drawing.header.version;
+        let text_encoding = drawing.text_encoding();
+        let write_handles = drawing.write_handles();
+
+        // the leading sections are buffered so the location of `$HANDSEED` can be found
+        let mut buffer = vec![];
+        let header_start;
+        {
+            let handle_seed = drawing.header.next_available_handle;
+            drawing.header.next_available_handle = HANDLE_SEED_PLACEHOLDER;
+            let mut buffer_writer =
+                CodePairWriter::new(&mut buffer, as_ascii, text_encoding, version);
+            buffer_writer.write_prelude()?;
+            drawing.write_extra_sections("", &mut buffer_writer)?;
+            header_start = buffer_writer.get_mut().len();
+            let header_result = drawing.header.write(&mut buffer_writer);
+            drawing.header.next_available_handle = handle_seed;
+            header_result?;
+            drawing.write_extra_sections("HEADER", &mut buffer_writer)?;
+            drawing.write_classes(&mut buffer_writer)?;
+            drawing.write_extra_sections("CLASSES", &mut buffer_writer)?;
+            drawing.write_tables(write_handles, &mut buffer_writer)?;
+            drawing.write_extra_sections("TABLES", &mut buffer_writer)?;
+            drawing.write_blocks(write_handles, &mut buffer_writer)?;
+            drawing.write_extra_sections("BLOCKS", &mut buffer_writer)?;
+            buffer_writer.write_code_pair(&CodePair::new_str(0, "SECTION"))?;
+            buffer_writer.write_code_pair(&CodePair::new_str(2, "ENTITIES"))?;
+        }
+
+        let placeholder = HANDLE_SEED_PLACEHOLDER.as_string().into_bytes();
+        let start = writer.stream_position()?;
+        let handle_seed_position = buffer[header_start..]
+            .windows(placeholder.len())
+            .position(|w| w == &placeholder[..])
+            .map(|i| start + (header_start + i) as u64);
+        writer.write_all(&buffer)?;
+
+        Ok(DxfWriter {
+            writer: CodePairWriter::new(writer, as_ascii, text_encoding, version),
+            drawing,
+            write_handles,
+            handle_seed_position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+    use std::io::Cursor;
+
+    fn write_lines(as_ascii: bool) -> (Vec<u8>, Vec<Handle>) {
+        let mut buf = Cursor::new(vec![]);
+        let mut handles = vec![];
+        {
+            let mut writer = if as_ascii {
+                DxfWriter::new(&mut buf, Drawing::new()).unwrap()
+            } else {
+                DxfWriter::new_binary(&mut buf, Drawing::new()).unwrap()
+            };
+            for i in 0..3 {
+                let line = Line::new(Point::origin(), Point::new(f64::from(i), 1.0, 0.0));
+                handles.push(
+                    writer
+                        .write_entity(&Entity::new(EntityType::Line(line)))
+                        .unwrap(),
+                );
+            }
+            writer.finish().unwrap();
+        }
+        (buf.into_inner(), handles)
+    }
+
+    fn assert_round_trip(as_ascii: bool) {
+        let (bytes, handles) = write_lines(as_ascii);
+        let drawing = Drawing::load(&mut bytes.as_slice()).unwrap();
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(3, entities.len());
+        for (entity, handle) in entities.iter().zip(handles.iter()) {
+            assert_eq!(*handle, entity.common.handle);
+        }
+        match entities[2].specific {
+            EntityType::Line(ref line) => assert_eq!(Point::new(2.0, 1.0, 0.0), line.p2),
+            _ => panic!("expected a line"),
+        }
+
+        // the handle seed is past every handle written
+        assert!(drawing.header.next_available_handle.0 > handles[2].0);
+    }
+
+    #[test]
+    fn write_ascii_entities() {
+        assert_round_trip(true);
+    }
+
+    #[test]
+    fn write_binary_entities() {
+        assert_round_trip(false);
+    }
+
+    #[test]
+    fn existing_handles_are_kept() {
+        let mut buf = Cursor::new(vec![]);
+        let mut entity = Entity::new(EntityType::Line(Line::default()));
+        entity.common.handle = Handle(0x1000);
+        {
+            let mut writer = DxfWriter::new(&mut buf, Drawing::new()).unwrap();
+            assert_eq!(Handle(0x1000), writer.write_entity(&entity).unwrap());
+            let next = writer.write_entity(&Entity::new(EntityType::Line(Line::default())));
+            assert_eq!(Handle(0x1001), next.unwrap());
+            writer.finish().unwrap();
+        }
+        let drawing = Drawing::load(&mut buf.into_inner().as_slice()).unwrap();
+        assert!(drawing.get_item_by_handle(Handle(0x1000)).is_some());
+        assert_eq!(Handle(0x1002), drawing.header.next_available_handle);
+    }
+
+    #[test]
+    fn handle_seed_is_patched() {
+        let (bytes, handles) = write_lines(true);
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(!text.contains("FFFFFFFFFFFFFFFF"));
+        assert!(text.contains(&format!(
+            "$HANDSEED\r\n  5\r\n{:016X}\r\n",
+            handles[2].next_handle_value().0
+        )));
+    }
+}
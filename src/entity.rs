@@ -3,15 +3,28 @@
 use enum_primitive::FromPrimitive;
 use std::io::{Read, Write};
 
-use crate::{CodePair, Color, DxfError, DxfResult, Handle, Point, Vector};
+use crate::{
+    BoundingBox, CodePair, Color, DxfError, DxfResult, Handle, HatchBoundaryPath,
+    HatchPatternDefinitionLine, LineWeight, MLeaderContextData, Point, PolylineSegment, TableCell,
+    TableCellRange, TransformationMatrix, Transparency, TrueColor, Vector,
+};
 
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::code_pair_writer::CodePairWriter;
+use crate::drawing::AUTO_REPLACE_HANDLE;
 use crate::entities::*;
 use crate::enums::*;
 use crate::helper_functions::*;
+use crate::polyline_segment::*;
+use crate::x_data;
 use crate::Drawing;
 
+/// The number of samples taken per knot span when searching a spline for its closest point.
+const CLOSEST_POINT_SAMPLES_PER_SPAN: usize = 16;
+
+/// The maximum number of times an interval is halved when integrating a spline's length.
+const MAX_INTEGRAL_DEPTH: usize = 20;
+
 //------------------------------------------------------------------------------
 //                                                                           Arc
 //------------------------------------------------------------------------------
@@ -25,6 +38,27 @@ impl Arc {
             ..Default::default()
         }
     }
+    /// The center of the arc in world coordinates.
+    pub fn center_wcs(&self) -> Point {
+        self.center.ocs_to_wcs(&self.normal)
+    }
+    /// The start point of the arc in world coordinates.
+    pub fn start_point_wcs(&self) -> Point {
+        self.point_at_angle(self.start_angle)
+            .ocs_to_wcs(&self.normal)
+    }
+    /// The end point of the arc in world coordinates.
+    pub fn end_point_wcs(&self) -> Point {
+        self.point_at_angle(self.end_angle).ocs_to_wcs(&self.normal)
+    }
+    fn point_at_angle(&self, angle: f64) -> Point {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Point::new(
+            self.center.x + self.radius * cos,
+            self.center.y + self.radius * sin,
+            self.center.z,
+        )
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -38,6 +72,10 @@ impl Circle {
             ..Default::default()
         }
     }
+    /// The center of the circle in world coordinates.
+    pub fn center_wcs(&self) -> Point {
+        self.center.ocs_to_wcs(&self.normal)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -86,6 +124,18 @@ impl Face3D {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                            HatchGradientColor
+//------------------------------------------------------------------------------
+/// Represents a single color of a gradient `Hatch` fill.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchGradientColor {
+    pub value: f64,
+    pub color: Color,
+    pub color_24_bit: i32,
+}
+
 //------------------------------------------------------------------------------
 //                                                                        Insert
 //------------------------------------------------------------------------------
@@ -100,6 +150,10 @@ impl Insert {
         let att_handle = drawing.next_handle();
         self.__attributes_and_handles.push((att, att_handle));
     }
+    /// The insertion point in world coordinates.
+    pub fn location_wcs(&self) -> Point {
+        self.location.ocs_to_wcs(&self.extrusion_direction)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -115,6 +169,96 @@ impl Line {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                                    LwPolyline
+//------------------------------------------------------------------------------
+impl LwPolyline {
+    /// The vertices in world coordinates.  The vertices are stored in the object coordinate
+    /// system at the specified elevation, which is the entity's `common.elevation`.
+    pub fn vertices_wcs(&self, elevation: f64) -> Vec<Point> {
+        self.vertices
+            .iter()
+            .map(|v| Point::new(v.x, v.y, elevation).ocs_to_wcs(&self.extrusion_direction))
+            .collect()
+    }
+    /// The line and arc segments in the object coordinate system at zero elevation, including
+    /// the segment back to the first vertex if the polyline is closed.
+    pub fn segments(&self) -> impl Iterator<Item = PolylineSegment> {
+        let vertices = self
+            .vertices
+            .iter()
+            .map(|v| (Point::new(v.x, v.y, 0.0), v.bulge))
+            .collect();
+        segments_from_vertices(vertices, self.get_is_closed()).into_iter()
+    }
+    pub fn length(&self) -> f64 {
+        self.segments().map(|s| s.length()).sum()
+    }
+    /// The signed area enclosed by the polyline, positive if counter-clockwise when viewed
+    /// against the extrusion direction.  Open polylines are closed with a straight line.
+    pub fn area(&self) -> f64 {
+        segments_area(&self.segments().collect::<Vec<_>>())
+    }
+    pub fn is_clockwise(&self) -> bool {
+        self.area() < 0.0
+    }
+    /// Reverses the direction of the polyline without changing its shape.
+    pub fn reverse(&mut self) {
+        let reversed = reversed_vertex_order(self.vertices.len())
+            .map(|(i, segment)| {
+                let (v, s) = (&self.vertices[i], &self.vertices[segment]);
+                LwPolylineVertex {
+                    starting_width: s.ending_width,
+                    ending_width: s.starting_width,
+                    bulge: -s.bulge,
+                    ..*v
+                }
+            })
+            .collect();
+        self.vertices = reversed;
+    }
+    /// The point in the object coordinate system the specified distance along the polyline, or
+    /// `None` if the distance is negative or exceeds the length.
+    pub fn point_at_distance(&self, distance: f64) -> Option<Point> {
+        segments_point_at_distance(&self.segments().collect::<Vec<_>>(), distance)
+    }
+    /// Returns `true` if the point, in the object coordinate system, is inside the area
+    /// enclosed by the polyline.  Open polylines are closed with a straight line.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        segments_contain_point(&self.segments().collect::<Vec<_>>(), p)
+    }
+    /// Converts to the equivalent 2D `Polyline` at the specified elevation, which is the
+    /// entity's `common.elevation`.
+    pub fn to_polyline(&self, elevation: f64) -> Polyline {
+        let mut poly = Polyline {
+            location: Point::new(0.0, 0.0, elevation),
+            thickness: self.thickness,
+            default_starting_width: self.constant_width,
+            default_ending_width: self.constant_width,
+            normal: self.extrusion_direction.clone(),
+            ..Default::default()
+        };
+        poly.set_is_closed(self.get_is_closed());
+        poly.set_is_line_type_pattern_generated_continuously(self.get_is_pline_gen());
+        poly.__vertices_and_handles = self
+            .vertices
+            .iter()
+            .map(|v| {
+                let vertex = Vertex {
+                    location: Point::new(v.x, v.y, 0.0),
+                    starting_width: v.starting_width,
+                    ending_width: v.ending_width,
+                    bulge: v.bulge,
+                    identifier: v.id,
+                    ..Default::default()
+                };
+                (vertex, AUTO_REPLACE_HANDLE)
+            })
+            .collect();
+        poly
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                              LwPolylineVertex
 //------------------------------------------------------------------------------
@@ -130,6 +274,91 @@ pub struct LwPolylineVertex {
     pub bulge: f64,
 }
 
+//------------------------------------------------------------------------------
+//                                                      MLeaderArrowheadOverride
+//------------------------------------------------------------------------------
+/// Overrides the arrowhead of a single leader line of an `MLeader`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderArrowheadOverride {
+    pub leader_line_index: i32,
+    pub arrowhead_handle: Handle,
+}
+
+impl Default for MLeaderArrowheadOverride {
+    fn default() -> Self {
+        MLeaderArrowheadOverride {
+            leader_line_index: 0,
+            arrowhead_handle: Handle::empty(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                         MLeaderBlockAttribute
+//------------------------------------------------------------------------------
+/// The value of a single attribute of an `MLeader`'s block content.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderBlockAttribute {
+    pub attribute_definition_handle: Handle,
+    pub index: i16,
+    pub width: f64,
+    pub text: String,
+}
+
+impl Default for MLeaderBlockAttribute {
+    fn default() -> Self {
+        MLeaderBlockAttribute {
+            attribute_definition_handle: Handle::empty(),
+            index: 0,
+            width: 0.0,
+            text: String::new(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                      MeshEdge
+//------------------------------------------------------------------------------
+/// Represents a single edge of a `Mesh`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshEdge {
+    /// The index into the `Mesh`'s `vertices` of the edge's start.
+    pub start_vertex_index: usize,
+    /// The index into the `Mesh`'s `vertices` of the edge's end.
+    pub end_vertex_index: usize,
+    pub crease: f64,
+}
+
+impl MeshEdge {
+    pub fn new(start_vertex_index: usize, end_vertex_index: usize) -> Self {
+        MeshEdge {
+            start_vertex_index,
+            end_vertex_index,
+            ..Default::default()
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                      MeshFace
+//------------------------------------------------------------------------------
+/// Represents a single face of a `Mesh`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshFace {
+    /// The indices into the `Mesh`'s `vertices` that make up the face.
+    pub vertex_indices: Vec<usize>,
+}
+
+impl MeshFace {
+    pub fn new(vertex_indices: Vec<usize>) -> Self {
+        MeshFace { vertex_indices }
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                    ModelPoint
 //------------------------------------------------------------------------------
@@ -142,6 +371,127 @@ impl ModelPoint {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                                         MText
+//------------------------------------------------------------------------------
+impl MText {
+    /// The text without formatting codes, split into paragraphs.  Stacked text is written as
+    /// `a/b`.
+    pub fn plain_text_lines(&self) -> Vec<String> {
+        let full_text = self.extended_text.concat() + &self.text;
+        let mut lines = vec![];
+        let mut line = String::new();
+        let mut chars = full_text.chars().peekable();
+        let skip_argument = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            for c in chars.by_ref() {
+                if c == ';' {
+                    break;
+                }
+            }
+        };
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('P') => lines.push(std::mem::take(&mut line)),
+                    Some('~') => line.push(' '),
+                    Some(c @ '\\') | Some(c @ '{') | Some(c @ '}') => line.push(c),
+                    Some('S') => {
+                        for c in chars.by_ref() {
+                            match c {
+                                ';' => break,
+                                '^' | '#' => line.push('/'),
+                                _ => line.push(c),
+                            }
+                        }
+                    }
+                    Some('A') | Some('C') | Some('c') | Some('F') | Some('f') | Some('H')
+                    | Some('Q') | Some('T') | Some('W') | Some('p') => skip_argument(&mut chars),
+                    Some('L') | Some('l') | Some('O') | Some('o') | Some('K') | Some('k') => (),
+                    Some(c) => {
+                        line.push('\\');
+                        line.push(c);
+                    }
+                    None => line.push('\\'),
+                },
+                '{' | '}' => (),
+                '\n' => lines.push(std::mem::take(&mut line)),
+                _ => line.push(c),
+            }
+        }
+        lines.push(line);
+        lines
+    }
+    /// Converts to one `Text` per line of `plain_text_lines`, stacked `5/3` of the text height
+    /// times `line_spacing_factor` apart and justified as the attachment point describes.
+    pub fn to_text_lines(&self) -> Vec<Text> {
+        let normal = &self.extrusion_direction;
+        let x_axis = if self.x_axis_direction.is_zero(1.0e-12)
+            || (self.rotation_angle != 0.0 && self.x_axis_direction == Vector::x_axis())
+        {
+            let angle = self.rotation_angle;
+            Point::new(angle.cos(), angle.sin(), 0.0)
+                .ocs_to_wcs(normal)
+                .to_vector()
+        } else {
+            self.x_axis_direction.normalize()
+        };
+        let up = normal.normalize().cross(&x_axis).normalize();
+        let x_axis_ocs = Point::from(x_axis).wcs_to_ocs(normal);
+        let rotation = x_axis_ocs.y.atan2(x_axis_ocs.x).to_degrees();
+
+        let (horizontal, vertical) = match self.attachment_point {
+            AttachmentPoint::TopLeft => (HorizontalTextJustification::Left, 0),
+            AttachmentPoint::TopCenter => (HorizontalTextJustification::Center, 0),
+            AttachmentPoint::TopRight => (HorizontalTextJustification::Right, 0),
+            AttachmentPoint::MiddleLeft => (HorizontalTextJustification::Left, 1),
+            AttachmentPoint::MiddleCenter => (HorizontalTextJustification::Center, 1),
+            AttachmentPoint::MiddleRight => (HorizontalTextJustification::Right, 1),
+            AttachmentPoint::BottomLeft => (HorizontalTextJustification::Left, 2),
+            AttachmentPoint::BottomCenter => (HorizontalTextJustification::Center, 2),
+            AttachmentPoint::BottomRight => (HorizontalTextJustification::Right, 2),
+        };
+        let vertical_text_justification = match vertical {
+            0 => VerticalTextJustification::Top,
+            1 => VerticalTextJustification::Middle,
+            _ => VerticalTextJustification::Bottom,
+        };
+
+        let lines = self.plain_text_lines();
+        let spacing = self.initial_text_height * 5.0 / 3.0 * self.line_spacing_factor;
+        let last = (lines.len() - 1) as f64;
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                // the distance above the insertion point
+                let offset = match vertical {
+                    0 => -(i as f64),
+                    1 => last / 2.0 - i as f64,
+                    _ => last - i as f64,
+                } * spacing;
+                let location = Point::new(
+                    self.insertion_point.x + up.x * offset,
+                    self.insertion_point.y + up.y * offset,
+                    self.insertion_point.z + up.z * offset,
+                )
+                .wcs_to_ocs(normal);
+                Text {
+                    location: location.clone(),
+                    text_height: self.initial_text_height,
+                    value,
+                    rotation,
+                    text_style_name: self.text_style_name.clone(),
+                    horizontal_text_justification: horizontal,
+                    second_alignment_point: location,
+                    normal: normal.clone(),
+                    vertical_text_justification,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                      Polyline
 //------------------------------------------------------------------------------
@@ -156,6 +506,107 @@ impl Polyline {
         let vertex_handle = drawing.next_handle();
         self.__vertices_and_handles.push((vertex, vertex_handle));
     }
+    /// The line and arc segments, including the segment back to the first vertex if the
+    /// polyline is closed.  2D polylines are in the object coordinate system with the elevation
+    /// in `location.z`; 3D polylines only have lines.  Meshes have no segments.
+    pub fn segments(&self) -> impl Iterator<Item = PolylineSegment> {
+        let vertices = if self.get_is_3d_polygon_mesh() || self.get_is_polyface_mesh() {
+            vec![]
+        } else {
+            let is_3d = self.get_is_3d_polyline();
+            self.vertices()
+                .filter(|v| !v.get_is_spline_frame_control_point())
+                .map(|v| (v.location.clone(), if is_3d { 0.0 } else { v.bulge }))
+                .collect()
+        };
+        segments_from_vertices(vertices, self.get_is_closed()).into_iter()
+    }
+    pub fn length(&self) -> f64 {
+        self.segments().map(|s| s.length()).sum()
+    }
+    /// The signed area enclosed by the polyline, positive if counter-clockwise when viewed
+    /// against the normal.  Open polylines are closed with a straight line.  Only X and Y are
+    /// considered.
+    pub fn area(&self) -> f64 {
+        segments_area(&self.segments().collect::<Vec<_>>())
+    }
+    pub fn is_clockwise(&self) -> bool {
+        self.area() < 0.0
+    }
+    /// Reverses the direction of the polyline without changing its shape.  Meshes are
+    /// unchanged.
+    pub fn reverse(&mut self) {
+        if self.get_is_3d_polygon_mesh() || self.get_is_polyface_mesh() {
+            return;
+        }
+        let vertices = &self.__vertices_and_handles;
+        let reversed = reversed_vertex_order(vertices.len())
+            .map(|(i, segment)| {
+                let ((v, handle), (s, _)) = (&vertices[i], &vertices[segment]);
+                let vertex = Vertex {
+                    starting_width: s.ending_width,
+                    ending_width: s.starting_width,
+                    bulge: -s.bulge,
+                    ..v.clone()
+                };
+                (vertex, *handle)
+            })
+            .collect();
+        self.__vertices_and_handles = reversed;
+    }
+    /// The point the specified distance along the polyline, in the same coordinates as
+    /// `segments`, or `None` if the distance is negative or exceeds the length.
+    pub fn point_at_distance(&self, distance: f64) -> Option<Point> {
+        segments_point_at_distance(&self.segments().collect::<Vec<_>>(), distance)
+    }
+    /// Returns `true` if the point, in the same coordinates as `segments`, is inside the area
+    /// enclosed by the polyline.  Open polylines are closed with a straight line.  Only X and Y
+    /// are considered.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        segments_contain_point(&self.segments().collect::<Vec<_>>(), p)
+    }
+    /// Converts a 2D polyline to the equivalent `LwPolyline`; the elevation, `location.z`,
+    /// becomes the entity's `common.elevation`.  Returns `None` if the conversion would lose
+    /// information, i.e., for 3D polylines, meshes, curve- or spline-fit polylines, and
+    /// differing default widths.
+    pub fn to_lw_polyline(&self) -> Option<LwPolyline> {
+        if self.get_is_3d_polyline()
+            || self.get_is_3d_polygon_mesh()
+            || self.get_is_polyface_mesh()
+            || self.get_curve_fit_vertices_added()
+            || self.get_spline_fit_vertices_added()
+            || self.default_starting_width != self.default_ending_width
+        {
+            return None;
+        }
+
+        let mut poly = LwPolyline {
+            constant_width: self.default_starting_width,
+            thickness: self.thickness,
+            extrusion_direction: self.normal.clone(),
+            vertices: self
+                .vertices()
+                .map(|v| LwPolylineVertex {
+                    x: v.location.x,
+                    y: v.location.y,
+                    id: v.identifier,
+                    starting_width: v.starting_width,
+                    ending_width: v.ending_width,
+                    bulge: v.bulge,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        poly.set_is_closed(self.get_is_closed());
+        poly.set_is_pline_gen(self.get_is_line_type_pattern_generated_continuously());
+        Some(poly)
+    }
+}
+
+/// For each vertex of a reversed polyline, the index of the original vertex at that position
+/// and of the original vertex starting the segment that now starts there.
+fn reversed_vertex_order(count: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..count).map(move |i| (count - 1 - i, (2 * count - 2 - i) % count))
 }
 
 //------------------------------------------------------------------------------
@@ -197,6 +648,622 @@ impl Solid {
             ..Default::default()
         }
     }
+    /// The four corners in world coordinates.
+    pub fn corners_wcs(&self) -> [Point; 4] {
+        [
+            self.first_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.second_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.third_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.fourth_corner.ocs_to_wcs(&self.extrusion_direction),
+        ]
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                        Spline
+//------------------------------------------------------------------------------
+impl Spline {
+    /// Creates a non-rational spline of the specified degree passing through the fit points,
+    /// using global interpolation with chord length parameterization.  If given, the curve
+    /// starts and ends in the direction of the tangents.  The degree is reduced if there are too
+    /// few points.  Returns `None` if all points coincide or the degree isn't positive.
+    pub fn from_fit_points(
+        fit_points: &[Point],
+        degree: i32,
+        start_tangent: Option<&Vector>,
+        end_tangent: Option<&Vector>,
+    ) -> Option<Spline> {
+        let lengths = fit_points
+            .windows(2)
+            .map(|w| w[0].distance_to(&w[1]))
+            .collect::<Vec<_>>();
+        let total_length: f64 = lengths.iter().sum();
+        if degree < 1 || total_length == 0.0 {
+            return None;
+        }
+
+        let mut parameters = vec![0.0];
+        for length in &lengths {
+            let previous = parameters[parameters.len() - 1];
+            parameters.push(previous + length / total_length);
+        }
+        let last = parameters.len() - 1;
+        parameters[last] = 1.0;
+
+        // each tangent adds a control point; the tangent is scaled to the parameter range
+        let start_tangent = start_tangent
+            .filter(|t| !t.is_zero(1.0e-12))
+            .map(|t| t.normalize() * total_length);
+        let end_tangent = end_tangent
+            .filter(|t| !t.is_zero(1.0e-12))
+            .map(|t| t.normalize() * total_length);
+        let mut averaged = parameters.clone();
+        if start_tangent.is_some() {
+            averaged.insert(0, 0.0);
+        }
+        if end_tangent.is_some() {
+            averaged.push(1.0);
+        }
+        let count = averaged.len();
+        let degree = (degree as usize).min(count - 1);
+
+        // average the parameters so every knot span contains at least one of them
+        let mut knot_values = vec![0.0; degree + 1];
+        for j in 1..count - degree {
+            knot_values.push(averaged[j..j + degree].iter().sum::<f64>() / degree as f64);
+        }
+        knot_values.extend(vec![1.0; degree + 1]);
+
+        let mut spline = Spline {
+            degree_of_curve: degree as i32,
+            knot_values,
+            control_points: vec![Point::origin(); count],
+            fit_points: fit_points.to_vec(),
+            start_tangent: start_tangent
+                .as_ref()
+                .map(|t| Point::from(t.normalize()))
+                .unwrap_or_else(Point::origin),
+            end_tangent: end_tangent
+                .as_ref()
+                .map(|t| Point::from(t.normalize()))
+                .unwrap_or_else(Point::origin),
+            ..Default::default()
+        };
+        let mut matrix = vec![];
+        let mut values = vec![];
+        for (i, t) in parameters.iter().enumerate() {
+            let mut row = vec![0.0; count];
+            let span = spline.find_span(*t);
+            for (j, value) in spline.basis_functions(span, *t).into_iter().enumerate() {
+                row[span - degree + j] = value;
+            }
+            matrix.push(row);
+            values.push(fit_points[i].clone());
+
+            // the first derivative at an end only depends on the two nearest control points
+            let derivative = match (i, &start_tangent, &end_tangent) {
+                (0, Some(tangent), _) => {
+                    Some((0, degree as f64 / spline.knot_values[degree + 1], tangent))
+                }
+                (i, _, Some(tangent)) if i == last => Some((
+                    count - 2,
+                    degree as f64 / (1.0 - spline.knot_values[count - 1]),
+                    tangent,
+                )),
+                _ => None,
+            };
+            if let Some((index, factor, tangent)) = derivative {
+                let mut row = vec![0.0; count];
+                row[index] = -factor;
+                row[index + 1] = factor;
+                matrix.push(row);
+                values.push(Point::from(tangent.clone()));
+            }
+        }
+
+        if end_tangent.is_some() {
+            // keep the end point as the last row
+            let len = matrix.len();
+            matrix.swap(len - 1, len - 2);
+            values.swap(len - 1, len - 2);
+        }
+
+        spline.control_points = Spline::solve(matrix, &values)?;
+        Some(spline)
+    }
+    /// Replaces the control points and knots with ones interpolating the fit points and the
+    /// non-zero start and end tangents.  A degree less than 1 is treated as cubic.  Returns
+    /// `false` and leaves the spline unchanged if there aren't at least two distinct fit points.
+    pub fn compute_control_points(&mut self) -> bool {
+        let degree = if self.degree_of_curve >= 1 {
+            self.degree_of_curve
+        } else {
+            3
+        };
+        let start_tangent = self.start_tangent.to_vector();
+        let end_tangent = self.end_tangent.to_vector();
+        match Spline::from_fit_points(
+            &self.fit_points,
+            degree,
+            Some(&start_tangent),
+            Some(&end_tangent),
+        ) {
+            Some(spline) => {
+                self.degree_of_curve = spline.degree_of_curve;
+                self.knot_values = spline.knot_values;
+                self.control_points = spline.control_points;
+                self.weights.clear();
+                true
+            }
+            None => false,
+        }
+    }
+    /// Returns `true` if the knot vector matches the degree and the number of control points.
+    pub fn has_valid_knots(&self) -> bool {
+        let degree = self.degree_of_curve;
+        degree >= 1
+            && self.control_points.len() > degree as usize
+            && self.knot_values.len() == self.control_points.len() + degree as usize + 1
+            && self.knot_values.windows(2).all(|w| w[0] <= w[1])
+            && self.domain().0 < self.domain().1
+    }
+    /// The range of the curve parameter, or `None` if the knots are invalid.
+    pub fn parameter_range(&self) -> Option<(f64, f64)> {
+        if self.has_valid_knots() {
+            Some(self.domain())
+        } else {
+            None
+        }
+    }
+    /// The point on the curve at parameter `t`, which is clamped to the parameter range.
+    /// Weights only apply if there is one per control point.  Returns `None` if the knots are
+    /// invalid.
+    pub fn evaluate(&self, t: f64) -> Option<Point> {
+        if self.has_valid_knots() {
+            Some(self.point_at(t))
+        } else {
+            None
+        }
+    }
+    /// The first derivative of the curve with respect to its parameter at `t`, which is clamped
+    /// to the parameter range.  Returns `None` if the knots are invalid.
+    pub fn derivative(&self, t: f64) -> Option<Vector> {
+        if self.has_valid_knots() {
+            Some(self.derivative_at(t))
+        } else {
+            None
+        }
+    }
+    /// The arc length of the curve, or `None` if the knots are invalid.
+    pub fn length(&self) -> Option<f64> {
+        if !self.has_valid_knots() {
+            return None;
+        }
+
+        let speed = |t: f64| self.derivative_at(t).length();
+        let length = self
+            .knot_spans()
+            .into_iter()
+            .map(|(t0, t1)| {
+                let estimate = gauss_legendre(&speed, t0, t1);
+                Spline::adaptive_integral(&speed, t0, t1, estimate, 0)
+            })
+            .sum();
+        Some(length)
+    }
+    /// Inserts the knot `t` without changing the shape of the curve, adding a control point.
+    /// Returns `false` if the knots are invalid, `t` isn't inside the parameter range, or the
+    /// knot's multiplicity would exceed the degree.
+    pub fn insert_knot(&mut self, t: f64) -> bool {
+        if !self.has_valid_knots() {
+            return false;
+        }
+        let (t0, t1) = self.domain();
+        let degree = self.degree_of_curve as usize;
+        let multiplicity = self.knot_values.iter().filter(|k| **k == t).count();
+        if t <= t0 || t >= t1 || multiplicity >= degree {
+            return false;
+        }
+
+        let span = self.find_span(t);
+        let points = self.homogeneous_points();
+        let mut inserted = points[..=span - degree].to_vec();
+        for i in span - degree + 1..=span {
+            let (ki, kd) = (self.knot_values[i], self.knot_values[i + degree]);
+            let alpha = (t - ki) / (kd - ki);
+            let mut point = [0.0; 4];
+            for (k, value) in point.iter_mut().enumerate() {
+                *value = (1.0 - alpha) * points[i - 1][k] + alpha * points[i][k];
+            }
+            inserted.push(point);
+        }
+        inserted.extend_from_slice(&points[span..]);
+
+        let is_rational = self.weights.len() == self.control_points.len();
+        self.control_points = inserted
+            .iter()
+            .map(|[x, y, z, w]| Point::new(x / w, y / w, z / w))
+            .collect();
+        if is_rational {
+            self.weights = inserted.iter().map(|p| p[3]).collect();
+        }
+        self.knot_values.insert(span + 1, t);
+        true
+    }
+    /// Returns the parameter and location of the point on the curve nearest to `p`, or `None` if
+    /// the knots are invalid.
+    pub fn closest_point(&self, p: &Point) -> Option<(f64, Point)> {
+        if !self.has_valid_knots() {
+            return None;
+        }
+
+        let mut parameters = vec![self.domain().0];
+        for (t0, t1) in self.knot_spans() {
+            for i in 1..=CLOSEST_POINT_SAMPLES_PER_SPAN {
+                parameters.push(t0 + (t1 - t0) * i as f64 / CLOSEST_POINT_SAMPLES_PER_SPAN as f64);
+            }
+        }
+
+        // refine around the nearest sample
+        let distance = |t: f64| self.point_at(t).distance_to(p);
+        let nearest = (0..parameters.len())
+            .min_by(|a, b| distance(parameters[*a]).total_cmp(&distance(parameters[*b])))?;
+        let t = golden_section_maximum(
+            |t| -distance(t),
+            parameters[nearest.saturating_sub(1)],
+            parameters[(nearest + 1).min(parameters.len() - 1)],
+        );
+        let mut t = if distance(t) <= distance(parameters[nearest]) {
+            t
+        } else {
+            parameters[nearest]
+        };
+
+        // the distance is flat near its minimum; polish with Newton's method on its derivative
+        let (t0, t1) = self.domain();
+        let h = (t1 - t0) * 1.0e-6;
+        let searched = t;
+        for _ in 0..8 {
+            let offset = self.point_at(t) - p.to_vector();
+            let first = self.derivative_at(t);
+            let second = (self.derivative_at(t + h) - self.derivative_at(t - h)) / (2.0 * h);
+            let slope = first.dot(&first) + second.dot(&offset.to_vector());
+            if slope <= 0.0 {
+                break;
+            }
+            let next = (t - first.dot(&offset.to_vector()) / slope).max(t0).min(t1);
+            let step = (next - t).abs();
+            t = next;
+            if step <= h * 1.0e-9 {
+                break;
+            }
+        }
+        if distance(t) > distance(searched) * (1.0 + 1.0e-9) {
+            // converged to another local minimum
+            t = searched;
+        }
+
+        Some((t, self.point_at(t)))
+    }
+}
+
+// internal visibility only
+impl Spline {
+    /// The range of the curve parameter.  Requires valid knots.
+    pub(crate) fn domain(&self) -> (f64, f64) {
+        let degree = self.degree_of_curve as usize;
+        (
+            self.knot_values[degree],
+            self.knot_values[self.control_points.len()],
+        )
+    }
+    /// The point on the curve at parameter `t`.  Requires valid knots.
+    pub(crate) fn point_at(&self, t: f64) -> Point {
+        let [x, y, z, w] = self.homogeneous_at(t);
+        Point::new(x / w, y / w, z / w)
+    }
+    /// The non-empty knot spans within the parameter range.  Requires valid knots.
+    pub(crate) fn knot_spans(&self) -> Vec<(f64, f64)> {
+        let (t0, t1) = self.domain();
+        self.knot_values
+            .windows(2)
+            .map(|w| (w[0].max(t0), w[1].min(t1)))
+            .filter(|(k0, k1)| k1 > k0)
+            .collect()
+    }
+}
+
+// private implementation
+impl Spline {
+    fn weight_at(&self, index: usize) -> f64 {
+        if self.weights.len() == self.control_points.len() {
+            self.weights[index]
+        } else {
+            1.0
+        }
+    }
+    /// The control points as `[x * w, y * w, z * w, w]`.
+    fn homogeneous_points(&self) -> Vec<[f64; 4]> {
+        self.control_points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let w = self.weight_at(i);
+                [p.x * w, p.y * w, p.z * w, w]
+            })
+            .collect()
+    }
+    fn homogeneous_at(&self, t: f64) -> [f64; 4] {
+        let (t0, t1) = self.domain();
+        Spline::de_boor(
+            self.degree_of_curve as usize,
+            &self.knot_values,
+            &self.homogeneous_points(),
+            t.max(t0).min(t1),
+        )
+    }
+    fn derivative_at(&self, t: f64) -> Vector {
+        let degree = self.degree_of_curve as usize;
+        let knots = &self.knot_values;
+        let (t0, t1) = self.domain();
+        let t = t.max(t0).min(t1);
+
+        // the derivative of the homogeneous curve is a spline of one degree less
+        let points = self.homogeneous_points();
+        let derivative_points = points
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| {
+                let denominator = knots[i + degree + 1] - knots[i + 1];
+                let mut point = [0.0; 4];
+                if denominator != 0.0 {
+                    for (k, value) in point.iter_mut().enumerate() {
+                        *value = degree as f64 * (w[1][k] - w[0][k]) / denominator;
+                    }
+                }
+                point
+            })
+            .collect::<Vec<_>>();
+        let [dx, dy, dz, dw] = Spline::de_boor(
+            degree - 1,
+            &knots[1..knots.len() - 1],
+            &derivative_points,
+            t,
+        );
+        let [x, y, z, w] = Spline::de_boor(degree, knots, &points, t);
+
+        // quotient rule: C' = (A' - w' C) / w
+        Vector::new(
+            (dx - dw * x / w) / w,
+            (dy - dw * y / w) / w,
+            (dz - dw * z / w) / w,
+        )
+    }
+    /// Evaluates the spline with the specified homogeneous control points using de Boor's
+    /// algorithm.
+    fn de_boor(degree: usize, knots: &[f64], points: &[[f64; 4]], t: f64) -> [f64; 4] {
+        let span = Spline::span_of(degree, knots, points.len(), t);
+        let mut points = points[span - degree..=span].to_vec();
+        for r in 1..=degree {
+            for j in (r..=degree).rev() {
+                let i = j + span - degree;
+                let denominator = knots[i + degree + 1 - r] - knots[i];
+                let alpha = if denominator == 0.0 {
+                    0.0
+                } else {
+                    (t - knots[i]) / denominator
+                };
+                let previous = points[j - 1];
+                for (value, previous) in points[j].iter_mut().zip(previous.iter()) {
+                    *value = previous + (*value - previous) * alpha;
+                }
+            }
+        }
+
+        points[degree]
+    }
+    /// Returns the index of the knot span containing `t`; the last span is closed on the right.
+    fn span_of(degree: usize, knots: &[f64], point_count: usize, t: f64) -> usize {
+        let mut span = degree;
+        while span + 1 < point_count && knots[span + 1] <= t {
+            span += 1;
+        }
+        span
+    }
+    fn find_span(&self, t: f64) -> usize {
+        Spline::span_of(
+            self.degree_of_curve as usize,
+            &self.knot_values,
+            self.control_points.len(),
+            t,
+        )
+    }
+    /// Returns the values of the basis functions that are non-zero in the knot span at `t`.
+    fn basis_functions(&self, span: usize, t: f64) -> Vec<f64> {
+        let degree = self.degree_of_curve as usize;
+        let knots = &self.knot_values;
+        let mut values = vec![0.0; degree + 1];
+        let mut left = vec![0.0; degree + 1];
+        let mut right = vec![0.0; degree + 1];
+        values[0] = 1.0;
+        for j in 1..=degree {
+            left[j] = t - knots[span + 1 - j];
+            right[j] = knots[span + j] - t;
+            let mut saved = 0.0;
+            for r in 0..j {
+                let temp = values[r] / (right[r + 1] + left[j - r]);
+                values[r] = saved + right[r + 1] * temp;
+                saved = left[j - r] * temp;
+            }
+            values[j] = saved;
+        }
+        values
+    }
+    /// Integrates `f` by halving the interval until both halves agree with the whole.
+    fn adaptive_integral<F>(f: &F, a: f64, b: f64, whole: f64, depth: usize) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mid = (a + b) * 0.5;
+        let left = gauss_legendre(f, a, mid);
+        let right = gauss_legendre(f, mid, b);
+        if depth >= MAX_INTEGRAL_DEPTH || (left + right - whole).abs() <= 1.0e-12 * whole.abs() {
+            left + right
+        } else {
+            Spline::adaptive_integral(f, a, mid, left, depth + 1)
+                + Spline::adaptive_integral(f, mid, b, right, depth + 1)
+        }
+    }
+    /// Solves `matrix * x = points` by Gaussian elimination with partial pivoting.
+    fn solve(mut matrix: Vec<Vec<f64>>, points: &[Point]) -> Option<Vec<Point>> {
+        let mut rhs = points.iter().map(|p| [p.x, p.y, p.z]).collect::<Vec<_>>();
+        let size = rhs.len();
+        for column in 0..size {
+            let pivot = (column..size).max_by(|a, b| {
+                matrix[*a][column]
+                    .abs()
+                    .total_cmp(&matrix[*b][column].abs())
+            })?;
+            if matrix[pivot][column].abs() < 1.0e-12 {
+                return None;
+            }
+            matrix.swap(pivot, column);
+            rhs.swap(pivot, column);
+            let (pivot_rows, rows) = matrix.split_at_mut(column + 1);
+            let (pivot_rhs, rows_rhs) = rhs.split_at_mut(column + 1);
+            let (pivot_row, pivot_rhs) = (&pivot_rows[column], &pivot_rhs[column]);
+            for (row, row_rhs) in rows.iter_mut().zip(rows_rhs.iter_mut()) {
+                let factor = row[column] / pivot_row[column];
+                if factor != 0.0 {
+                    for (value, pivot) in row.iter_mut().zip(pivot_row.iter()).skip(column) {
+                        *value -= factor * pivot;
+                    }
+                    for (value, pivot) in row_rhs.iter_mut().zip(pivot_rhs.iter()) {
+                        *value -= factor * pivot;
+                    }
+                }
+            }
+        }
+
+        let mut result = vec![[0.0; 3]; size];
+        for row in (0..size).rev() {
+            for k in 0..3 {
+                let sum: f64 = (row + 1..size).map(|c| matrix[row][c] * result[c][k]).sum();
+                result[row][k] = (rhs[row][k] - sum) / matrix[row][row];
+            }
+        }
+
+        Some(
+            result
+                .iter()
+                .map(|p| Point::new(p[0], p[1], p[2]))
+                .collect(),
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                         Table
+//------------------------------------------------------------------------------
+impl Table {
+    /// Returns the number of rows in the table.
+    pub fn row_count(&self) -> usize {
+        self.row_heights.len()
+    }
+    /// Returns the number of columns in the table.
+    pub fn column_count(&self) -> usize {
+        self.column_widths.len()
+    }
+    /// Returns the cell at the specified zero-based row and column, if it exists.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&TableCell> {
+        match self.cell_index(row, column) {
+            Some(index) => self.cells.get(index),
+            None => None,
+        }
+    }
+    /// Returns the cell at the specified zero-based row and column, if it exists.
+    pub fn cell_mut(&mut self, row: usize, column: usize) -> Option<&mut TableCell> {
+        match self.cell_index(row, column) {
+            Some(index) => self.cells.get_mut(index),
+            None => None,
+        }
+    }
+    /// Returns the ranges of cells that have been merged together.
+    pub fn merged_ranges(&self) -> Vec<TableCellRange> {
+        let mut ranges = vec![];
+        for row in 0..self.row_count() {
+            for column in 0..self.column_count() {
+                if let Some(cell) = self.cell(row, column) {
+                    if cell.merged_row_count > 1 || cell.merged_column_count > 1 {
+                        ranges.push(TableCellRange {
+                            top_row: row,
+                            left_column: column,
+                            bottom_row: row + cell.merged_row_count.max(1) as usize - 1,
+                            right_column: column + cell.merged_column_count.max(1) as usize - 1,
+                        });
+                    }
+                }
+            }
+        }
+        ranges
+    }
+    fn cell_index(&self, row: usize, column: usize) -> Option<usize> {
+        if row < self.row_count() && column < self.column_count() {
+            Some(row * self.column_count() + column)
+        } else {
+            None
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                          Text
+//------------------------------------------------------------------------------
+impl Text {
+    /// The first alignment point in world coordinates.
+    pub fn location_wcs(&self) -> Point {
+        self.location.ocs_to_wcs(&self.normal)
+    }
+    /// The second alignment point in world coordinates.
+    pub fn second_alignment_point_wcs(&self) -> Point {
+        self.second_alignment_point.ocs_to_wcs(&self.normal)
+    }
+    /// Creates the `Text` displaying the value of an `Attribute`.
+    pub(crate) fn from_attribute(att: &Attribute) -> Self {
+        Text {
+            thickness: att.thickness,
+            location: att.location.clone(),
+            text_height: att.text_height,
+            value: att.value.clone(),
+            rotation: att.rotation,
+            relative_x_scale_factor: att.relative_x_scale_factor,
+            oblique_angle: att.oblique_angle,
+            text_style_name: att.text_style_name.clone(),
+            text_generation_flags: att.text_generation_flags,
+            horizontal_text_justification: att.horizontal_text_justification,
+            second_alignment_point: att.second_alignment_point.clone(),
+            normal: att.normal.clone(),
+            vertical_text_justification: att.vertical_text_justification,
+        }
+    }
+    /// Creates the `Text` displaying the default value of an `AttributeDefinition`.
+    pub(crate) fn from_attribute_definition(att: &AttributeDefinition) -> Self {
+        Text {
+            thickness: att.thickness,
+            location: att.location.clone(),
+            text_height: att.text_height,
+            value: att.value.clone(),
+            rotation: att.rotation,
+            relative_x_scale_factor: att.relative_x_scale_factor,
+            oblique_angle: att.oblique_angle,
+            text_style_name: att.text_style_name.clone(),
+            text_generation_flags: att.text_generation_flags,
+            horizontal_text_justification: att.horizontal_text_justification,
+            second_alignment_point: att.second_alignment_point.clone(),
+            normal: att.normal.clone(),
+            vertical_text_justification: att.vertical_text_justification,
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -217,6 +1284,15 @@ impl Trace {
             ..Default::default()
         }
     }
+    /// The four corners in world coordinates.
+    pub fn corners_wcs(&self) -> [Point; 4] {
+        [
+            self.first_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.second_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.third_corner.ocs_to_wcs(&self.extrusion_direction),
+            self.fourth_corner.ocs_to_wcs(&self.extrusion_direction),
+        ]
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -389,17 +1465,93 @@ impl EntityCommon {
     pub fn normalize(&mut self) {
         default_if_empty(&mut self.layer, "0");
     }
-}
-
-//------------------------------------------------------------------------------
-//                                                                        Entity
-//------------------------------------------------------------------------------
-impl Entity {
-    /// Creates a new `Entity` with the default common values.
-    pub fn new(specific: EntityType) -> Self {
-        Entity {
-            common: Default::default(),
-            specific,
+    /// Gets the 24-bit color, or `None` if `color_24_bit` isn't set.
+    pub fn get_true_color(&self) -> Option<TrueColor> {
+        self.color_24_bit.map(TrueColor::from_raw_value)
+    }
+    /// Sets the 24-bit color.  The index color is set to the closest match for readers that don't
+    /// support 24-bit colors.
+    pub fn set_true_color(&mut self, color: Option<TrueColor>) {
+        match color {
+            Some(color) => {
+                self.color = color.to_index_color();
+                self.color_24_bit = Some(color.get_raw_value());
+            }
+            None => self.color_24_bit = None,
+        }
+    }
+    pub fn get_transparency(&self) -> Transparency {
+        Transparency::from_raw_value(self.transparency)
+    }
+    pub fn set_transparency(&mut self, transparency: Transparency) {
+        self.transparency = transparency.get_raw_value();
+    }
+    /// Gets the RGB value the entity is displayed with.  BYLAYER colors are looked up in the
+    /// drawing's layers and BYBLOCK colors and the layer `0` are taken from the block reference
+    /// the entity is displayed through, if any.  Colors that can't be resolved are white.
+    pub fn resolve_rgb(
+        &self,
+        drawing: &Drawing,
+        block_reference: Option<&EntityCommon>,
+    ) -> (u8, u8, u8) {
+        let default_color = || Color::from_index(7).to_rgb().unwrap_or((255, 255, 255));
+        if self.color.is_by_block() {
+            return match block_reference {
+                Some(block_reference) => block_reference.resolve_rgb(drawing, None),
+                None => default_color(),
+            };
+        }
+        if self.color.is_by_layer() {
+            let layer_name = match block_reference {
+                Some(block_reference) if self.layer == "0" => &block_reference.layer,
+                _ => &self.layer,
+            };
+            return drawing
+                .layers()
+                .find(|l| l.name.eq_ignore_ascii_case(layer_name))
+                .and_then(|l| Color::from_raw_value(l.color.get_raw_value().abs()).to_rgb())
+                .unwrap_or_else(default_color);
+        }
+
+        match (self.get_true_color(), self.color.to_rgb()) {
+            (Some(true_color), _) => true_color.to_rgb(),
+            (None, Some(rgb)) => rgb,
+            (None, None) => default_color(),
+        }
+    }
+    /// Replaces the values inherited from a block reference, i.e., BYBLOCK colors, line types,
+    /// line weights, and transparencies and the layer `0`, with the values of the block reference.
+    pub(crate) fn resolve_by_block(&mut self, block_reference: &EntityCommon) {
+        if self.color.is_by_block() {
+            self.color = block_reference.color.clone();
+            self.color_24_bit = block_reference.color_24_bit;
+            self.color_name = block_reference.color_name.clone();
+        }
+        if self.line_type_name.eq_ignore_ascii_case("BYBLOCK") {
+            self.line_type_name = block_reference.line_type_name.clone();
+        }
+        if LineWeight::from_raw_value(self.lineweight_enum_value).is_by_block() {
+            self.lineweight_enum_value = block_reference.lineweight_enum_value;
+        }
+        if self.get_transparency().is_by_block() {
+            self.transparency = block_reference.transparency;
+        }
+        if self.layer == "0" {
+            self.layer = block_reference.layer.clone();
+        }
+        self.is_in_paper_space = block_reference.is_in_paper_space;
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                        Entity
+//------------------------------------------------------------------------------
+impl Entity {
+    /// Creates a new `Entity` with the default common values.
+    pub fn new(specific: EntityType) -> Self {
+        Entity {
+            common: Default::default(),
+            specific,
         }
     }
     /// Ensures all entity values are valid.
@@ -407,6 +1559,12 @@ impl Entity {
         self.common.normalize();
         // no entity-specific values to set
     }
+    /// Returns the smallest axis-aligned box in world coordinates containing the entity, or `None`
+    /// if the entity is unbounded or has no geometry.  Block references are followed through the
+    /// specified drawing.  Text-like entities only contribute their insertion point.
+    pub fn bounding_box(&self, drawing: &Drawing) -> Option<BoundingBox> {
+        BoundingBox::of_entity(self, drawing, &TransformationMatrix::identity(), 0)
+    }
     pub(crate) fn read<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<Option<Entity>>
     where
         I: Read,
@@ -644,19 +1802,13 @@ impl Entity {
                                     return Ok(Some(entity));
                                 }
                                 None => {
-                                    // swallow unsupported entity
-                                    loop {
-                                        match iter.next() {
-                                            Some(Ok(pair @ CodePair { code: 0, .. })) => {
-                                                // found another entity or ENDSEC
-                                                iter.put_back(Ok(pair));
-                                                break;
-                                            }
-                                            Some(Ok(_)) => (), // part of the unsupported entity
-                                            Some(Err(e)) => return Err(e),
-                                            None => return Err(DxfError::UnexpectedEndOfInput),
-                                        }
-                                    }
+                                    // unsupported entity; keep the raw code pairs so it can be written back
+                                    let mut entity = Entity::new(EntityType::Unknown {
+                                        type_string,
+                                        pairs: vec![],
+                                    });
+                                    entity.apply_custom_reader(iter)?;
+                                    return Ok(Some(entity));
                                 }
                             }
                         }
@@ -808,12 +1960,27 @@ impl Entity {
             EntityType::AttributeDefinition(ref mut att) => {
                 Entity::apply_custom_reader_attributedefinition(&mut self.common, att, iter)
             }
+            EntityType::Hatch(ref mut hatch) => {
+                Entity::apply_custom_reader_hatch(&mut self.common, hatch, iter)
+            }
             EntityType::LwPolyline(ref mut poly) => {
                 Entity::apply_custom_reader_lwpolyline(&mut self.common, poly, iter)
             }
+            EntityType::Mesh(ref mut mesh) => {
+                Entity::apply_custom_reader_mesh(&mut self.common, mesh, iter)
+            }
+            EntityType::MLeader(ref mut mleader) => {
+                Entity::apply_custom_reader_mleader(&mut self.common, mleader, iter)
+            }
             EntityType::MText(ref mut mtext) => {
                 Entity::apply_custom_reader_mtext(&mut self.common, mtext, iter)
             }
+            EntityType::Table(ref mut table) => {
+                Entity::apply_custom_reader_table(&mut self.common, table, iter)
+            }
+            EntityType::Unknown { ref mut pairs, .. } => {
+                Entity::apply_custom_reader_unknown(&mut self.common, pairs, iter)
+            }
             _ => Ok(false), // no custom reader
         }
     }
@@ -1122,6 +2289,135 @@ impl Entity {
             }
         }
     }
+    fn apply_custom_reader_hatch<I>(
+        common: &mut EntityCommon,
+        hatch: &mut Hatch,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        let mut reading_seed_points = false;
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                10 => {
+                    if reading_seed_points {
+                        hatch
+                            .seed_points
+                            .push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                    } else {
+                        hatch.elevation_point.x = pair.assert_f64()?;
+                    }
+                }
+                20 => {
+                    if reading_seed_points {
+                        vec_last!(hatch.seed_points).y = pair.assert_f64()?;
+                    } else {
+                        hatch.elevation_point.y = pair.assert_f64()?;
+                    }
+                }
+                30 => {
+                    hatch.elevation_point.z = pair.assert_f64()?;
+                }
+                210 => {
+                    hatch.extrusion_direction.x = pair.assert_f64()?;
+                }
+                220 => {
+                    hatch.extrusion_direction.y = pair.assert_f64()?;
+                }
+                230 => {
+                    hatch.extrusion_direction.z = pair.assert_f64()?;
+                }
+                2 => {
+                    hatch.pattern_name = pair.assert_string()?;
+                }
+                70 => {
+                    hatch.is_solid_fill = as_bool(pair.assert_i16()?);
+                }
+                71 => {
+                    hatch.is_associative = as_bool(pair.assert_i16()?);
+                }
+                91 => {
+                    // boundary path count; we just read as many as we're given
+                    while let Some(path) = HatchBoundaryPath::read(iter)? {
+                        hatch.boundary_paths.push(path);
+                    }
+                }
+                75 => {
+                    hatch.hatch_style =
+                        enum_from_number!(HatchStyle, OddParity, from_i16, pair.assert_i16()?);
+                }
+                76 => {
+                    hatch.pattern_type = enum_from_number!(
+                        HatchPatternType,
+                        Predefined,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                52 => {
+                    hatch.pattern_angle = pair.assert_f64()?;
+                }
+                41 => {
+                    hatch.pattern_scale = pair.assert_f64()?;
+                }
+                77 => {
+                    hatch.is_pattern_double = as_bool(pair.assert_i16()?);
+                }
+                78 => {
+                    // pattern definition line count; we just read as many as we're given
+                    while let Some(line) = HatchPatternDefinitionLine::read(iter)? {
+                        hatch.pattern_definition_lines.push(line);
+                    }
+                }
+                47 => {
+                    hatch.pixel_size = pair.assert_f64()?;
+                }
+                98 => {
+                    // seed point count; we just read as many as we're given
+                    reading_seed_points = true;
+                }
+                450 => {
+                    hatch.is_gradient = pair.assert_i32()? != 0;
+                }
+                451 => (), // reserved
+                452 => {
+                    hatch.is_single_color_gradient = pair.assert_i32()? != 0;
+                }
+                453 => (), // gradient color count; we just read as many as we're given
+                460 => {
+                    hatch.gradient_angle = pair.assert_f64()?;
+                }
+                461 => {
+                    hatch.gradient_shift = pair.assert_f64()?;
+                }
+                462 => {
+                    hatch.single_color_gradient_tint = pair.assert_f64()?;
+                }
+                463 => {
+                    // start a new gradient color
+                    hatch.gradient_colors.push(HatchGradientColor {
+                        value: pair.assert_f64()?,
+                        ..Default::default()
+                    });
+                }
+                63 => {
+                    vec_last!(hatch.gradient_colors).color =
+                        Color::from_raw_value(pair.assert_i16()?);
+                }
+                421 => {
+                    vec_last!(hatch.gradient_colors).color_24_bit = pair.assert_i32()?;
+                }
+                470 => {
+                    hatch.gradient_name = pair.assert_string()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
     fn apply_custom_reader_lwpolyline<I>(
         common: &mut EntityCommon,
         poly: &mut LwPolyline,
@@ -1179,6 +2475,287 @@ impl Entity {
             }
         }
     }
+    fn apply_custom_reader_mesh<I>(
+        common: &mut EntityCommon,
+        mesh: &mut Mesh,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        // code 90 is reused for the packed face list, the edge list, and the property override count, so
+        // track which of those is being read along with how many values are left
+        enum MeshList {
+            None,
+            Faces,
+            Edges,
+            Overrides,
+        }
+        let mut current_list = MeshList::None;
+        let mut remaining_list_values = 0;
+        let mut remaining_face_indices = 0;
+        let mut edge_start_index = None;
+        let mut crease_index = 0;
+        loop {
+            let pair = next_pair!(iter);
+            if let MeshList::Overrides = current_list {
+                // sub-entity property overrides are kept as-is, followed by any trailing XDATA
+                if pair.code >= 1000 {
+                    common.apply_individual_pair(&pair, iter)?;
+                } else {
+                    mesh.sub_entity_override_pairs.push(pair);
+                }
+                continue;
+            }
+            match pair.code {
+                71 => {
+                    mesh.version = i32::from(pair.assert_i16()?);
+                }
+                72 => {
+                    mesh.is_blend_crease = as_bool(pair.assert_i16()?);
+                }
+                91 => {
+                    mesh.subdivision_level = pair.assert_i32()?;
+                }
+                92 => (), // vertex count; we just read as many as we're given
+                10 => {
+                    // start a new vertex
+                    mesh.vertices.push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                }
+                20 => {
+                    vec_last!(mesh.vertices).y = pair.assert_f64()?;
+                }
+                30 => {
+                    vec_last!(mesh.vertices).z = pair.assert_f64()?;
+                }
+                93 => {
+                    current_list = MeshList::Faces;
+                    remaining_list_values = pair.assert_i32()?;
+                    remaining_face_indices = 0;
+                }
+                94 => {
+                    current_list = MeshList::Edges;
+                    remaining_list_values = pair.assert_i32()? * 2;
+                    edge_start_index = None;
+                }
+                95 => {
+                    // crease count; we just read as many as we're given
+                    current_list = MeshList::None;
+                    crease_index = 0;
+                }
+                140 => {
+                    let crease = pair.assert_f64()?;
+                    if let Some(edge) = mesh.edges.get_mut(crease_index) {
+                        edge.crease = crease;
+                    }
+                    crease_index += 1;
+                }
+                90 => {
+                    let value = pair.assert_i32()?;
+                    let offset = pair.offset;
+                    match current_list {
+                        MeshList::Faces if remaining_list_values > 0 => {
+                            if remaining_face_indices == 0 {
+                                // the first value of each face is its vertex count
+                                mesh.faces.push(MeshFace::default());
+                                remaining_face_indices = value;
+                            } else {
+                                vec_last!(mesh.faces)
+                                    .vertex_indices
+                                    .push(index_from_i32(value, offset)?);
+                                remaining_face_indices -= 1;
+                            }
+                            remaining_list_values -= 1;
+                        }
+                        MeshList::Edges if remaining_list_values > 0 => {
+                            let index = index_from_i32(value, offset)?;
+                            match edge_start_index.take() {
+                                Some(start) => mesh.edges.push(MeshEdge::new(start, index)),
+                                None => edge_start_index = Some(index),
+                            }
+                            remaining_list_values -= 1;
+                        }
+                        _ => {
+                            // sub-entity property override count
+                            if value > 0 {
+                                current_list = MeshList::Overrides;
+                                mesh.sub_entity_override_pairs.push(pair);
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    fn apply_custom_reader_mleader<I>(
+        common: &mut EntityCommon,
+        mleader: &mut MLeader,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        let mleader_text = "AcDbMLeader";
+        let mut last_subclass_marker = String::new();
+        loop {
+            let pair = next_pair!(iter);
+            if last_subclass_marker != mleader_text {
+                // still in the common entity data
+                if pair.code == 100 {
+                    last_subclass_marker = pair.assert_string()?;
+                } else {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+                continue;
+            }
+            match pair.code {
+                270 => {
+                    mleader.version = pair.assert_i16()?;
+                }
+                300 => {
+                    // value should be "CONTEXT_DATA{", but it doesn't really matter
+                    mleader.context_data = MLeaderContextData::read(iter)?;
+                }
+                340 => {
+                    mleader.__style_handle = pair.as_handle()?;
+                }
+                90 => {
+                    mleader.property_override_flags = pair.assert_i32()?;
+                }
+                170 => {
+                    mleader.leader_type = pair.assert_i16()?;
+                }
+                91 => {
+                    mleader.leader_line_color = pair.assert_i32()?;
+                }
+                341 => {
+                    mleader.__leader_line_type_handle = pair.as_handle()?;
+                }
+                171 => {
+                    mleader.leader_line_weight = pair.assert_i16()?;
+                }
+                290 => {
+                    mleader.enable_landing = pair.assert_bool()?;
+                }
+                291 => {
+                    mleader.enable_dogleg = pair.assert_bool()?;
+                }
+                41 => {
+                    mleader.dogleg_length = pair.assert_f64()?;
+                }
+                342 => {
+                    mleader.__arrowhead_handle = pair.as_handle()?;
+                }
+                42 => {
+                    mleader.arrowhead_size = pair.assert_f64()?;
+                }
+                172 => {
+                    mleader.content_type = pair.assert_i16()?;
+                }
+                343 => {
+                    mleader.__text_style_handle = pair.as_handle()?;
+                }
+                173 => {
+                    mleader.text_left_attachment_type = pair.assert_i16()?;
+                }
+                95 => {
+                    mleader.text_right_attachment_type = pair.assert_i32()?;
+                }
+                174 => {
+                    mleader.text_angle_type = pair.assert_i16()?;
+                }
+                175 => {
+                    mleader.text_alignment_type = pair.assert_i16()?;
+                }
+                92 => {
+                    mleader.text_color = pair.assert_i32()?;
+                }
+                292 => {
+                    mleader.enable_frame_text = pair.assert_bool()?;
+                }
+                344 => {
+                    mleader.__block_content_handle = pair.as_handle()?;
+                }
+                93 => {
+                    mleader.block_content_color = pair.assert_i32()?;
+                }
+                10 => {
+                    mleader.block_content_scale.x = pair.assert_f64()?;
+                }
+                20 => {
+                    mleader.block_content_scale.y = pair.assert_f64()?;
+                }
+                30 => {
+                    mleader.block_content_scale.z = pair.assert_f64()?;
+                }
+                43 => {
+                    mleader.block_content_rotation = pair.assert_f64()?;
+                }
+                176 => {
+                    mleader.block_content_connection_type = pair.assert_i16()?;
+                }
+                293 => {
+                    mleader.enable_annotation_scale = pair.assert_bool()?;
+                }
+                94 => {
+                    // start a new arrowhead override
+                    mleader.arrowhead_overrides.push(MLeaderArrowheadOverride {
+                        leader_line_index: pair.assert_i32()?,
+                        ..Default::default()
+                    });
+                }
+                345 => {
+                    vec_last!(mleader.arrowhead_overrides).arrowhead_handle = pair.as_handle()?;
+                }
+                330 => {
+                    // start a new block attribute
+                    mleader.block_attributes.push(MLeaderBlockAttribute {
+                        attribute_definition_handle: pair.as_handle()?,
+                        ..Default::default()
+                    });
+                }
+                177 => {
+                    vec_last!(mleader.block_attributes).index = pair.assert_i16()?;
+                }
+                44 => {
+                    vec_last!(mleader.block_attributes).width = pair.assert_f64()?;
+                }
+                302 => {
+                    vec_last!(mleader.block_attributes).text = pair.assert_string()?;
+                }
+                294 => {
+                    mleader.is_text_direction_negative = pair.assert_bool()?;
+                }
+                178 => {
+                    mleader.text_align_in_ipe = pair.assert_i16()?;
+                }
+                179 => {
+                    mleader.text_attachment_point = pair.assert_i16()?;
+                }
+                45 => {
+                    mleader.overall_scale = pair.assert_f64()?;
+                }
+                271 => {
+                    mleader.text_attachment_direction = pair.assert_i16()?;
+                }
+                272 => {
+                    mleader.text_bottom_attachment_type = pair.assert_i16()?;
+                }
+                273 => {
+                    mleader.text_top_attachment_type = pair.assert_i16()?;
+                }
+                295 => {
+                    mleader.extend_leader_to_text = pair.assert_bool()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
     fn apply_custom_reader_mtext<I>(
         common: &mut EntityCommon,
         mtext: &mut MText,
@@ -1319,33 +2896,138 @@ impl Entity {
             }
         }
     }
-    pub(crate) fn write<T>(
-        &self,
-        version: AcadVersion,
-        write_handles: bool,
-        writer: &mut CodePairWriter<T>,
-    ) -> DxfResult<()>
+    fn apply_custom_reader_table<I>(
+        common: &mut EntityCommon,
+        table: &mut Table,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
     where
-        T: Write + ?Sized,
+        I: Read,
     {
-        if self.specific.is_supported_on_version(version) {
-            writer.write_code_pair(&CodePair::new_str(0, self.specific.to_type_string()))?;
-            self.common.write(version, write_handles, writer)?;
-            if !self.apply_custom_writer(version, writer)? {
-                self.specific.write(&self.common, version, writer)?;
+        let block_reference_text = "AcDbBlockReference";
+        let table_text = "AcDbTable";
+        let mut last_subclass_marker = String::new();
+        let mut in_cells = false;
+        loop {
+            let pair = next_pair!(iter);
+            if pair.code == 100 {
+                last_subclass_marker = pair.assert_string()?;
+                continue;
             }
+            if last_subclass_marker == block_reference_text {
+                match pair.code {
+                    2 => table.block_name = pair.assert_string()?,
+                    10 => table.insertion_point.x = pair.assert_f64()?,
+                    20 => table.insertion_point.y = pair.assert_f64()?,
+                    30 => table.insertion_point.z = pair.assert_f64()?,
+                    _ => common.apply_individual_pair(&pair, iter)?,
+                }
+            } else if last_subclass_marker == table_text {
+                if pair.code == 171 {
+                    // start a new cell
+                    table.cells.push(TableCell::default());
+                    in_cells = true;
+                }
+                if in_cells {
+                    let cell = table.cells.last_mut().unwrap();
+                    if cell.apply_code_pair(&pair, iter)? {
+                        continue;
+                    }
 
-            self.post_write(version, write_handles, writer)?;
-            for x in &self.common.x_data {
-                x.write(version, writer)?;
+                    // the first pair that isn't part of a cell ends the cells
+                    in_cells = false;
+                }
+                match table.cells.last() {
+                    Some(_) => {
+                        // the table-level values after the cells aren't modeled but are kept
+                        if pair.code == x_data::XDATA_APPLICATIONNAME {
+                            common.apply_individual_pair(&pair, iter)?;
+                        } else {
+                            table.__unsupported_pairs.push(pair);
+                        }
+                    }
+                    None => match pair.code {
+                        280 => table.version = pair.assert_i16()?,
+                        342 => table.__table_style_handle = pair.as_handle()?,
+                        343 => table.__block_record_handle = pair.as_handle()?,
+                        11 => table.horizontal_direction.x = pair.assert_f64()?,
+                        21 => table.horizontal_direction.y = pair.assert_f64()?,
+                        31 => table.horizontal_direction.z = pair.assert_f64()?,
+                        90 => table.table_value_flags = pair.assert_i32()?,
+                        91 => (), // row count; we just read as many as we're given
+                        92 => (), // column count; we just read as many as we're given
+                        93 => table.override_flags = pair.assert_i32()?,
+                        94 => table.border_color_override_flags = pair.assert_i32()?,
+                        95 => table.border_line_weight_override_flags = pair.assert_i32()?,
+                        96 => table.border_visibility_override_flags = pair.assert_i32()?,
+                        141 => table.row_heights.push(pair.assert_f64()?),
+                        142 => table.column_widths.push(pair.assert_f64()?),
+                        _ => common.apply_individual_pair(&pair, iter)?,
+                    },
+                }
+            } else {
+                common.apply_individual_pair(&pair, iter)?;
             }
         }
-
-        Ok(())
     }
-    fn apply_custom_writer<T>(
-        &self,
-        version: AcadVersion,
+    fn apply_custom_reader_unknown<I>(
+        common: &mut EntityCommon,
+        pairs: &mut Vec<CodePair>,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        // the common values come first; everything from the first entity-specific pair on is kept
+        let mut in_common_section = true;
+        loop {
+            let pair = next_pair!(iter);
+            if pair.code == x_data::XDATA_APPLICATIONNAME {
+                common.apply_individual_pair(&pair, iter)?;
+                continue;
+            }
+            if in_common_section {
+                in_common_section = match pair.code {
+                    100 => pair.assert_string()? == "AcDbEntity",
+                    5 | 6 | 8 | 38 | 48 | 60 | 62 | 67 | 92 | 102 | 284 | 310 | 330 | 347 | 370
+                    | 390 | 420 | 430 | 440 => true,
+                    _ => false,
+                };
+            }
+            if in_common_section {
+                common.apply_individual_pair(&pair, iter)?;
+            } else {
+                pairs.push(pair);
+            }
+        }
+    }
+    pub(crate) fn write<T>(
+        &self,
+        version: AcadVersion,
+        write_handles: bool,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        if self.specific.is_supported_on_version(version) {
+            writer.write_code_pair(&CodePair::new_str(0, self.specific.to_type_string()))?;
+            self.common.write(version, write_handles, writer)?;
+            if !self.apply_custom_writer(version, writer)? {
+                self.specific.write(&self.common, version, writer)?;
+            }
+
+            self.post_write(version, write_handles, writer)?;
+            for x in &self.common.x_data {
+                x.write(version, writer)?;
+            }
+        }
+
+        Ok(())
+    }
+    fn apply_custom_writer<T>(
+        &self,
+        version: AcadVersion,
         writer: &mut CodePairWriter<T>,
     ) -> DxfResult<bool>
     where
@@ -1367,12 +3049,29 @@ impl Entity {
             EntityType::OrdinateDimension(ref dim) => {
                 Entity::apply_custom_writer_ordinatedimension(dim, version, writer)?;
             }
+            EntityType::Hatch(ref hatch) => {
+                Entity::apply_custom_writer_hatch(hatch, version, writer)?;
+            }
+            EntityType::Mesh(ref mesh) => {
+                Entity::apply_custom_writer_mesh(mesh, writer)?;
+            }
+            EntityType::MLeader(ref mleader) => {
+                Entity::apply_custom_writer_mleader(mleader, version, writer)?;
+            }
             EntityType::Polyline(ref poly) => {
                 Entity::apply_custom_writer_polyline(poly, version, writer)?;
             }
+            EntityType::Table(ref table) => {
+                Entity::apply_custom_writer_table(table, writer)?;
+            }
             EntityType::Vertex(ref v) => {
                 Entity::apply_custom_writer_vertex(v, version, writer)?;
             }
+            EntityType::Unknown { ref pairs, .. } => {
+                for pair in pairs {
+                    writer.write_code_pair(pair)?;
+                }
+            }
             _ => return Ok(false), // no custom writer
         }
 
@@ -1480,6 +3179,200 @@ impl Entity {
         writer.write_code_pair(&CodePair::new_f64(34, dim.definition_point_3.z))?;
         Ok(true)
     }
+    fn apply_custom_writer_hatch<T>(
+        hatch: &Hatch,
+        version: AcadVersion,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<bool>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbHatch"))?;
+        writer.write_code_pair(&CodePair::new_f64(10, hatch.elevation_point.x))?;
+        writer.write_code_pair(&CodePair::new_f64(20, hatch.elevation_point.y))?;
+        writer.write_code_pair(&CodePair::new_f64(30, hatch.elevation_point.z))?;
+        writer.write_code_pair(&CodePair::new_f64(210, hatch.extrusion_direction.x))?;
+        writer.write_code_pair(&CodePair::new_f64(220, hatch.extrusion_direction.y))?;
+        writer.write_code_pair(&CodePair::new_f64(230, hatch.extrusion_direction.z))?;
+        writer.write_code_pair(&CodePair::new_string(2, &hatch.pattern_name))?;
+        writer.write_code_pair(&CodePair::new_i16(70, as_i16(hatch.is_solid_fill)))?;
+        writer.write_code_pair(&CodePair::new_i16(71, as_i16(hatch.is_associative)))?;
+        writer.write_code_pair(&CodePair::new_i32(91, hatch.boundary_paths.len() as i32))?;
+        for path in &hatch.boundary_paths {
+            path.write(version, writer)?;
+        }
+        writer.write_code_pair(&CodePair::new_i16(75, hatch.hatch_style as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(76, hatch.pattern_type as i16))?;
+        if !hatch.is_solid_fill {
+            writer.write_code_pair(&CodePair::new_f64(52, hatch.pattern_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(41, hatch.pattern_scale))?;
+            writer.write_code_pair(&CodePair::new_i16(77, as_i16(hatch.is_pattern_double)))?;
+        }
+        writer.write_code_pair(&CodePair::new_i16(
+            78,
+            hatch.pattern_definition_lines.len() as i16,
+        ))?;
+        for line in &hatch.pattern_definition_lines {
+            line.write(writer)?;
+        }
+        if hatch.pixel_size != 0.0 {
+            writer.write_code_pair(&CodePair::new_f64(47, hatch.pixel_size))?;
+        }
+        writer.write_code_pair(&CodePair::new_i32(98, hatch.seed_points.len() as i32))?;
+        for point in &hatch.seed_points {
+            writer.write_code_pair(&CodePair::new_f64(10, point.x))?;
+            writer.write_code_pair(&CodePair::new_f64(20, point.y))?;
+        }
+        if version >= AcadVersion::R2004 && hatch.is_gradient {
+            writer.write_code_pair(&CodePair::new_i32(450, 1))?;
+            writer.write_code_pair(&CodePair::new_i32(451, 0))?;
+            writer.write_code_pair(&CodePair::new_i32(
+                452,
+                i32::from(as_i16(hatch.is_single_color_gradient)),
+            ))?;
+            writer.write_code_pair(&CodePair::new_i32(453, hatch.gradient_colors.len() as i32))?;
+            writer.write_code_pair(&CodePair::new_f64(460, hatch.gradient_angle))?;
+            writer.write_code_pair(&CodePair::new_f64(461, hatch.gradient_shift))?;
+            writer.write_code_pair(&CodePair::new_f64(462, hatch.single_color_gradient_tint))?;
+            for color in &hatch.gradient_colors {
+                writer.write_code_pair(&CodePair::new_f64(463, color.value))?;
+                writer.write_code_pair(&CodePair::new_i16(63, color.color.get_raw_value()))?;
+                writer.write_code_pair(&CodePair::new_i32(421, color.color_24_bit))?;
+            }
+            writer.write_code_pair(&CodePair::new_string(470, &hatch.gradient_name))?;
+        }
+        Ok(true)
+    }
+    fn apply_custom_writer_mesh<T>(mesh: &Mesh, writer: &mut CodePairWriter<T>) -> DxfResult<bool>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbSubDMesh"))?;
+        writer.write_code_pair(&CodePair::new_i16(71, mesh.version as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(72, as_i16(mesh.is_blend_crease)))?;
+        writer.write_code_pair(&CodePair::new_i32(91, mesh.subdivision_level))?;
+        writer.write_code_pair(&CodePair::new_i32(92, mesh.vertices.len() as i32))?;
+        for vertex in &mesh.vertices {
+            writer.write_code_pair(&CodePair::new_f64(10, vertex.x))?;
+            writer.write_code_pair(&CodePair::new_f64(20, vertex.y))?;
+            writer.write_code_pair(&CodePair::new_f64(30, vertex.z))?;
+        }
+        let face_list_size: usize = mesh
+            .faces
+            .iter()
+            .map(|face| face.vertex_indices.len() + 1)
+            .sum();
+        writer.write_code_pair(&CodePair::new_i32(93, face_list_size as i32))?;
+        for face in &mesh.faces {
+            writer.write_code_pair(&CodePair::new_i32(90, face.vertex_indices.len() as i32))?;
+            for index in &face.vertex_indices {
+                writer.write_code_pair(&CodePair::new_i32(90, *index as i32))?;
+            }
+        }
+        writer.write_code_pair(&CodePair::new_i32(94, mesh.edges.len() as i32))?;
+        for edge in &mesh.edges {
+            writer.write_code_pair(&CodePair::new_i32(90, edge.start_vertex_index as i32))?;
+            writer.write_code_pair(&CodePair::new_i32(90, edge.end_vertex_index as i32))?;
+        }
+        writer.write_code_pair(&CodePair::new_i32(95, mesh.edges.len() as i32))?;
+        for edge in &mesh.edges {
+            writer.write_code_pair(&CodePair::new_f64(140, edge.crease))?;
+        }
+        if mesh.sub_entity_override_pairs.is_empty() {
+            writer.write_code_pair(&CodePair::new_i32(90, 0))?; // sub-entity property override count
+        } else {
+            for pair in &mesh.sub_entity_override_pairs {
+                writer.write_code_pair(pair)?;
+            }
+        }
+        Ok(true)
+    }
+    fn apply_custom_writer_mleader<T>(
+        mleader: &MLeader,
+        version: AcadVersion,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<bool>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbMLeader"))?;
+        writer.write_code_pair(&CodePair::new_i16(270, mleader.version))?;
+        mleader.context_data.write(version, writer)?;
+        writer.write_code_pair(&CodePair::new_string(
+            340,
+            &mleader.__style_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_i32(90, mleader.property_override_flags))?;
+        writer.write_code_pair(&CodePair::new_i16(170, mleader.leader_type))?;
+        writer.write_code_pair(&CodePair::new_i32(91, mleader.leader_line_color))?;
+        writer.write_code_pair(&CodePair::new_string(
+            341,
+            &mleader.__leader_line_type_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_i16(171, mleader.leader_line_weight))?;
+        writer.write_code_pair(&CodePair::new_bool(290, mleader.enable_landing))?;
+        writer.write_code_pair(&CodePair::new_bool(291, mleader.enable_dogleg))?;
+        writer.write_code_pair(&CodePair::new_f64(41, mleader.dogleg_length))?;
+        writer.write_code_pair(&CodePair::new_string(
+            342,
+            &mleader.__arrowhead_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_f64(42, mleader.arrowhead_size))?;
+        writer.write_code_pair(&CodePair::new_i16(172, mleader.content_type))?;
+        writer.write_code_pair(&CodePair::new_string(
+            343,
+            &mleader.__text_style_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_i16(173, mleader.text_left_attachment_type))?;
+        writer.write_code_pair(&CodePair::new_i32(95, mleader.text_right_attachment_type))?;
+        writer.write_code_pair(&CodePair::new_i16(174, mleader.text_angle_type))?;
+        writer.write_code_pair(&CodePair::new_i16(175, mleader.text_alignment_type))?;
+        writer.write_code_pair(&CodePair::new_i32(92, mleader.text_color))?;
+        writer.write_code_pair(&CodePair::new_bool(292, mleader.enable_frame_text))?;
+        writer.write_code_pair(&CodePair::new_string(
+            344,
+            &mleader.__block_content_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_i32(93, mleader.block_content_color))?;
+        writer.write_code_pair(&CodePair::new_f64(10, mleader.block_content_scale.x))?;
+        writer.write_code_pair(&CodePair::new_f64(20, mleader.block_content_scale.y))?;
+        writer.write_code_pair(&CodePair::new_f64(30, mleader.block_content_scale.z))?;
+        writer.write_code_pair(&CodePair::new_f64(43, mleader.block_content_rotation))?;
+        writer.write_code_pair(&CodePair::new_i16(
+            176,
+            mleader.block_content_connection_type,
+        ))?;
+        writer.write_code_pair(&CodePair::new_bool(293, mleader.enable_annotation_scale))?;
+        for arrowhead_override in &mleader.arrowhead_overrides {
+            writer.write_code_pair(&CodePair::new_i32(94, arrowhead_override.leader_line_index))?;
+            writer.write_code_pair(&CodePair::new_string(
+                345,
+                &arrowhead_override.arrowhead_handle.as_string(),
+            ))?;
+        }
+        for attribute in &mleader.block_attributes {
+            writer.write_code_pair(&CodePair::new_string(
+                330,
+                &attribute.attribute_definition_handle.as_string(),
+            ))?;
+            writer.write_code_pair(&CodePair::new_i16(177, attribute.index))?;
+            writer.write_code_pair(&CodePair::new_f64(44, attribute.width))?;
+            writer.write_code_pair(&CodePair::new_string(302, &attribute.text))?;
+        }
+        writer.write_code_pair(&CodePair::new_bool(294, mleader.is_text_direction_negative))?;
+        writer.write_code_pair(&CodePair::new_i16(178, mleader.text_align_in_ipe))?;
+        writer.write_code_pair(&CodePair::new_i16(179, mleader.text_attachment_point))?;
+        writer.write_code_pair(&CodePair::new_f64(45, mleader.overall_scale))?;
+        if version >= AcadVersion::R2010 {
+            writer.write_code_pair(&CodePair::new_i16(271, mleader.text_attachment_direction))?;
+            writer.write_code_pair(&CodePair::new_i16(272, mleader.text_bottom_attachment_type))?;
+            writer.write_code_pair(&CodePair::new_i16(273, mleader.text_top_attachment_type))?;
+        }
+        if version >= AcadVersion::R2013 {
+            writer.write_code_pair(&CodePair::new_bool(295, mleader.extend_leader_to_text))?;
+        }
+        Ok(true)
+    }
     fn apply_custom_writer_polyline<T>(
         poly: &Polyline,
         version: AcadVersion,
@@ -1542,6 +3435,58 @@ impl Entity {
         }
         Ok(true)
     }
+    fn apply_custom_writer_table<T>(
+        table: &Table,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<bool>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbBlockReference"))?;
+        writer.write_code_pair(&CodePair::new_string(2, &table.block_name))?;
+        writer.write_code_pair(&CodePair::new_f64(10, table.insertion_point.x))?;
+        writer.write_code_pair(&CodePair::new_f64(20, table.insertion_point.y))?;
+        writer.write_code_pair(&CodePair::new_f64(30, table.insertion_point.z))?;
+        writer.write_code_pair(&CodePair::new_str(100, "AcDbTable"))?;
+        writer.write_code_pair(&CodePair::new_i16(280, table.version))?;
+        writer.write_code_pair(&CodePair::new_string(
+            342,
+            &table.__table_style_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_string(
+            343,
+            &table.__block_record_handle.as_string(),
+        ))?;
+        writer.write_code_pair(&CodePair::new_f64(11, table.horizontal_direction.x))?;
+        writer.write_code_pair(&CodePair::new_f64(21, table.horizontal_direction.y))?;
+        writer.write_code_pair(&CodePair::new_f64(31, table.horizontal_direction.z))?;
+        writer.write_code_pair(&CodePair::new_i32(90, table.table_value_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(91, table.row_count() as i32))?;
+        writer.write_code_pair(&CodePair::new_i32(92, table.column_count() as i32))?;
+        writer.write_code_pair(&CodePair::new_i32(93, table.override_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(94, table.border_color_override_flags))?;
+        writer.write_code_pair(&CodePair::new_i32(
+            95,
+            table.border_line_weight_override_flags,
+        ))?;
+        writer.write_code_pair(&CodePair::new_i32(
+            96,
+            table.border_visibility_override_flags,
+        ))?;
+        for row_height in &table.row_heights {
+            writer.write_code_pair(&CodePair::new_f64(141, *row_height))?;
+        }
+        for column_width in &table.column_widths {
+            writer.write_code_pair(&CodePair::new_f64(142, *column_width))?;
+        }
+        for cell in &table.cells {
+            cell.write(writer)?;
+        }
+        for pair in &table.__unsupported_pairs {
+            writer.write_code_pair(pair)?;
+        }
+        Ok(true)
+    }
     fn apply_custom_writer_vertex<T>(
         v: &Vertex,
         version: AcadVersion,
@@ -1742,8 +3687,117 @@ mod tests {
             .join("\r\n")
             .as_str(),
         );
-        let entities = drawing.entities();
-        assert_eq!(0, entities.count());
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Unknown {
+                ref type_string,
+                ref pairs,
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string);
+                assert_eq!(vec![CodePair::new_str(1, "unsupported string")], *pairs);
+            }
+            _ => panic!("expected an unknown entity"),
+        }
+    }
+
+    #[test]
+    fn read_unsupported_entity_with_common_values() {
+        let ent = read_entity(
+            "UNSUPPORTED_ENTITY",
+            vec![
+                "  5",
+                "2A",
+                "100",
+                "AcDbEntity",
+                "  8",
+                "some-layer",
+                " 62",
+                "     3",
+                "100",
+                "AcDbUnsupported",
+                " 10",
+                "1.0",
+                "  8",
+                "not-a-layer",
+            ]
+            .join("\r\n"),
+        );
+        assert_eq!(Handle(0x2A), ent.common.handle);
+        assert_eq!("some-layer", ent.common.layer);
+        assert_eq!(Some(3), ent.common.color.index());
+        match ent.specific {
+            EntityType::Unknown { ref pairs, .. } => {
+                assert_eq!(
+                    vec![
+                        CodePair::new_str(100, "AcDbUnsupported"),
+                        CodePair::new_f64(10, 1.0),
+                        CodePair::new_str(8, "not-a-layer"),
+                    ],
+                    *pairs
+                );
+            }
+            _ => panic!("expected an unknown entity"),
+        }
+    }
+
+    #[test]
+    fn round_trip_unsupported_entity() {
+        let drawing = parse_drawing(
+            vec![
+                "  0",
+                "SECTION",
+                "  2",
+                "ENTITIES",
+                "  0",
+                "UNSUPPORTED_ENTITY",
+                "  5",
+                "2A",
+                "100",
+                "AcDbEntity",
+                "  8",
+                "0",
+                "100",
+                "AcDbUnsupported",
+                "  1",
+                "unsupported string",
+                " 40",
+                "1.5",
+                "  0",
+                "ENDSEC",
+                "  0",
+                "EOF",
+            ]
+            .join("\r\n")
+            .as_str(),
+        );
+        assert_contains(
+            &drawing,
+            [
+                "  0",
+                "UNSUPPORTED_ENTITY",
+                "  5",
+                "2A",
+                "100",
+                "AcDbEntity",
+                "  8",
+                "0",
+            ]
+            .join("\r\n"),
+        );
+        assert_contains(
+            &drawing,
+            [
+                "100",
+                "AcDbUnsupported",
+                "  1",
+                "unsupported string",
+                " 40",
+                "1.5",
+                "  0",
+            ]
+            .join("\r\n"),
+        );
     }
 
     #[test]
@@ -1771,12 +3825,20 @@ mod tests {
             .as_str(),
         );
         let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
+        assert_eq!(3, entities.len());
         match entities[0].specific {
             EntityType::Line(_) => (),
             _ => panic!("expected a line"),
         }
         match entities[1].specific {
+            EntityType::Unknown {
+                ref type_string, ..
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string)
+            }
+            _ => panic!("expected an unknown entity"),
+        }
+        match entities[2].specific {
             EntityType::Circle(_) => (),
             _ => panic!("expected a circle"),
         }
@@ -2740,40 +4802,1394 @@ mod tests {
         drawing.add_entity(Entity::new(EntityType::LwPolyline(poly)));
         assert_contains(
             &drawing,
-            vec![
-                "100",
-                "AcDbPolyline",
-                " 90",
-                "        2",
-                " 70",
-                "     0",
-                " 43",
-                "43.0",
-                // vertex 1
-                " 10",
-                "1.1",
-                " 20",
-                "2.1",
-                " 91",
-                "        0",
-                // vertex 2
-                " 10",
-                "1.2",
-                " 20",
-                "2.2",
-                " 91",
-                "       92",
-                " 40",
-                "40.2",
-                " 41",
-                "41.2",
-                " 42",
-                "42.2",
+            vec![
+                "100",
+                "AcDbPolyline",
+                " 90",
+                "        2",
+                " 70",
+                "     0",
+                " 43",
+                "43.0",
+                // vertex 1
+                " 10",
+                "1.1",
+                " 20",
+                "2.1",
+                " 91",
+                "        0",
+                // vertex 2
+                " 10",
+                "1.2",
+                " 20",
+                "2.2",
+                " 91",
+                "       92",
+                " 40",
+                "40.2",
+                " 41",
+                "41.2",
+                " 42",
+                "42.2",
+            ]
+            .join("\r\n"),
+        );
+    }
+
+    #[test]
+    fn read_hatch() {
+        let hatch = read_entity(
+            "HATCH",
+            vec![
+                "100",
+                "AcDbHatch",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 30",
+                "3.0",
+                "  2",
+                "ANSI31",
+                " 70",
+                "     0",
+                " 71",
+                "     1",
+                " 91",
+                "        2",
+                // polyline path
+                " 92",
+                "        3",
+                " 72",
+                "     1",
+                " 73",
+                "     1",
+                " 93",
+                "        2",
+                " 10",
+                "1.0",
+                " 20",
+                "2.0",
+                " 42",
+                "0.5",
+                " 10",
+                "3.0",
+                " 20",
+                "4.0",
+                " 42",
+                "0.0",
+                " 97",
+                "        1",
+                "330",
+                "ABC",
+                // edge path
+                " 92",
+                "        0",
+                " 93",
+                "        2",
+                " 72",
+                "     1",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 11",
+                "1.0",
+                " 21",
+                "1.0",
+                " 72",
+                "     2",
+                " 10",
+                "1.0",
+                " 20",
+                "0.0",
+                " 40",
+                "1.0",
+                " 50",
+                "0.0",
+                " 51",
+                "90.0",
+                " 73",
+                "     1",
+                " 97",
+                "        0",
+                " 75",
+                "     1",
+                " 76",
+                "     1",
+                " 52",
+                "45.0",
+                " 41",
+                "2.0",
+                " 77",
+                "     0",
+                " 78",
+                "     1",
+                " 53",
+                "45.0",
+                " 43",
+                "0.0",
+                " 44",
+                "0.0",
+                " 45",
+                "-0.1",
+                " 46",
+                "0.1",
+                " 79",
+                "     2",
+                " 49",
+                "0.5",
+                " 49",
+                "-0.25",
+                " 98",
+                "        1",
+                " 10",
+                "5.0",
+                " 20",
+                "6.0",
+            ]
+            .join("\r\n"),
+        );
+        match hatch.specific {
+            EntityType::Hatch(ref hatch) => {
+                assert_eq!(3.0, hatch.elevation_point.z);
+                assert_eq!("ANSI31", hatch.pattern_name);
+                assert!(!hatch.is_solid_fill);
+                assert!(hatch.is_associative);
+                assert_eq!(2, hatch.boundary_paths.len());
+
+                let path = &hatch.boundary_paths[0];
+                assert!(path.get_is_polyline());
+                assert!(path.get_is_external());
+                assert_eq!(vec![Handle(0xABC)], path.source_boundary_object_handles);
+                match path.data {
+                    HatchBoundaryPathData::Polyline {
+                        is_closed,
+                        ref vertices,
+                    } => {
+                        assert!(is_closed);
+                        assert_eq!(
+                            vec![
+                                HatchPolylineVertex::new(1.0, 2.0, 0.5),
+                                HatchPolylineVertex::new(3.0, 4.0, 0.0),
+                            ],
+                            *vertices
+                        );
+                    }
+                    _ => panic!("expected a polyline path"),
+                }
+
+                let path = &hatch.boundary_paths[1];
+                assert!(!path.get_is_polyline());
+                match path.data {
+                    HatchBoundaryPathData::Edges(ref edges) => {
+                        assert_eq!(
+                            vec![
+                                HatchBoundaryPathEdge::Line {
+                                    start: Point::new(0.0, 0.0, 0.0),
+                                    end: Point::new(1.0, 1.0, 0.0),
+                                },
+                                HatchBoundaryPathEdge::CircularArc {
+                                    center: Point::new(1.0, 0.0, 0.0),
+                                    radius: 1.0,
+                                    start_angle: 0.0,
+                                    end_angle: 90.0,
+                                    is_counter_clockwise: true,
+                                },
+                            ],
+                            *edges
+                        );
+                    }
+                    _ => panic!("expected an edge path"),
+                }
+
+                assert_eq!(HatchStyle::OutermostAreaOnly, hatch.hatch_style);
+                assert_eq!(HatchPatternType::Predefined, hatch.pattern_type);
+                assert_eq!(45.0, hatch.pattern_angle);
+                assert_eq!(2.0, hatch.pattern_scale);
+                assert_eq!(1, hatch.pattern_definition_lines.len());
+                let line = &hatch.pattern_definition_lines[0];
+                assert_eq!(45.0, line.angle);
+                assert_eq!(Vector::new(-0.1, 0.1, 0.0), line.offset);
+                assert_eq!(vec![0.5, -0.25], line.dash_lengths);
+                assert_eq!(vec![Point::new(5.0, 6.0, 0.0)], hatch.seed_points);
+            }
+            _ => panic!("expected a hatch"),
+        }
+    }
+
+    #[test]
+    fn write_hatch() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        let hatch = Hatch {
+            boundary_paths: vec![HatchBoundaryPath::polyline(
+                vec![
+                    HatchPolylineVertex::new(1.0, 2.0, 0.0),
+                    HatchPolylineVertex::new(3.0, 4.0, 0.0),
+                ],
+                true,
+            )],
+            is_gradient: true,
+            gradient_colors: vec![HatchGradientColor {
+                value: 0.5,
+                color: Color::from_index(3),
+                color_24_bit: 0x00FF00,
+            }],
+            gradient_name: String::from("LINEAR"),
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Hatch(hatch)));
+        assert_contains(
+            &drawing,
+            vec![
+                "100",
+                "AcDbHatch",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                "210",
+                "0.0",
+                "220",
+                "0.0",
+                "230",
+                "1.0",
+                "  2",
+                "SOLID",
+                " 70",
+                "     1",
+                " 71",
+                "     0",
+                " 91",
+                "        1",
+                " 92",
+                "        3",
+                " 72",
+                "     0",
+                " 73",
+                "     1",
+                " 93",
+                "        2",
+                " 10",
+                "1.0",
+                " 20",
+                "2.0",
+                " 10",
+                "3.0",
+                " 20",
+                "4.0",
+                " 97",
+                "        0",
+                " 75",
+                "     0",
+                " 76",
+                "     1",
+                " 78",
+                "     0",
+                " 98",
+                "        0",
+                "450",
+                "        1",
+                "451",
+                "        0",
+                "452",
+                "        0",
+                "453",
+                "        1",
+                "460",
+                "0.0",
+                "461",
+                "0.0",
+                "462",
+                "0.0",
+                "463",
+                "0.5",
+                " 63",
+                "     3",
+                "421",
+                "    65280",
+                "470",
+                "LINEAR",
+            ]
+            .join("\r\n"),
+        );
+    }
+
+    #[test]
+    fn round_trip_hatch() {
+        let hatch = Hatch {
+            pattern_name: String::from("ANSI31"),
+            is_solid_fill: false,
+            boundary_paths: vec![
+                HatchBoundaryPath::polyline(
+                    vec![
+                        HatchPolylineVertex::new(1.0, 2.0, 0.5),
+                        HatchPolylineVertex::new(3.0, 4.0, 0.0),
+                    ],
+                    true,
+                ),
+                HatchBoundaryPath::edges(vec![
+                    HatchBoundaryPathEdge::Line {
+                        start: Point::new(0.0, 0.0, 0.0),
+                        end: Point::new(1.0, 1.0, 0.0),
+                    },
+                    HatchBoundaryPathEdge::EllipticArc {
+                        center: Point::new(1.0, 1.0, 0.0),
+                        major_axis: Vector::new(2.0, 0.0, 0.0),
+                        minor_axis_ratio: 0.5,
+                        start_angle: 0.0,
+                        end_angle: 180.0,
+                        is_counter_clockwise: true,
+                    },
+                    HatchBoundaryPathEdge::Spline {
+                        degree_of_curve: 3,
+                        is_rational: false,
+                        is_periodic: false,
+                        knot_values: vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+                        control_points: vec![
+                            Point::new(0.0, 0.0, 0.0),
+                            Point::new(1.0, 1.0, 0.0),
+                            Point::new(2.0, 1.0, 0.0),
+                            Point::new(3.0, 0.0, 0.0),
+                        ],
+                        weights: vec![],
+                        fit_points: vec![],
+                        start_tangent: Vector::zero(),
+                        end_tangent: Vector::zero(),
+                    },
+                ]),
+            ],
+            pattern_definition_lines: vec![HatchPatternDefinitionLine {
+                angle: 45.0,
+                base_point: Point::origin(),
+                offset: Vector::new(-0.1, 0.1, 0.0),
+                dash_lengths: vec![0.5, -0.25],
+            }],
+            seed_points: vec![Point::new(5.0, 6.0, 0.0)],
+            is_gradient: true,
+            gradient_colors: vec![HatchGradientColor {
+                value: 0.5,
+                color: Color::from_index(3),
+                color_24_bit: 0x00FF00,
+            }],
+            ..Default::default()
+        };
+        for version in &[
+            AcadVersion::R14,
+            AcadVersion::R2000,
+            AcadVersion::R2004,
+            AcadVersion::R2007,
+            AcadVersion::R2010,
+            AcadVersion::R2013,
+            AcadVersion::R2018,
+        ] {
+            let mut drawing = Drawing::new();
+            drawing.header.version = *version;
+            drawing.add_entity(Entity::new(EntityType::Hatch(hatch.clone())));
+
+            let drawing = parse_drawing(&to_test_string(&drawing));
+            let entities = drawing.entities().collect::<Vec<_>>();
+            assert_eq!(1, entities.len());
+            match entities[0].specific {
+                EntityType::Hatch(ref h) => {
+                    assert_eq!(hatch.pattern_name, h.pattern_name);
+                    assert_eq!(hatch.boundary_paths, h.boundary_paths);
+                    assert_eq!(hatch.pattern_definition_lines, h.pattern_definition_lines);
+                    assert_eq!(hatch.seed_points, h.seed_points);
+                    if *version >= AcadVersion::R2004 {
+                        assert_eq!(hatch.gradient_colors, h.gradient_colors);
+                    } else {
+                        assert!(h.gradient_colors.is_empty());
+                    }
+                }
+                _ => panic!("expected a hatch"),
+            }
+        }
+    }
+
+    #[test]
+    fn read_mesh() {
+        let mesh = read_entity(
+            "MESH",
+            vec![
+                "100",
+                "AcDbSubDMesh",
+                " 71",
+                "     2",
+                " 72",
+                "     0",
+                " 91",
+                "        1",
+                " 92",
+                "        4",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 10",
+                "1.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 10",
+                "1.0",
+                " 20",
+                "1.0",
+                " 30",
+                "0.0",
+                " 10",
+                "0.0",
+                " 20",
+                "1.0",
+                " 30",
+                "1.0",
+                " 93",
+                "        7",
+                " 90",
+                "        3",
+                " 90",
+                "        0",
+                " 90",
+                "        1",
+                " 90",
+                "        2",
+                " 90",
+                "        2",
+                " 90",
+                "        2",
+                " 90",
+                "        3",
+                " 94",
+                "        2",
+                " 90",
+                "        0",
+                " 90",
+                "        1",
+                " 90",
+                "        1",
+                " 90",
+                "        2",
+                " 95",
+                "        2",
+                "140",
+                "0.5",
+                "140",
+                "1.5",
+                " 90",
+                "        0",
+            ]
+            .join("\r\n"),
+        );
+        match mesh.specific {
+            EntityType::Mesh(ref mesh) => {
+                assert_eq!(1, mesh.subdivision_level);
+                assert_eq!(4, mesh.vertices.len());
+                assert_eq!(Point::new(0.0, 1.0, 1.0), mesh.vertices[3]);
+                assert_eq!(
+                    vec![MeshFace::new(vec![0, 1, 2]), MeshFace::new(vec![2, 3])],
+                    mesh.faces
+                );
+                assert_eq!(
+                    vec![
+                        MeshEdge {
+                            start_vertex_index: 0,
+                            end_vertex_index: 1,
+                            crease: 0.5,
+                        },
+                        MeshEdge {
+                            start_vertex_index: 1,
+                            end_vertex_index: 2,
+                            crease: 1.5,
+                        },
+                    ],
+                    mesh.edges
+                );
+            }
+            _ => panic!("expected a mesh"),
+        }
+    }
+
+    #[test]
+    fn round_trip_mesh_with_sub_entity_overrides() {
+        let overrides = [
+            " 90",
+            "        1",
+            " 91",
+            "        0",
+            " 92",
+            "        1",
+            " 90",
+            "        0",
+            " 63",
+            "     1",
+        ];
+        let body = [
+            "100",
+            "AcDbSubDMesh",
+            " 92",
+            "        0",
+            " 93",
+            "        0",
+            " 94",
+            "        0",
+            " 95",
+            "        0",
+        ]
+        .iter()
+        .chain(overrides.iter())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\r\n");
+        let entity = read_entity("MESH", body);
+        match entity.specific {
+            EntityType::Mesh(ref mesh) => assert_eq!(5, mesh.sub_entity_override_pairs.len()),
+            _ => panic!("expected a mesh"),
+        }
+
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2013;
+        drawing.add_entity(entity);
+        assert_contains(
+            &drawing,
+            [" 95", "        0"]
+                .iter()
+                .chain(overrides.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\r\n"),
+        );
+    }
+
+    #[test]
+    fn read_mesh_with_negative_index_is_rejected() {
+        let text = [
+            "0",
+            "SECTION",
+            "2",
+            "ENTITIES",
+            "0",
+            "MESH",
+            "100",
+            "AcDbSubDMesh",
+            "93",
+            "2",
+            "90",
+            "1",
+            "90",
+            "-1",
+            "0",
+            "ENDSEC",
+            "0",
+            "EOF",
+        ]
+        .join("\r\n");
+        let drawing = Drawing::load(&mut text.as_bytes()).unwrap();
+        assert_eq!(0, drawing.entities().count());
+
+        let mut reader = DxfReader::open(text.as_bytes()).unwrap();
+        assert!(reader.entities().any(|e| e.is_err()));
+    }
+
+    #[test]
+    fn write_mesh() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2013;
+        let mesh = Mesh {
+            vertices: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+            ],
+            faces: vec![MeshFace::new(vec![0, 1, 2])],
+            edges: vec![MeshEdge::new(0, 1)],
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Mesh(mesh)));
+        assert_contains(
+            &drawing,
+            vec![
+                "100",
+                "AcDbSubDMesh",
+                " 71",
+                "     2",
+                " 72",
+                "     0",
+                " 91",
+                "        0",
+                " 92",
+                "        3",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 10",
+                "1.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 10",
+                "1.0",
+                " 20",
+                "1.0",
+                " 30",
+                "0.0",
+                " 93",
+                "        4",
+                " 90",
+                "        3",
+                " 90",
+                "        0",
+                " 90",
+                "        1",
+                " 90",
+                "        2",
+                " 94",
+                "        1",
+                " 90",
+                "        0",
+                " 90",
+                "        1",
+                " 95",
+                "        1",
+                "140",
+                "0.0",
+                " 90",
+                "        0",
+            ]
+            .join("\r\n"),
+        );
+    }
+
+    #[test]
+    fn read_mleader() {
+        let mleader = read_entity(
+            "MULTILEADER",
+            vec![
+                "100",
+                "AcDbEntity",
+                "  8",
+                "layer",
+                "100",
+                "AcDbMLeader",
+                "270",
+                "     2",
+                "300",
+                "CONTEXT_DATA{",
+                " 40",
+                "1.0",
+                "290",
+                "     1",
+                "304",
+                "some text",
+                " 12",
+                "5.0",
+                " 22",
+                "6.0",
+                " 32",
+                "0.0",
+                "296",
+                "     0",
+                "302",
+                "LEADER{",
+                "290",
+                "     1",
+                "291",
+                "     1",
+                " 10",
+                "4.0",
+                " 20",
+                "6.0",
+                " 30",
+                "0.0",
+                " 11",
+                "1.0",
+                " 21",
+                "0.0",
+                " 31",
+                "0.0",
+                " 90",
+                "        0",
+                " 40",
+                "0.5",
+                "304",
+                "LEADER_LINE{",
+                " 10",
+                "0.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 10",
+                "2.0",
+                " 20",
+                "3.0",
+                " 30",
+                "0.0",
+                " 91",
+                "        0",
+                "305",
+                "}",
+                "304",
+                "LEADER_LINE{",
+                " 10",
+                "8.0",
+                " 20",
+                "0.0",
+                " 30",
+                "0.0",
+                " 91",
+                "        1",
+                "305",
+                "}",
+                "303",
+                "}",
+                "301",
+                "}",
+                "340",
+                "ABC",
+                "172",
+                "     2",
+                " 94",
+                "        1",
+                "345",
+                "DEF",
+                "330",
+                "123",
+                "177",
+                "     3",
+                " 44",
+                "2.5",
+                "302",
+                "attribute text",
+            ]
+            .join("\r\n"),
+        );
+        assert_eq!("layer", mleader.common.layer);
+        match mleader.specific {
+            EntityType::MLeader(ref mleader) => {
+                assert_eq!(Handle(0xABC), mleader.__style_handle);
+                assert_eq!(2, mleader.content_type);
+                let context = &mleader.context_data;
+                let text = context.text_content.as_ref().unwrap();
+                assert_eq!("some text", text.text);
+                assert_eq!(Point::new(5.0, 6.0, 0.0), text.location);
+                assert!(context.block_content.is_none());
+                assert_eq!(1, context.roots.len());
+                let root = &context.roots[0];
+                assert_eq!(Some(Point::new(4.0, 6.0, 0.0)), root.last_leader_line_point);
+                assert_eq!(Some(Vector::x_axis()), root.dogleg_vector);
+                assert_eq!(0.5, root.dogleg_length);
+                assert_eq!(2, root.lines.len());
+                assert_eq!(
+                    vec![Point::new(0.0, 0.0, 0.0), Point::new(2.0, 3.0, 0.0)],
+                    root.lines[0].vertices
+                );
+                assert_eq!(vec![Point::new(8.0, 0.0, 0.0)], root.lines[1].vertices);
+                assert_eq!(1, root.lines[1].leader_line_index);
+                assert_eq!(
+                    vec![MLeaderArrowheadOverride {
+                        leader_line_index: 1,
+                        arrowhead_handle: Handle(0xDEF),
+                    }],
+                    mleader.arrowhead_overrides
+                );
+                assert_eq!(
+                    vec![MLeaderBlockAttribute {
+                        attribute_definition_handle: Handle(0x123),
+                        index: 3,
+                        width: 2.5,
+                        text: String::from("attribute text"),
+                    }],
+                    mleader.block_attributes
+                );
+            }
+            _ => panic!("expected an mleader"),
+        }
+    }
+
+    #[test]
+    fn round_trip_mleader() {
+        let line = |index: i32, x: f64| MLeaderLine {
+            vertices: vec![Point::new(x, 0.0, 0.0), Point::new(x, 1.0, 0.0)],
+            breaks: vec![MLeaderLineBreak {
+                segment_index: 0,
+                start: Point::new(x, 0.25, 0.0),
+                end: Point::new(x, 0.5, 0.0),
+            }],
+            leader_line_index: index,
+            ..Default::default()
+        };
+        let mleader = MLeader {
+            context_data: MLeaderContextData {
+                text_content: Some(MLeaderTextContent {
+                    text: String::from("some text"),
+                    column_sizes: vec![1.0, 2.0],
+                    ..Default::default()
+                }),
+                block_content: Some(MLeaderBlockContent {
+                    location: Point::new(1.0, 2.0, 3.0),
+                    ..Default::default()
+                }),
+                roots: vec![
+                    MLeaderRoot {
+                        last_leader_line_point: Some(Point::new(1.0, 1.0, 0.0)),
+                        breaks: vec![MLeaderBreak {
+                            start: Point::new(0.0, 0.0, 0.0),
+                            end: Point::new(1.0, 0.0, 0.0),
+                        }],
+                        lines: vec![line(0, 0.0), line(1, 1.0)],
+                        ..Default::default()
+                    },
+                    MLeaderRoot {
+                        dogleg_vector: Some(Vector::x_axis()),
+                        leader_branch_index: 1,
+                        lines: vec![line(2, 2.0)],
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            arrowhead_overrides: vec![MLeaderArrowheadOverride {
+                leader_line_index: 1,
+                arrowhead_handle: Handle(0xDEF),
+            }],
+            ..Default::default()
+        };
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2013;
+        drawing.add_entity(Entity::new(EntityType::MLeader(mleader.clone())));
+        let written = to_test_string(&drawing);
+
+        let drawing = parse_drawing(&written);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::MLeader(ref m) => assert_eq!(mleader, *m),
+            _ => panic!("expected an mleader"),
+        }
+
+        // the nested groups must be written back in exactly the same order
+        let context_data = |s: &str| {
+            let start = s.find("CONTEXT_DATA{").unwrap();
+            let end = start + s[start..].find("\r\n301\r\n}").unwrap();
+            s[start..end].to_string()
+        };
+        assert_eq!(
+            context_data(&written),
+            context_data(&to_test_string(&drawing))
+        );
+    }
+
+    #[test]
+    fn round_trip_mleader_context_data_with_unsupported_codes() {
+        // as written by AutoCAD 2018, which adds per-line overrides to `LEADER_LINE{`
+        let context_data = [
+            "300",
+            "CONTEXT_DATA{",
+            " 40",
+            "1.0",
+            " 10",
+            "4.0",
+            " 20",
+            "2.0",
+            " 30",
+            "0.0",
+            " 41",
+            "0.18",
+            "140",
+            "0.18",
+            "145",
+            "0.09",
+            "174",
+            "1",
+            "175",
+            "1",
+            "176",
+            "0",
+            "177",
+            "0",
+            "290",
+            "1",
+            "304",
+            "note",
+            " 11",
+            "0.0",
+            " 21",
+            "0.0",
+            " 31",
+            "1.0",
+            "340",
+            "11",
+            " 12",
+            "4.0",
+            " 22",
+            "2.0",
+            " 32",
+            "0.0",
+            " 13",
+            "1.0",
+            " 23",
+            "0.0",
+            " 33",
+            "0.0",
+            " 42",
+            "0.0",
+            " 43",
+            "0.0",
+            " 44",
+            "0.0",
+            " 45",
+            "1.0",
+            "170",
+            "1",
+            " 90",
+            "-1056964608",
+            "171",
+            "1",
+            "172",
+            "5",
+            " 91",
+            "-1056964608",
+            "141",
+            "1.5",
+            " 92",
+            "0",
+            "291",
+            "0",
+            "292",
+            "0",
+            "173",
+            "0",
+            "293",
+            "0",
+            "142",
+            "0.0",
+            "143",
+            "0.0",
+            "294",
+            "0",
+            "295",
+            "1",
+            "296",
+            "0",
+            "110",
+            "0.0",
+            "120",
+            "0.0",
+            "130",
+            "0.0",
+            "111",
+            "1.0",
+            "121",
+            "0.0",
+            "131",
+            "0.0",
+            "112",
+            "0.0",
+            "122",
+            "1.0",
+            "132",
+            "0.0",
+            "297",
+            "0",
+            "302",
+            "LEADER{",
+            "290",
+            "1",
+            "291",
+            "1",
+            " 10",
+            "3.5",
+            " 20",
+            "2.0",
+            " 30",
+            "0.0",
+            " 11",
+            "1.0",
+            " 21",
+            "0.0",
+            " 31",
+            "0.0",
+            " 90",
+            "0",
+            " 40",
+            "0.36",
+            "304",
+            "LEADER_LINE{",
+            " 10",
+            "0.0",
+            " 20",
+            "0.0",
+            " 30",
+            "0.0",
+            " 91",
+            "0",
+            "170",
+            "1",
+            " 92",
+            "-1056964608",
+            "340",
+            "14",
+            "171",
+            "-2",
+            " 40",
+            "0.18",
+            "341",
+            "0",
+            " 93",
+            "0",
+            "305",
+            "}",
+            "271",
+            "0",
+            "303",
+            "}",
+            "272",
+            "9",
+            "273",
+            "9",
+            "301",
+            "}",
+        ];
+        let body = ["100", "AcDbMLeader", "270", "2"]
+            .iter()
+            .chain(context_data.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2018;
+        drawing.add_entity(read_entity("MULTILEADER", body));
+
+        let written = to_test_string(&drawing);
+        let lines = written.lines().map(str::trim).collect::<Vec<_>>();
+        let start = lines.iter().position(|l| *l == "CONTEXT_DATA{").unwrap() - 1;
+        let actual = lines[start..start + context_data.len()]
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        let expected = context_data
+            .chunks(2)
+            .map(|pair| (pair[0].trim(), pair[1]))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn round_trip_mleader_with_style() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2013;
+        let style = drawing
+            .add_object(Object::new(ObjectType::MLeaderStyle(MLeaderStyle {
+                m_leader_style_description: String::from("some style"),
+                ..Default::default()
+            })))
+            .clone();
+        let mut mleader = MLeader::default();
+        mleader.set_style(&style).unwrap();
+        drawing.add_entity(Entity::new(EntityType::MLeader(mleader)));
+
+        let drawing = parse_drawing(&to_test_string(&drawing));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::MLeader(ref m) => match m.get_style(&drawing) {
+                Some(Object {
+                    specific: ObjectType::MLeaderStyle(ref style),
+                    ..
+                }) => assert_eq!("some style", style.m_leader_style_description),
+                _ => panic!("expected an mleader style"),
+            },
+            _ => panic!("expected an mleader"),
+        }
+    }
+
+    #[test]
+    fn read_table() {
+        let table = read_entity(
+            "ACAD_TABLE",
+            vec![
+                "100",
+                "AcDbEntity",
+                "  8",
+                "layer",
+                "100",
+                "AcDbBlockReference",
+                "  2",
+                "*T1",
+                " 10",
+                "1.0",
+                " 20",
+                "2.0",
+                " 30",
+                "0.0",
+                "100",
+                "AcDbTable",
+                "342",
+                "ABC",
+                " 91",
+                "        2",
+                " 92",
+                "        2",
+                "141",
+                "0.5",
+                "141",
+                "0.75",
+                "142",
+                "3.0",
+                "142",
+                "4.0",
+                // cell (0, 0), merged across both columns
+                "171",
+                "     1",
+                "173",
+                "     1",
+                "175",
+                "     2",
+                "176",
+                "     1",
+                " 91",
+                "        0",
+                " 63",
+                "     1",
+                "  2",
+                "long ",
+                "  1",
+                "title",
+                "  7",
+                "Standard",
+                // cell (0, 1)
+                "171",
+                "     1",
+                "173",
+                "     1",
+                "  1",
+                "",
+                // cell (1, 0)
+                "171",
+                "     2",
+                "340",
+                "DEF",
+                "144",
+                "2.0",
+                "179",
+                "     1",
+                "331",
+                "123",
+                "300",
+                "attribute value",
+                // cell (1, 1)
+                "171",
+                "     1",
+                "344",
+                "456",
+                "  1",
+                "field value",
+            ]
+            .join("\r\n"),
+        );
+        assert_eq!("layer", table.common.layer);
+        match table.specific {
+            EntityType::Table(ref table) => {
+                assert_eq!("*T1", table.block_name);
+                assert_eq!(Point::new(1.0, 2.0, 0.0), table.insertion_point);
+                assert_eq!(Handle(0xABC), table.__table_style_handle);
+                assert_eq!(2, table.row_count());
+                assert_eq!(2, table.column_count());
+                assert_eq!(vec![0.5, 0.75], table.row_heights);
+                assert_eq!(vec![3.0, 4.0], table.column_widths);
+
+                let title = table.cell(0, 0).unwrap();
+                assert_eq!(TableCellType::Text, title.cell_type);
+                assert_eq!("long title", title.text);
+                assert_eq!("Standard", title.text_style_name);
+                assert_eq!(Some(Color::from_index(1)), title.overrides.fill_color);
+                assert_eq!(None, title.overrides.content_color);
+                assert_eq!(
+                    vec![TableCellRange {
+                        top_row: 0,
+                        left_column: 0,
+                        bottom_row: 0,
+                        right_column: 1,
+                    }],
+                    table.merged_ranges()
+                );
+
+                let block = table.cell(1, 0).unwrap();
+                assert_eq!(TableCellType::Block, block.cell_type);
+                assert_eq!(Handle(0xDEF), block.block_record_handle);
+                assert_eq!(2.0, block.block_scale);
+                assert_eq!(
+                    vec![TableCellBlockAttribute {
+                        attribute_definition_handle: Handle(0x123),
+                        text: String::from("attribute value"),
+                    }],
+                    block.block_attributes
+                );
+
+                let field = table.cell(1, 1).unwrap();
+                assert_eq!(Handle(0x456), field.field_handle);
+                assert_eq!("field value", field.text);
+
+                assert!(table.cell(2, 0).is_none());
+                assert!(table.cell(0, 2).is_none());
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn read_table_level_values_after_the_cells() {
+        let text = [
+            "100",
+            "AcDbTable",
+            "171",
+            "     1",
+            "177",
+            "     0",
+            "  1",
+            "cell",
+            // table-level values, some of which share codes with the cells
+            "280",
+            "     0",
+            " 63",
+            "     5",
+            "140",
+            "0.25",
+            "1001",
+            "ACAD",
+            "1000",
+            "x data",
+        ]
+        .join("\r\n");
+        let table = read_entity("ACAD_TABLE", text);
+        assert_eq!(1, table.common.x_data.len());
+        match table.specific {
+            EntityType::Table(ref table) => {
+                assert_eq!(1, table.cells.len());
+                let cell = &table.cells[0];
+                assert_eq!("cell", cell.text);
+                assert_eq!(None, cell.overrides.fill_color);
+                assert_eq!(None, cell.overrides.text_height);
+                assert_eq!(vec![CodePair::new_i16(177, 0)], cell.__unsupported_pairs);
+                assert_eq!(
+                    vec![
+                        CodePair::new_i16(280, 0),
+                        CodePair::new_i16(63, 5),
+                        CodePair::new_f64(140, 0.25),
+                    ],
+                    table.__unsupported_pairs
+                );
+            }
+            _ => panic!("expected a table"),
+        }
+
+        // the kept values are written back after the cells
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(table);
+        assert_contains(
+            &drawing,
+            [
+                "  1", "cell", "  7", "", "177", "     0", "280", "     0", " 63", "     5", "140",
+                "0.25",
             ]
             .join("\r\n"),
         );
     }
 
+    #[test]
+    fn round_trip_table() {
+        let mut table = Table {
+            row_heights: vec![1.0, 2.0],
+            column_widths: vec![3.0],
+            cells: vec![TableCell::default(), TableCell::default()],
+            ..Default::default()
+        };
+        {
+            let cell = table.cell_mut(1, 0).unwrap();
+            cell.text = "x".repeat(600);
+            cell.overrides.text_height = Some(0.25);
+            cell.overrides.is_left_border_visible = Some(false);
+        }
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(Entity::new(EntityType::Table(table.clone())));
+
+        let drawing = parse_drawing(&to_test_string(&drawing));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Table(ref t) => assert_eq!(table, *t),
+            _ => panic!("expected a table"),
+        }
+    }
+
+    #[test]
+    fn round_trip_table_cell_values() {
+        let value = |data_type, data, format_string: &str, value_string: &str| TableCellValue {
+            flags: 1,
+            data_type,
+            data,
+            unit_type: 0,
+            format_string: String::from(format_string),
+            value_string: String::from(value_string),
+            ..Default::default()
+        };
+        let table = Table {
+            row_heights: vec![1.0, 2.0, 3.0],
+            column_widths: vec![4.0],
+            cells: vec![
+                TableCell {
+                    text: String::from("1.50"),
+                    value: Some(value(
+                        2,
+                        TableCellValueData::Double(1.5),
+                        "%lu2%pr2",
+                        "1.50",
+                    )),
+                    ..Default::default()
+                },
+                TableCell {
+                    text: String::from("42"),
+                    value: Some(value(1, TableCellValueData::Long(42), "", "42")),
+                    ..Default::default()
+                },
+                TableCell {
+                    text: String::from("date"),
+                    value: Some(value(
+                        8,
+                        TableCellValueData::Binary(vec![0x01, 0x02, 0x03]),
+                        "%TH",
+                        "date",
+                    )),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(Entity::new(EntityType::Table(table.clone())));
+
+        let drawing = parse_drawing(&to_test_string(&drawing));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Table(ref t) => assert_eq!(table, *t),
+            _ => panic!("expected a table"),
+        }
+    }
+
     #[test]
     fn read_dimension() {
         let ent = read_entity(
@@ -3200,11 +6616,11 @@ mod tests {
                 common,
                 specific: expected_type,
             });
-            // 3DLINE writes as a LINE
-            let type_string = if type_string == "3DLINE" {
-                "LINE"
-            } else {
-                type_string
+            // 3DLINE writes as a LINE and MLEADER writes as a MULTILEADER
+            let type_string = match type_string {
+                "3DLINE" => "LINE",
+                "MLEADER" => "MULTILEADER",
+                _ => type_string,
             };
             assert_contains(&drawing, vec!["  0", type_string].join("\r\n"));
             if max_version >= AcadVersion::R14 {
@@ -3261,4 +6677,606 @@ mod tests {
         assert_eq!("STANDARD", dim_styles[1].name);
         assert_eq!("style name", dim_styles[2].name);
     }
+
+    #[test]
+    fn mirrored_circle_center_wcs() {
+        let circle = Circle {
+            center: Point::new(1.0, 2.0, 3.0),
+            normal: Vector::new(0.0, 0.0, -1.0),
+            ..Default::default()
+        };
+        assert_eq!(Point::new(-1.0, 2.0, -3.0), circle.center_wcs());
+    }
+
+    #[test]
+    fn arc_end_points_wcs() {
+        let arc = Arc {
+            center: Point::new(1.0, 0.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: 90.0,
+            normal: Vector::new(0.0, 0.0, -1.0),
+            ..Default::default()
+        };
+        assert!(arc
+            .start_point_wcs()
+            .is_close_to(&Point::new(-2.0, 0.0, 0.0), 1e-12));
+        assert!(arc
+            .end_point_wcs()
+            .is_close_to(&Point::new(-1.0, 1.0, 0.0), 1e-12));
+    }
+
+    #[test]
+    fn lw_polyline_vertices_wcs() {
+        let poly = LwPolyline {
+            vertices: vec![
+                LwPolylineVertex {
+                    x: 1.0,
+                    y: 2.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 3.0,
+                    y: 4.0,
+                    ..Default::default()
+                },
+            ],
+            extrusion_direction: Vector::x_axis(),
+            ..Default::default()
+        };
+
+        // OCS X is world Y and OCS Y is world Z; the elevation is along world X
+        assert_eq!(
+            vec![Point::new(5.0, 1.0, 2.0), Point::new(5.0, 3.0, 4.0)],
+            poly.vertices_wcs(5.0)
+        );
+    }
+
+    fn quarter_circle_spline() -> Spline {
+        Spline {
+            degree_of_curve: 2,
+            knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            control_points: vec![
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            weights: vec![1.0, 0.5f64.sqrt(), 1.0],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn read_spline_weights() {
+        let ent = read_entity(
+            "SPLINE",
+            vec![
+                "71", "1", "72", "4", "40", "0.0", "40", "0.0", "40", "1.0", "40", "1.0", "73",
+                "2", "10", "1.0", "20", "2.0", "30", "0.0", "10", "3.0", "20", "4.0", "30", "0.0",
+                "41", "0.5", "41", "2.0",
+            ]
+            .join("\r\n"),
+        );
+        match ent.specific {
+            EntityType::Spline(ref spline) => {
+                assert_eq!(vec![0.5, 2.0], spline.weights);
+                // the weights pull the midpoint towards the heavier end
+                let p = spline.evaluate(0.5).unwrap();
+                assert!(p.is_close_to(&Point::new(2.6, 3.6, 0.0), 1e-12));
+            }
+            _ => panic!("expected a SPLINE"),
+        }
+    }
+
+    #[test]
+    fn evaluate_rational_spline() {
+        let spline = quarter_circle_spline();
+        assert_eq!(Some((0.0, 1.0)), spline.parameter_range());
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let p = spline.evaluate(t).unwrap();
+            assert!((p.distance_to(&Point::origin()) - 1.0).abs() < 1e-12);
+
+            // the tangent of a circle is perpendicular to its radius
+            let d = spline.derivative(t).unwrap();
+            assert!(d.dot(&p.to_vector()).abs() < 1e-12);
+            assert!(d.length() > 0.0);
+        }
+        assert!(spline
+            .derivative(0.0)
+            .unwrap()
+            .is_parallel_to(&Vector::y_axis(), 1e-12));
+    }
+
+    #[test]
+    fn derivative_matches_finite_difference() {
+        let spline = Spline {
+            degree_of_curve: 3,
+            knot_values: vec![0.0, 0.0, 0.0, 0.0, 0.4, 1.0, 1.0, 1.0, 1.0],
+            control_points: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 2.0, 0.0),
+                Point::new(2.0, -1.0, 1.0),
+                Point::new(3.0, 1.0, 0.0),
+                Point::new(4.0, 0.0, 2.0),
+            ],
+            weights: vec![1.0, 2.0, 0.5, 1.0, 1.5],
+            ..Default::default()
+        };
+        let h = 1e-6;
+        for t in [0.1, 0.4, 0.75] {
+            let expected =
+                (spline.evaluate(t + h).unwrap() - spline.evaluate(t - h).unwrap()) / (2.0 * h);
+            assert!(spline.derivative(t).unwrap().is_close_to(&expected, 1e-5));
+        }
+    }
+
+    #[test]
+    fn spline_length() {
+        let length = quarter_circle_spline().length().unwrap();
+        assert!((std::f64::consts::FRAC_PI_2 - length).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spline_with_invalid_knots_cannot_be_evaluated() {
+        let spline = Spline {
+            degree_of_curve: 3,
+            control_points: vec![Point::origin(), Point::new(1.0, 0.0, 0.0)],
+            ..Default::default()
+        };
+        assert_eq!(None, spline.parameter_range());
+        assert_eq!(None, spline.evaluate(0.0));
+        assert_eq!(None, spline.derivative(0.0));
+        assert_eq!(None, spline.length());
+        assert_eq!(None, spline.closest_point(&Point::origin()));
+    }
+
+    #[test]
+    fn insert_knot_keeps_shape() {
+        let original = quarter_circle_spline();
+        let mut spline = original.clone();
+        assert!(spline.insert_knot(0.3));
+        assert!(spline.insert_knot(0.3));
+        assert!(!spline.insert_knot(0.3)); // multiplicity would exceed the degree
+        assert!(!spline.insert_knot(1.0)); // outside of the parameter range
+        assert_eq!(5, spline.control_points.len());
+        assert_eq!(5, spline.weights.len());
+        assert_eq!(
+            vec![0.0, 0.0, 0.0, 0.3, 0.3, 1.0, 1.0, 1.0],
+            spline.knot_values
+        );
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!(original
+                .evaluate(t)
+                .unwrap()
+                .is_close_to(&spline.evaluate(t).unwrap(), 1e-12));
+        }
+
+        // with the multiplicity equal to the degree the curve passes through a control point
+        assert!(spline.control_points[2].is_close_to(&original.evaluate(0.3).unwrap(), 1e-12));
+    }
+
+    #[test]
+    fn spline_from_fit_points() {
+        let fit_points = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+            Point::new(4.0, 3.0, 1.0),
+            Point::new(6.0, 0.0, 0.0),
+        ];
+        let spline = Spline::from_fit_points(&fit_points, 3, None, None).unwrap();
+        assert_eq!(3, spline.degree_of_curve);
+        assert_eq!(5, spline.control_points.len());
+        assert_eq!(fit_points.to_vec(), spline.fit_points);
+        let closest = fit_points
+            .iter()
+            .map(|p| spline.closest_point(p).unwrap().1.distance_to(p))
+            .fold(0.0, f64::max);
+        assert!(closest < 1e-9);
+    }
+
+    #[test]
+    fn spline_from_fit_points_with_tangents() {
+        let fit_points = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 1.0, 0.0),
+            Point::new(4.0, 0.0, 0.0),
+        ];
+        let start_tangent = Vector::new(0.0, 1.0, 0.0);
+        let end_tangent = Vector::new(1.0, -1.0, 0.0);
+        let spline =
+            Spline::from_fit_points(&fit_points, 3, Some(&start_tangent), Some(&end_tangent))
+                .unwrap();
+        assert_eq!(5, spline.control_points.len());
+        assert_eq!(Point::new(0.0, 1.0, 0.0), spline.start_tangent);
+        let (t0, t1) = spline.parameter_range().unwrap();
+        assert!(spline
+            .evaluate(t0)
+            .unwrap()
+            .is_close_to(&fit_points[0], 1e-12));
+        assert!(spline
+            .evaluate(t1)
+            .unwrap()
+            .is_close_to(&fit_points[2], 1e-12));
+        assert!(spline
+            .closest_point(&fit_points[1])
+            .unwrap()
+            .1
+            .is_close_to(&fit_points[1], 1e-9));
+        let start = spline.derivative(t0).unwrap();
+        assert!(start.is_parallel_to(&start_tangent, 1e-9) && start.y > 0.0);
+        let end = spline.derivative(t1).unwrap();
+        assert!(end.is_parallel_to(&end_tangent, 1e-9) && end.x > 0.0);
+    }
+
+    #[test]
+    fn compute_control_points_from_fit_points() {
+        let mut spline = Spline {
+            degree_of_curve: 3,
+            fit_points: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(2.0, 0.0, 0.0),
+            ],
+            ..Default::default()
+        };
+        assert!(spline.compute_control_points());
+        assert_eq!(2, spline.degree_of_curve); // reduced to fit three points
+        assert!(spline.has_valid_knots());
+
+        let mut degenerate = Spline {
+            fit_points: vec![Point::origin(), Point::origin()],
+            ..Default::default()
+        };
+        assert!(!degenerate.compute_control_points());
+    }
+
+    #[test]
+    fn spline_closest_point() {
+        let spline = quarter_circle_spline();
+        let (t, p) = spline.closest_point(&Point::new(2.0, 2.0, 0.0)).unwrap();
+        let expected = Point::new(0.5f64.sqrt(), 0.5f64.sqrt(), 0.0);
+        assert!(p.is_close_to(&expected, 1e-9));
+        assert!(spline.evaluate(t).unwrap().is_close_to(&p, 1e-12));
+
+        // beyond the end the closest point is the end point
+        let (t, _) = spline.closest_point(&Point::new(-1.0, 2.0, 0.0)).unwrap();
+        assert!((1.0 - t).abs() < 1e-9);
+    }
+
+    fn lw_polyline(vertices: &[(f64, f64, f64)], is_closed: bool) -> LwPolyline {
+        let mut poly = LwPolyline {
+            vertices: vertices
+                .iter()
+                .map(|(x, y, bulge)| LwPolylineVertex {
+                    x: *x,
+                    y: *y,
+                    bulge: *bulge,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        poly.set_is_closed(is_closed);
+        poly
+    }
+
+    #[test]
+    fn lw_polyline_segments() {
+        let poly = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 1.0), (2.0, 2.0, 0.0)], true);
+        let segments = poly.segments().collect::<Vec<_>>();
+        assert_eq!(3, segments.len());
+        assert_eq!(
+            PolylineSegment::Line {
+                start: Point::new(0.0, 0.0, 0.0),
+                end: Point::new(2.0, 0.0, 0.0),
+            },
+            segments[0]
+        );
+        match segments[1] {
+            PolylineSegment::Arc {
+                ref center,
+                radius,
+                included_angle,
+                ..
+            } => {
+                assert!(center.is_close_to(&Point::new(2.0, 1.0, 0.0), 1e-12));
+                assert!((1.0 - radius).abs() < 1e-12);
+                assert!((180.0 - included_angle).abs() < 1e-12);
+            }
+            _ => panic!("expected an arc"),
+        }
+        assert_eq!(&Point::origin(), segments[2].end());
+
+        // open polylines have no closing segment
+        let poly = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 1.0), (2.0, 2.0, 0.0)], false);
+        assert_eq!(2, poly.segments().count());
+    }
+
+    #[test]
+    fn lw_polyline_length_and_area() {
+        let square = lw_polyline(
+            &[
+                (0.0, 0.0, 0.0),
+                (2.0, 0.0, 0.0),
+                (2.0, 2.0, 0.0),
+                (0.0, 2.0, 0.0),
+            ],
+            true,
+        );
+        assert_eq!(8.0, square.length());
+        assert_eq!(4.0, square.area());
+        assert!(!square.is_clockwise());
+
+        // a closed polyline of two half circles
+        let circle = lw_polyline(&[(0.0, 0.0, 1.0), (2.0, 0.0, 1.0)], true);
+        assert!((2.0 * std::f64::consts::PI - circle.length()).abs() < 1e-12);
+        assert!((std::f64::consts::PI - circle.area()).abs() < 1e-12);
+
+        // open polylines are closed with a straight line, ignoring the last bulge
+        let open = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 0.0), (2.0, 2.0, 1.0)], false);
+        assert_eq!(4.0, open.length());
+        assert_eq!(2.0, open.area());
+    }
+
+    #[test]
+    fn lw_polyline_reverse() {
+        let mut poly = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, 1.0), (2.0, 2.0, 0.5)], true);
+        poly.vertices[0].starting_width = 1.0;
+        poly.vertices[0].ending_width = 2.0;
+        let original = poly.clone();
+        let area = poly.area();
+        poly.reverse();
+        assert!((area + poly.area()).abs() < 1e-12);
+        assert!(poly.is_clockwise());
+        assert_eq!(
+            vec![(2.0, 2.0, -1.0), (2.0, 0.0, 0.0), (0.0, 0.0, -0.5)],
+            poly.vertices
+                .iter()
+                .map(|v| (v.x, v.y, v.bulge))
+                .collect::<Vec<_>>()
+        );
+
+        // the first segment is now the last one, reversed
+        assert_eq!(2.0, poly.vertices[1].starting_width);
+        assert_eq!(1.0, poly.vertices[1].ending_width);
+
+        poly.reverse();
+        assert_eq!(original, poly);
+    }
+
+    #[test]
+    fn lw_polyline_point_at_distance() {
+        let poly = lw_polyline(&[(0.0, 0.0, 0.0), (2.0, 0.0, -1.0), (4.0, 0.0, 0.0)], false);
+        assert_eq!(Some(Point::new(1.0, 0.0, 0.0)), poly.point_at_distance(1.0));
+        // halfway along the clockwise half circle above the chord
+        assert!(poly
+            .point_at_distance(2.0 + std::f64::consts::FRAC_PI_2)
+            .unwrap()
+            .is_close_to(&Point::new(3.0, 1.0, 0.0), 1e-12));
+        assert!(poly
+            .point_at_distance(poly.length())
+            .unwrap()
+            .is_close_to(&Point::new(4.0, 0.0, 0.0), 1e-12));
+        assert_eq!(None, poly.point_at_distance(-1.0));
+        assert_eq!(None, poly.point_at_distance(10.0));
+    }
+
+    #[test]
+    fn lw_polyline_contains_point() {
+        // a square with a bulge outwards on the right and inwards on the top
+        let poly = lw_polyline(
+            &[
+                (0.0, 0.0, 0.0),
+                (2.0, 0.0, 1.0),
+                (2.0, 2.0, -0.5),
+                (0.0, 2.0, 0.0),
+            ],
+            true,
+        );
+        assert!(poly.contains_point(&Point::new(1.0, 1.0, 0.0)));
+        assert!(poly.contains_point(&Point::new(2.5, 1.0, 0.0)));
+        assert!(!poly.contains_point(&Point::new(3.5, 1.0, 0.0)));
+        assert!(!poly.contains_point(&Point::new(1.0, 1.9, 0.0)));
+        assert!(poly.contains_point(&Point::new(0.1, 1.6, 0.0)));
+        assert!(!poly.contains_point(&Point::new(-1.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn lw_polyline_round_trips_through_polyline() {
+        let mut lw = lw_polyline(&[(1.0, 2.0, 0.5), (3.0, 4.0, 0.0), (5.0, 1.0, -0.25)], true);
+        lw.set_is_pline_gen(true);
+        lw.constant_width = 0.5;
+        lw.thickness = 2.0;
+        lw.extrusion_direction = Vector::new(0.0, 1.0, 0.0);
+        lw.vertices[1].id = 7;
+        lw.vertices[1].starting_width = 1.0;
+        lw.vertices[1].ending_width = 1.5;
+
+        let poly = lw.to_polyline(3.0);
+        assert!(poly.get_is_closed());
+        assert!(poly.get_is_line_type_pattern_generated_continuously());
+        assert_eq!(3.0, poly.location.z);
+        assert_eq!(3, poly.vertices().count());
+        assert_eq!(lw.area(), poly.area());
+        assert_eq!(lw.length(), poly.length());
+        assert_eq!(Some(lw), poly.to_lw_polyline());
+    }
+
+    #[test]
+    fn polyline_converted_from_lw_polyline_gets_vertex_handles() {
+        let mut drawing = Drawing::new();
+        let lw = lw_polyline(&[(0.0, 0.0, 0.0), (1.0, 0.0, 0.0)], false);
+        let added = drawing.add_entity(Entity::new(EntityType::Polyline(lw.to_polyline(0.0))));
+        match added.specific {
+            EntityType::Polyline(ref poly) => {
+                assert!(poly
+                    .__vertices_and_handles
+                    .iter()
+                    .all(|(_, h)| !h.is_empty() && *h != crate::drawing::AUTO_REPLACE_HANDLE));
+            }
+            _ => panic!("expected a POLYLINE"),
+        }
+    }
+
+    #[test]
+    fn polyline_without_lw_polyline_equivalent() {
+        let mut poly = Polyline::default();
+        poly.set_is_3d_polyline(true);
+        assert_eq!(None, poly.to_lw_polyline());
+
+        let poly = Polyline {
+            default_starting_width: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(None, poly.to_lw_polyline());
+    }
+
+    #[test]
+    fn set_true_color_sets_closest_index_color() {
+        let mut common = EntityCommon::default();
+        common.set_true_color(Some(TrueColor::from_rgb(250, 0, 0)));
+        assert_eq!(Some(0x00FA_0000), common.color_24_bit);
+        assert_eq!(Color::from_index(1), common.color);
+        assert_eq!(
+            Some(TrueColor::from_rgb(250, 0, 0)),
+            common.get_true_color()
+        );
+
+        common.set_true_color(None);
+        assert_eq!(None, common.get_true_color());
+    }
+
+    #[test]
+    fn round_trip_black_true_color() {
+        let mut common = EntityCommon::default();
+        common.set_true_color(Some(TrueColor::from_rgb(0, 0, 0)));
+        assert_eq!(Some(TrueColor::from_rgb(0, 0, 0)), common.get_true_color());
+
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(Entity {
+            common,
+            specific: EntityType::Line(Default::default()),
+        });
+        let contents = to_test_string(&drawing);
+        assert!(contents.contains("\r\n420\r\n        0\r\n"));
+        let drawing = parse_drawing(&contents);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        assert_eq!(
+            Some(TrueColor::from_rgb(0, 0, 0)),
+            entities[0].common.get_true_color()
+        );
+    }
+
+    #[test]
+    fn read_black_true_color() {
+        let ent = read_entity("LINE", ["420", "0"].join("\r\n"));
+        assert_eq!(
+            Some(TrueColor::from_rgb(0, 0, 0)),
+            ent.common.get_true_color()
+        );
+
+        let ent = read_entity("LINE", ["8", "0"].join("\r\n"));
+        assert_eq!(None, ent.common.get_true_color());
+    }
+
+    #[test]
+    fn write_true_color_without_upper_byte() {
+        let mut ent = Entity::new(EntityType::Line(Default::default()));
+        ent.common.color_24_bit = Some(0xC212_3456_u32 as i32);
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(ent);
+        assert_contains(&drawing, ["420", "  1193046"].join("\r\n"));
+    }
+
+    #[test]
+    fn resolve_entity_rgb() {
+        let mut drawing = Drawing::new();
+        drawing.add_layer(tables::Layer {
+            name: String::from("blue-layer"),
+            color: Color::from_index(5),
+            ..Default::default()
+        });
+        let common = |color: Color, layer: &str| EntityCommon {
+            color,
+            layer: String::from(layer),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            (255, 0, 0),
+            common(Color::from_index(1), "0").resolve_rgb(&drawing, None)
+        );
+        assert_eq!(
+            (0, 0, 255),
+            common(Color::by_layer(), "BLUE-LAYER").resolve_rgb(&drawing, None)
+        );
+
+        // the true color wins over the index color
+        let mut true_color = common(Color::from_index(1), "0");
+        true_color.color_24_bit = Some(0x0012_3456);
+        assert_eq!((0x12, 0x34, 0x56), true_color.resolve_rgb(&drawing, None));
+
+        // BYBLOCK and the layer `0` come from the block reference
+        let insert = common(Color::from_index(3), "blue-layer");
+        assert_eq!(
+            (0, 255, 0),
+            common(Color::by_block(), "0").resolve_rgb(&drawing, Some(&insert))
+        );
+        assert_eq!(
+            (0, 0, 255),
+            common(Color::by_layer(), "0").resolve_rgb(&drawing, Some(&insert))
+        );
+        assert_eq!(
+            (255, 255, 255),
+            common(Color::by_block(), "0").resolve_rgb(&drawing, None)
+        );
+    }
+
+    #[test]
+    fn transparency_accessors() {
+        let mut common = EntityCommon::default();
+        assert!(common.get_transparency().is_by_layer());
+        common.set_transparency(Transparency::from_alpha(64));
+        assert_eq!(0x0200_0040, common.transparency);
+        assert_eq!(Some(64), common.get_transparency().alpha());
+    }
+
+    #[test]
+    fn m_text_plain_text_lines() {
+        let m_text = MText {
+            text: String::from("{\\fArial|b1;bold}\\Pa\\~b \\S1^2; \\\\ \\Lu\\l\\Pend"),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["bold", "a b 1/2 \\ u", "end"],
+            m_text.plain_text_lines()
+        );
+    }
+
+    #[test]
+    fn m_text_to_text_lines() {
+        let m_text = MText {
+            insertion_point: Point::new(1.0, 2.0, 0.0),
+            initial_text_height: 3.0,
+            attachment_point: AttachmentPoint::BottomLeft,
+            text: String::from("one\\Ptwo"),
+            ..Default::default()
+        };
+        let lines = m_text.to_text_lines();
+        assert_eq!(2, lines.len());
+        assert_eq!("one", lines[0].value);
+        assert_eq!(Point::new(1.0, 7.0, 0.0), lines[0].second_alignment_point);
+        assert_eq!("two", lines[1].value);
+        assert_eq!(Point::new(1.0, 2.0, 0.0), lines[1].second_alignment_point);
+        assert_eq!(
+            VerticalTextJustification::Bottom,
+            lines[1].vertical_text_justification
+        );
+    }
 }
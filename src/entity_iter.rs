@@ -3,28 +3,50 @@ use std::io::Read;
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::drawing::AUTO_REPLACE_HANDLE;
 use crate::entities::*;
-use crate::DxfResult;
-
-use itertools::{put_back, PutBack};
+use crate::{DxfError, DxfResult};
 
 pub(crate) struct EntityIter<'a, T: 'a + Read> {
     pub iter: &'a mut CodePairPutBack<T>,
+    /// The error that stopped the iteration, if any.
+    pub error: Option<DxfError>,
+}
+
+impl<'a, I: 'a + Read> EntityIter<'a, I> {
+    pub(crate) fn new(iter: &'a mut CodePairPutBack<I>) -> Self {
+        EntityIter { iter, error: None }
+    }
 }
 
 impl<'a, I: 'a + Read> Iterator for EntityIter<'a, I> {
     type Item = Entity;
 
     fn next(&mut self) -> Option<Entity> {
-        match Entity::read(self.iter) {
-            Ok(Some(e)) => Some(e),
-            Ok(None) | Err(_) => None,
+        loop {
+            match Entity::read(self.iter) {
+                Ok(Some(e)) => return Some(e),
+                Ok(None) => return None,
+                Err(e) => {
+                    // in recovery mode the broken entity is dropped and reading resumes at the next one
+                    if let Err(e) = self.iter.recover(e) {
+                        self.error = Some(e);
+                        return None;
+                    }
+                    if !self.iter.skip_until(|p| p.code == 0) {
+                        return None;
+                    }
+                }
+            }
         }
     }
 }
 
 impl<'a, I: 'a + Read> EntityIter<'a, I> {
     pub(crate) fn read_entities_into_vec(&mut self, entities: &mut Vec<Entity>) -> DxfResult<()> {
-        collect_entities(self, entities)
+        collect_entities(self, entities)?;
+        match self.error.take() {
+            Some(e) if self.iter.is_strict() => Err(e),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -32,140 +54,158 @@ pub(crate) fn collect_entities<I>(iter: &mut I, entities: &mut Vec<Entity>) -> D
 where
     I: Iterator<Item = Entity>,
 {
-    fn swallow_seqend<I>(iter: &mut PutBack<I>) -> DxfResult<()>
-    where
-        I: Iterator<Item = Entity>,
-    {
-        match iter.next() {
+    entities.extend(CollapsedEntityIter::new(iter));
+    Ok(())
+}
+
+/// Gathers the trailing `ATTRIB`, `VERTEX`, and `SEQEND` entities into the `INSERT`, `POLYLINE`,
+/// `ATTRIB`, or `ATTDEF` that owns them.
+pub(crate) struct CollapsedEntityIter<I: Iterator<Item = Entity>> {
+    pub iter: I,
+    put_back: Option<Entity>,
+}
+
+impl<I: Iterator<Item = Entity>> CollapsedEntityIter<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        CollapsedEntityIter {
+            iter,
+            put_back: None,
+        }
+    }
+    fn next_raw(&mut self) -> Option<Entity> {
+        match self.put_back.take() {
+            Some(ent) => Some(ent),
+            None => self.iter.next(),
+        }
+    }
+    fn put_back(&mut self, ent: Entity) {
+        self.put_back = Some(ent);
+    }
+    fn swallow_seqend(&mut self) {
+        match self.next_raw() {
             Some(Entity {
                 specific: EntityType::Seqend(_),
                 ..
             }) => (),
-            Some(ent) => iter.put_back(ent),
+            Some(ent) => self.put_back(ent),
             None => (),
         }
-
-        Ok(())
     }
-
-    fn get_mtext<I>(iter: &mut PutBack<I>) -> DxfResult<Option<MText>>
-    where
-        I: Iterator<Item = Entity>,
-    {
-        let m_text = match iter.next() {
+    fn get_mtext(&mut self) -> Option<MText> {
+        match self.next_raw() {
             Some(Entity {
                 specific: EntityType::MText(m),
                 ..
             }) => Some(m),
             Some(ent) => {
-                iter.put_back(ent);
+                self.put_back(ent);
                 None
             }
             None => None,
-        };
-
-        Ok(m_text)
+        }
     }
+}
+
+impl<I: Iterator<Item = Entity>> Iterator for CollapsedEntityIter<I> {
+    type Item = Entity;
 
-    let mut iter = put_back(iter);
-    loop {
-        match iter.next() {
+    fn next(&mut self) -> Option<Entity> {
+        match self.next_raw() {
             Some(Entity {
-                ref common,
-                specific: EntityType::Attribute(ref att),
+                common,
+                specific: EntityType::Attribute(mut att),
             }) => {
-                let mut att = att.clone(); // 27 fields
-                match get_mtext(&mut iter) {
-                    Ok(Some(m_text)) => att.m_text = m_text,
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+                if let Some(m_text) = self.get_mtext() {
+                    att.m_text = m_text;
                 }
 
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
+                Some(Entity {
+                    common,
                     specific: EntityType::Attribute(att),
-                });
+                })
             }
             Some(Entity {
-                ref common,
-                specific: EntityType::AttributeDefinition(ref att),
+                common,
+                specific: EntityType::AttributeDefinition(mut att),
             }) => {
-                let mut att = att.clone(); // 27 fields
-                match get_mtext(&mut iter) {
-                    Ok(Some(m_text)) => att.m_text = m_text,
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+                if let Some(m_text) = self.get_mtext() {
+                    att.m_text = m_text;
                 }
 
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
+                Some(Entity {
+                    common,
                     specific: EntityType::AttributeDefinition(att),
-                });
+                })
             }
             Some(Entity {
-                ref common,
-                specific: EntityType::Insert(ref ins),
+                common,
+                specific: EntityType::Insert(mut ins),
             }) if ins.__has_attributes => {
-                let mut ins = ins.clone(); // 12 fields
                 loop {
-                    match iter.next() {
+                    match self.next_raw() {
                         Some(Entity {
+                            common: child_common,
                             specific: EntityType::Attribute(att),
-                            ..
-                        }) => ins
-                            .__attributes_and_handles
-                            .push((att, AUTO_REPLACE_HANDLE)),
+                        }) => {
+                            let handle = if child_common.handle.is_empty() {
+                                AUTO_REPLACE_HANDLE
+                            } else {
+                                child_common.handle
+                            };
+                            ins.__attributes_and_handles.push((att, handle));
+                        }
                         Some(ent) => {
                             // stop gathering on any non-ATTRIBUTE
-                            iter.put_back(ent);
+                            self.put_back(ent);
                             break;
                         }
                         None => break,
                     }
                 }
 
-                swallow_seqend(&mut iter)?;
+                self.swallow_seqend();
 
                 // and finally keep the INSERT
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
+                Some(Entity {
+                    common,
                     specific: EntityType::Insert(ins),
-                });
+                })
             }
             Some(Entity {
                 common,
-                specific: EntityType::Polyline(poly),
+                specific: EntityType::Polyline(mut poly),
             }) => {
-                let mut poly = poly.clone(); // 13 fields
                 loop {
-                    match iter.next() {
+                    match self.next_raw() {
                         Some(Entity {
+                            common: child_common,
                             specific: EntityType::Vertex(vertex),
-                            ..
-                        }) => poly
-                            .__vertices_and_handles
-                            .push((vertex, AUTO_REPLACE_HANDLE)),
+                        }) => {
+                            let handle = if child_common.handle.is_empty() {
+                                AUTO_REPLACE_HANDLE
+                            } else {
+                                child_common.handle
+                            };
+                            poly.__vertices_and_handles.push((vertex, handle));
+                        }
                         Some(ent) => {
                             // stop gathering on any non-VERTEX
-                            iter.put_back(ent);
+                            self.put_back(ent);
                             break;
                         }
                         None => break,
                     }
                 }
 
-                swallow_seqend(&mut iter)?;
+                self.swallow_seqend();
 
                 // and finally keep the POLYLINE
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
+                Some(Entity {
+                    common,
                     specific: EntityType::Polyline(poly),
-                });
+                })
             }
-            Some(entity) => entities.push(entity),
-            None => break,
+            entity => entity,
         }
     }
-
-    Ok(())
 }
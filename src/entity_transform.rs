@@ -0,0 +1,862 @@
+use std::f64::consts::PI;
+
+use crate::entities::*;
+use crate::{
+    HatchBoundaryPathData, HatchBoundaryPathEdge, HatchPatternDefinitionLine, Point,
+    TransformationMatrix, Vector,
+};
+
+/// A transformation of the plane of an object coordinate system (OCS).  Points are mapped from
+/// the original OCS to the OCS of the transformed plane.
+pub(crate) struct PlanarTransform {
+    to_wcs: TransformationMatrix,
+    to_ocs: TransformationMatrix,
+    /// The extrusion direction of the transformed plane.  The plane keeps its orientation, so
+    /// counter-clockwise angles stay counter-clockwise, even when mirrored.
+    pub normal: Vector,
+    /// The image of the original OCS X axis in the new OCS.
+    x_axis: Vector,
+    /// The image of the original OCS Y axis in the new OCS.
+    y_axis: Vector,
+    /// The factor applied to distances along the original normal, e.g., thickness.
+    pub thickness_scale: f64,
+}
+
+// internal visibility only
+impl PlanarTransform {
+    pub(crate) fn new(matrix: &TransformationMatrix, normal: &Vector) -> Self {
+        let to_wcs = *matrix * TransformationMatrix::from_ocs_normal(normal);
+        let x_axis = to_wcs.transform_vector(&Vector::x_axis());
+        let y_axis = to_wcs.transform_vector(&Vector::y_axis());
+        let extrusion = to_wcs.transform_vector(&Vector::z_axis());
+        let mut new_normal = x_axis.cross(&y_axis).normalize();
+        if new_normal.is_zero(1.0e-12) {
+            // the plane collapsed; keep the extrusion direction
+            new_normal = extrusion.normalize();
+        }
+
+        // the OCS matrix is orthonormal, so its transpose converts back
+        let to_ocs = TransformationMatrix::from_ocs_normal(&new_normal).transpose();
+        PlanarTransform {
+            to_wcs,
+            to_ocs,
+            thickness_scale: extrusion.dot(&new_normal),
+            x_axis: to_ocs.transform_vector(&x_axis),
+            y_axis: to_ocs.transform_vector(&y_axis),
+            normal: new_normal,
+        }
+    }
+    /// Maps a point in the original OCS to the new OCS.
+    pub(crate) fn point(&self, p: &Point) -> Point {
+        self.to_ocs.transform_point(&self.to_wcs.transform_point(p))
+    }
+    /// Maps a vector in the original OCS to the new OCS.
+    pub(crate) fn vector(&self, v: &Vector) -> Vector {
+        &self.x_axis * v.x + &self.y_axis * v.y
+    }
+    /// Maps an angle in radians in the original OCS to the new OCS.
+    pub(crate) fn angle(&self, angle: f64) -> f64 {
+        let (sin, cos) = angle.sin_cos();
+        let direction = self.vector(&Vector::new(cos, sin, 0.0));
+        direction.y.atan2(direction.x)
+    }
+    /// The factor applied to lengths along the specified angle in radians.
+    pub(crate) fn scale_along(&self, angle: f64) -> f64 {
+        let (sin, cos) = angle.sin_cos();
+        self.vector(&Vector::new(cos, sin, 0.0)).length()
+    }
+    /// The factor applied to areas.
+    pub(crate) fn area_scale(&self) -> f64 {
+        (self.x_axis.x * self.y_axis.y - self.x_axis.y * self.y_axis.x).abs()
+    }
+    /// The average factor applied to lengths; exact for conformal transforms.
+    pub(crate) fn scale(&self) -> f64 {
+        self.area_scale().sqrt()
+    }
+    /// Returns `true` if the transform preserves circles, i.e., it scales uniformly.
+    pub(crate) fn is_conformal(&self) -> bool {
+        let (a, b) = (self.x_axis.length(), self.y_axis.length());
+        (a - b).abs() <= 1.0e-9 * a.max(b) && self.x_axis.dot(&self.y_axis).abs() <= 1.0e-9 * a * b
+    }
+    /// Returns the WCS conjugate semi-diameters of a circle of the specified radius.
+    fn circle_axes(&self, radius: f64) -> (Vector, Vector) {
+        (
+            self.to_wcs.transform_vector(&(Vector::x_axis() * radius)),
+            self.to_wcs.transform_vector(&(Vector::y_axis() * radius)),
+        )
+    }
+    /// Transforms text-like values in place.  The height is scaled
+    /// perpendicular to the new baseline and the width factor makes up the difference.
+    fn text(
+        &self,
+        rotation: &mut f64,
+        height: &mut f64,
+        relative_x_scale_factor: &mut f64,
+        thickness: &mut f64,
+    ) {
+        let angle = rotation.to_radians();
+        let baseline_scale = self.scale_along(angle);
+        let height_scale = if baseline_scale == 0.0 {
+            0.0
+        } else {
+            self.area_scale() / baseline_scale
+        };
+        *rotation = self.angle(angle).to_degrees();
+        *height *= height_scale;
+        if height_scale != 0.0 {
+            *relative_x_scale_factor *= baseline_scale / height_scale;
+        }
+        *thickness *= self.thickness_scale;
+    }
+}
+
+impl Entity {
+    /// Applies the transformation to the entity's geometry.
+    ///
+    /// Planar entities keep their orientation, so a mirroring transformation flips the extrusion
+    /// direction instead of reversing arcs.  A `Circle` or `Arc` that is scaled non-uniformly
+    /// becomes an `Ellipse`.  Bulges, text, inserts, and hatches can only be represented exactly
+    /// under uniform scaling; otherwise their scale is approximated.  Entities without
+    /// interpretable geometry, e.g., `Region`, `Solid3D`, `MLeader`, `Table`, and unknown
+    /// entities, are left unchanged.
+    pub fn transform(&mut self, matrix: &TransformationMatrix) {
+        let replacement = match self.specific {
+            EntityType::Arc(ref mut arc) => {
+                let planar = PlanarTransform::new(matrix, &arc.normal);
+                if planar.is_conformal() {
+                    arc.center = planar.point(&arc.center);
+                    arc.radius *= planar.scale();
+                    arc.start_angle = planar.angle(arc.start_angle.to_radians()).to_degrees();
+                    arc.end_angle = planar.angle(arc.end_angle.to_radians()).to_degrees();
+                    arc.thickness *= planar.thickness_scale;
+                    arc.normal = planar.normal;
+                    None
+                } else {
+                    let (u, v) = planar.circle_axes(arc.radius);
+                    let mut end_angle = arc.end_angle;
+                    while end_angle <= arc.start_angle {
+                        end_angle += 360.0;
+                    }
+                    Some(EntityType::Ellipse(Entity::ellipse_from_conjugate_axes(
+                        planar.to_wcs.transform_point(&arc.center),
+                        &u,
+                        &v,
+                        arc.start_angle.to_radians(),
+                        end_angle.to_radians(),
+                    )))
+                }
+            }
+            EntityType::Circle(ref mut circle) => {
+                let planar = PlanarTransform::new(matrix, &circle.normal);
+                if planar.is_conformal() {
+                    circle.center = planar.point(&circle.center);
+                    circle.radius *= planar.scale();
+                    circle.thickness *= planar.thickness_scale;
+                    circle.normal = planar.normal;
+                    None
+                } else {
+                    let (u, v) = planar.circle_axes(circle.radius);
+                    Some(EntityType::Ellipse(Entity::ellipse_from_conjugate_axes(
+                        planar.to_wcs.transform_point(&circle.center),
+                        &u,
+                        &v,
+                        0.0,
+                        2.0 * PI,
+                    )))
+                }
+            }
+            EntityType::Ellipse(ref mut ellipse) => {
+                let minor_axis = ellipse.normal.normalize().cross(&ellipse.major_axis)
+                    * ellipse.minor_axis_ratio;
+                let transformed = Entity::ellipse_from_conjugate_axes(
+                    matrix.transform_point(&ellipse.center),
+                    &matrix.transform_vector(&ellipse.major_axis),
+                    &matrix.transform_vector(&minor_axis),
+                    ellipse.start_parameter,
+                    ellipse.end_parameter,
+                );
+                ellipse.center = transformed.center;
+                ellipse.major_axis = transformed.major_axis;
+                ellipse.normal = transformed.normal;
+                ellipse.minor_axis_ratio = transformed.minor_axis_ratio;
+                ellipse.start_parameter = transformed.start_parameter;
+                ellipse.end_parameter = transformed.end_parameter;
+                None
+            }
+            EntityType::Line(ref mut line) => {
+                line.p1 = matrix.transform_point(&line.p1);
+                line.p2 = matrix.transform_point(&line.p2);
+                Entity::transform_extrusion(
+                    matrix,
+                    &mut line.extrusion_direction,
+                    &mut line.thickness,
+                );
+                None
+            }
+            EntityType::LwPolyline(ref mut poly) => {
+                let planar = PlanarTransform::new(matrix, &poly.extrusion_direction);
+                let elevation = self.common.elevation;
+                self.common.elevation = planar.point(&Point::new(0.0, 0.0, elevation)).z;
+                for v in poly.vertices.iter_mut() {
+                    let p = planar.point(&Point::new(v.x, v.y, elevation));
+                    v.x = p.x;
+                    v.y = p.y;
+                    v.starting_width *= planar.scale();
+                    v.ending_width *= planar.scale();
+                }
+                poly.constant_width *= planar.scale();
+                poly.thickness *= planar.thickness_scale;
+                poly.extrusion_direction = planar.normal;
+                None
+            }
+            EntityType::Polyline(ref mut poly) => {
+                if poly.get_is_3d_polyline()
+                    || poly.get_is_3d_polygon_mesh()
+                    || poly.get_is_polyface_mesh()
+                {
+                    for v in poly.vertices_mut() {
+                        v.location = matrix.transform_point(&v.location);
+                    }
+                } else {
+                    let planar = PlanarTransform::new(matrix, &poly.normal);
+                    let elevation = poly.location.z;
+                    poly.location = planar.point(&Point::new(0.0, 0.0, elevation));
+                    let scale = planar.scale();
+                    for v in poly.vertices_mut() {
+                        let p = planar.point(&Point::new(v.location.x, v.location.y, elevation));
+                        v.location = Point::new(p.x, p.y, v.location.z);
+                        v.starting_width *= scale;
+                        v.ending_width *= scale;
+                    }
+                    poly.default_starting_width *= scale;
+                    poly.default_ending_width *= scale;
+                    poly.thickness *= planar.thickness_scale;
+                    poly.normal = planar.normal;
+                }
+                None
+            }
+            EntityType::Spline(ref mut spline) => {
+                for p in spline.control_points.iter_mut() {
+                    *p = matrix.transform_point(p);
+                }
+                for p in spline.fit_points.iter_mut() {
+                    *p = matrix.transform_point(p);
+                }
+                spline.start_tangent =
+                    Entity::transform_direction_point(matrix, &spline.start_tangent);
+                spline.end_tangent = Entity::transform_direction_point(matrix, &spline.end_tangent);
+                spline.normal = matrix.transform_vector(&spline.normal).normalize();
+                None
+            }
+            EntityType::Helix(ref mut helix) => {
+                let axis = matrix.transform_vector(&helix.axis_vector);
+                helix.axis_base_point = matrix.transform_point(&helix.axis_base_point);
+                helix.start_point = matrix.transform_point(&helix.start_point);
+                let axis_length = helix.axis_vector.length();
+                if axis_length != 0.0 {
+                    helix.turn_height *= axis.length() / axis_length;
+                }
+                let offset = &helix.start_point - &helix.axis_base_point;
+                let unit_axis = axis.normalize();
+                helix.radius = (&offset - &(&unit_axis * offset.dot(&unit_axis))).length();
+                helix.axis_vector = axis;
+                if matrix.determinant() < 0.0 {
+                    helix.is_right_handed = !helix.is_right_handed;
+                }
+                None
+            }
+            EntityType::Insert(ref mut insert) => {
+                Entity::transform_insert(matrix, insert);
+                None
+            }
+            EntityType::Text(ref mut text) => {
+                let planar = PlanarTransform::new(matrix, &text.normal);
+                text.location = planar.point(&text.location);
+                text.second_alignment_point = planar.point(&text.second_alignment_point);
+                planar.text(
+                    &mut text.rotation,
+                    &mut text.text_height,
+                    &mut text.relative_x_scale_factor,
+                    &mut text.thickness,
+                );
+                text.normal = planar.normal;
+                None
+            }
+            EntityType::Attribute(ref mut att) => {
+                Entity::transform_attribute(matrix, att);
+                None
+            }
+            EntityType::AttributeDefinition(ref mut att) => {
+                let planar = PlanarTransform::new(matrix, &att.normal);
+                att.location = planar.point(&att.location);
+                att.second_alignment_point = planar.point(&att.second_alignment_point);
+                planar.text(
+                    &mut att.rotation,
+                    &mut att.text_height,
+                    &mut att.relative_x_scale_factor,
+                    &mut att.thickness,
+                );
+                att.normal = planar.normal;
+                None
+            }
+            EntityType::MText(ref mut mtext) => {
+                Entity::transform_mtext(matrix, mtext);
+                None
+            }
+            EntityType::Shape(ref mut shape) => {
+                let planar = PlanarTransform::new(matrix, &shape.extrusion_direction);
+                shape.location = planar.point(&shape.location);
+                planar.text(
+                    &mut shape.rotation_angle,
+                    &mut shape.size,
+                    &mut shape.relative_x_scale_factor,
+                    &mut shape.thickness,
+                );
+                shape.extrusion_direction = planar.normal;
+                None
+            }
+            EntityType::Solid(ref mut solid) => {
+                let planar = PlanarTransform::new(matrix, &solid.extrusion_direction);
+                solid.first_corner = planar.point(&solid.first_corner);
+                solid.second_corner = planar.point(&solid.second_corner);
+                solid.third_corner = planar.point(&solid.third_corner);
+                solid.fourth_corner = planar.point(&solid.fourth_corner);
+                solid.thickness *= planar.thickness_scale;
+                solid.extrusion_direction = planar.normal;
+                None
+            }
+            EntityType::Trace(ref mut trace) => {
+                let planar = PlanarTransform::new(matrix, &trace.extrusion_direction);
+                trace.first_corner = planar.point(&trace.first_corner);
+                trace.second_corner = planar.point(&trace.second_corner);
+                trace.third_corner = planar.point(&trace.third_corner);
+                trace.fourth_corner = planar.point(&trace.fourth_corner);
+                trace.thickness *= planar.thickness_scale;
+                trace.extrusion_direction = planar.normal;
+                None
+            }
+            EntityType::Hatch(ref mut hatch) => {
+                Entity::transform_hatch(matrix, hatch);
+                None
+            }
+            EntityType::Face3D(ref mut face) => {
+                face.first_corner = matrix.transform_point(&face.first_corner);
+                face.second_corner = matrix.transform_point(&face.second_corner);
+                face.third_corner = matrix.transform_point(&face.third_corner);
+                face.fourth_corner = matrix.transform_point(&face.fourth_corner);
+                None
+            }
+            EntityType::ModelPoint(ref mut point) => {
+                point.location = matrix.transform_point(&point.location);
+                Entity::transform_extrusion(
+                    matrix,
+                    &mut point.extrusion_direction,
+                    &mut point.thickness,
+                );
+                None
+            }
+            EntityType::Vertex(ref mut vertex) => {
+                vertex.location = matrix.transform_point(&vertex.location);
+                None
+            }
+            EntityType::Ray(ref mut ray) => {
+                ray.start_point = matrix.transform_point(&ray.start_point);
+                ray.unit_direction_vector = matrix
+                    .transform_vector(&ray.unit_direction_vector)
+                    .normalize();
+                None
+            }
+            EntityType::XLine(ref mut xline) => {
+                xline.first_point = matrix.transform_point(&xline.first_point);
+                xline.unit_direction_vector = matrix
+                    .transform_vector(&xline.unit_direction_vector)
+                    .normalize();
+                None
+            }
+            EntityType::Leader(ref mut leader) => {
+                for p in leader.vertices.iter_mut() {
+                    *p = matrix.transform_point(p);
+                }
+                leader.normal = matrix.transform_vector(&leader.normal).normalize();
+                leader.right = matrix.transform_vector(&leader.right).normalize();
+                leader.block_offset = matrix.transform_vector(&leader.block_offset);
+                leader.annotation_offset = matrix.transform_vector(&leader.annotation_offset);
+                None
+            }
+            EntityType::MLine(ref mut mline) => {
+                mline.start_point = matrix.transform_point(&mline.start_point);
+                for p in mline.vertices.iter_mut() {
+                    *p = matrix.transform_point(p);
+                }
+                for v in mline.segment_directions.iter_mut() {
+                    *v = matrix.transform_vector(v).normalize();
+                }
+                for v in mline.miter_directions.iter_mut() {
+                    *v = matrix.transform_vector(v).normalize();
+                }
+                mline.normal = matrix.transform_vector(&mline.normal).normalize();
+                None
+            }
+            EntityType::Mesh(ref mut mesh) => {
+                for p in mesh.vertices.iter_mut() {
+                    *p = matrix.transform_point(p);
+                }
+                None
+            }
+            EntityType::Image(ref mut image) => {
+                image.location = matrix.transform_point(&image.location);
+                image.u_vector = matrix.transform_vector(&image.u_vector);
+                image.v_vector = matrix.transform_vector(&image.v_vector);
+                None
+            }
+            EntityType::Wipeout(ref mut wipeout) => {
+                wipeout.location = matrix.transform_point(&wipeout.location);
+                wipeout.u_vector = matrix.transform_vector(&wipeout.u_vector);
+                wipeout.v_vector = matrix.transform_vector(&wipeout.v_vector);
+                None
+            }
+            EntityType::Tolerance(ref mut tolerance) => {
+                tolerance.insertion_point = matrix.transform_point(&tolerance.insertion_point);
+                tolerance.direction_vector = matrix.transform_vector(&tolerance.direction_vector);
+                tolerance.extrusion_direction = matrix
+                    .transform_vector(&tolerance.extrusion_direction)
+                    .normalize();
+                None
+            }
+            EntityType::RotatedDimension(ref mut dim) => {
+                Entity::transform_dimension_base(matrix, &mut dim.dimension_base);
+                dim.insertion_point = matrix.transform_point(&dim.insertion_point);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                None
+            }
+            EntityType::RadialDimension(ref mut dim) => {
+                Entity::transform_dimension_base(matrix, &mut dim.dimension_base);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                None
+            }
+            EntityType::DiameterDimension(ref mut dim) => {
+                Entity::transform_dimension_base(matrix, &mut dim.dimension_base);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                None
+            }
+            EntityType::AngularThreePointDimension(ref mut dim) => {
+                Entity::transform_dimension_base(matrix, &mut dim.dimension_base);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                dim.definition_point_4 = matrix.transform_point(&dim.definition_point_4);
+                dim.definition_point_5 = matrix.transform_point(&dim.definition_point_5);
+                None
+            }
+            EntityType::OrdinateDimension(ref mut dim) => {
+                Entity::transform_dimension_base(matrix, &mut dim.dimension_base);
+                dim.definition_point_2 = matrix.transform_point(&dim.definition_point_2);
+                dim.definition_point_3 = matrix.transform_point(&dim.definition_point_3);
+                None
+            }
+            _ => None,
+        };
+        if let Some(specific) = replacement {
+            self.specific = specific;
+        }
+    }
+}
+
+// private implementation
+impl Entity {
+    /// Creates the ellipse `center + u·cos(t) + v·sin(t)` for `t` in `start..end` radians, where
+    /// `u` and `v` are conjugate semi-diameters.
+    fn ellipse_from_conjugate_axes(
+        center: Point,
+        u: &Vector,
+        v: &Vector,
+        start: f64,
+        end: f64,
+    ) -> Ellipse {
+        // rotate the parameter so the semi-diameters become the principal axes
+        let mut shift = 0.5 * (2.0 * u.dot(v)).atan2(u.dot(u) - v.dot(v));
+        let (sin, cos) = shift.sin_cos();
+        let mut major_axis = u * cos + v * sin;
+        let mut minor_axis = v * cos - u * sin;
+        if minor_axis.length() > major_axis.length() {
+            let previous_major_axis = major_axis;
+            major_axis = minor_axis;
+            minor_axis = -previous_major_axis;
+            shift += PI / 2.0;
+        }
+
+        let (start_parameter, end_parameter) = if end - start >= 2.0 * PI - 1.0e-12 {
+            (0.0, 2.0 * PI)
+        } else {
+            let start_parameter = (start - shift).rem_euclid(2.0 * PI);
+            (start_parameter, start_parameter + (end - start))
+        };
+        let major_length = major_axis.length();
+        Ellipse {
+            center,
+            normal: major_axis.cross(&minor_axis).normalize(),
+            minor_axis_ratio: if major_length == 0.0 {
+                1.0
+            } else {
+                minor_axis.length() / major_length
+            },
+            major_axis,
+            start_parameter,
+            end_parameter,
+        }
+    }
+    fn transform_extrusion(
+        matrix: &TransformationMatrix,
+        extrusion_direction: &mut Vector,
+        thickness: &mut f64,
+    ) {
+        let extrusion = matrix.transform_vector(&extrusion_direction.normalize());
+        *thickness *= extrusion.length();
+        *extrusion_direction = extrusion.normalize();
+    }
+    /// Transforms a direction that is stored as a `Point`; an unset (zero) direction stays unset.
+    fn transform_direction_point(matrix: &TransformationMatrix, direction: &Point) -> Point {
+        let direction = matrix.transform_vector(&direction.to_vector());
+        Point::from(direction)
+    }
+    fn transform_insert(matrix: &TransformationMatrix, insert: &mut Insert) {
+        let planar = PlanarTransform::new(matrix, &insert.extrusion_direction);
+        let angle = insert.rotation.to_radians();
+        let x_scale = planar.scale_along(angle);
+        let y_scale = planar.scale_along(angle + PI / 2.0);
+        insert.location = planar.point(&insert.location);
+        insert.rotation = planar.angle(angle).to_degrees();
+        insert.x_scale_factor *= x_scale;
+        insert.y_scale_factor *= y_scale;
+        insert.z_scale_factor *= planar.thickness_scale;
+        insert.column_spacing *= x_scale;
+        insert.row_spacing *= y_scale;
+        insert.extrusion_direction = planar.normal;
+        for att in insert.attributes_mut() {
+            Entity::transform_attribute(matrix, att);
+        }
+    }
+    fn transform_attribute(matrix: &TransformationMatrix, att: &mut Attribute) {
+        let planar = PlanarTransform::new(matrix, &att.normal);
+        att.location = planar.point(&att.location);
+        att.second_alignment_point = planar.point(&att.second_alignment_point);
+        planar.text(
+            &mut att.rotation,
+            &mut att.text_height,
+            &mut att.relative_x_scale_factor,
+            &mut att.thickness,
+        );
+        att.normal = planar.normal;
+    }
+    fn transform_mtext(matrix: &TransformationMatrix, mtext: &mut MText) {
+        // the rotation angle, in radians, is only meaningful without an explicit direction
+        let ocs = TransformationMatrix::from_ocs_normal(&mtext.extrusion_direction);
+        let x_axis = if mtext
+            .x_axis_direction
+            .is_close_to(&Vector::x_axis(), 1.0e-12)
+            && mtext.rotation_angle != 0.0
+        {
+            let (sin, cos) = mtext.rotation_angle.sin_cos();
+            ocs.transform_vector(&Vector::new(cos, sin, 0.0))
+        } else {
+            mtext.x_axis_direction.normalize()
+        };
+        let y_axis = mtext.extrusion_direction.normalize().cross(&x_axis);
+        let new_x_axis = matrix.transform_vector(&x_axis);
+        let new_y_axis = matrix.transform_vector(&y_axis);
+        let normal = new_x_axis.cross(&new_y_axis);
+        let baseline_scale = new_x_axis.length();
+        let height_scale = if baseline_scale == 0.0 {
+            0.0
+        } else {
+            normal.length() / baseline_scale
+        };
+
+        mtext.insertion_point = matrix.transform_point(&mtext.insertion_point);
+        mtext.initial_text_height *= height_scale;
+        mtext.reference_rectangle_width *= baseline_scale;
+        mtext.horizontal_width *= baseline_scale;
+        mtext.vertical_height *= height_scale;
+        mtext.extrusion_direction = normal.normalize();
+        mtext.x_axis_direction = new_x_axis.normalize();
+        let ocs_x_axis = TransformationMatrix::from_ocs_normal(&mtext.extrusion_direction)
+            .transpose()
+            .transform_vector(&mtext.x_axis_direction);
+        mtext.rotation_angle = ocs_x_axis.y.atan2(ocs_x_axis.x);
+    }
+    fn transform_hatch(matrix: &TransformationMatrix, hatch: &mut Hatch) {
+        let planar = PlanarTransform::new(matrix, &hatch.extrusion_direction);
+        let scale = planar.scale();
+        let rotation = planar.angle(0.0).to_degrees();
+        let transform_angle = |angle: f64, is_counter_clockwise: bool| {
+            // clockwise edges store negated angles
+            if is_counter_clockwise {
+                planar.angle(angle.to_radians()).to_degrees()
+            } else {
+                -planar.angle(-angle.to_radians()).to_degrees()
+            }
+        };
+
+        hatch.elevation_point = planar.point(&hatch.elevation_point);
+        let elevation = hatch.elevation_point.z;
+        let point = |p: &Point| {
+            let p = planar.point(&Point::new(p.x, p.y, elevation));
+            Point::new(p.x, p.y, 0.0)
+        };
+        for path in hatch.boundary_paths.iter_mut() {
+            match path.data {
+                HatchBoundaryPathData::Polyline {
+                    ref mut vertices, ..
+                } => {
+                    for v in vertices.iter_mut() {
+                        let p = point(&Point::new(v.x, v.y, 0.0));
+                        v.x = p.x;
+                        v.y = p.y;
+                    }
+                }
+                HatchBoundaryPathData::Edges(ref mut edges) => {
+                    for edge in edges.iter_mut() {
+                        match edge {
+                            HatchBoundaryPathEdge::Line { start, end } => {
+                                *start = point(start);
+                                *end = point(end);
+                            }
+                            HatchBoundaryPathEdge::CircularArc {
+                                center,
+                                radius,
+                                start_angle,
+                                end_angle,
+                                is_counter_clockwise,
+                            } => {
+                                *center = point(center);
+                                *radius *= scale;
+                                *start_angle = transform_angle(*start_angle, *is_counter_clockwise);
+                                *end_angle = transform_angle(*end_angle, *is_counter_clockwise);
+                            }
+                            HatchBoundaryPathEdge::EllipticArc {
+                                center,
+                                major_axis,
+                                start_angle,
+                                end_angle,
+                                is_counter_clockwise,
+                                ..
+                            } => {
+                                *center = point(center);
+                                *major_axis = planar.vector(major_axis);
+                                *start_angle = transform_angle(*start_angle, *is_counter_clockwise);
+                                *end_angle = transform_angle(*end_angle, *is_counter_clockwise);
+                            }
+                            HatchBoundaryPathEdge::Spline {
+                                control_points,
+                                fit_points,
+                                start_tangent,
+                                end_tangent,
+                                ..
+                            } => {
+                                for p in control_points.iter_mut().chain(fit_points.iter_mut()) {
+                                    *p = point(p);
+                                }
+                                *start_tangent = planar.vector(start_tangent);
+                                *end_tangent = planar.vector(end_tangent);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        for p in hatch.seed_points.iter_mut() {
+            *p = point(p);
+        }
+
+        hatch.pattern_angle += rotation;
+        hatch.pattern_scale *= scale;
+        for line in hatch.pattern_definition_lines.iter_mut() {
+            *line = HatchPatternDefinitionLine {
+                angle: line.angle + rotation,
+                base_point: Point::from(planar.vector(&line.base_point.to_vector())),
+                offset: planar.vector(&line.offset),
+                dash_lengths: line.dash_lengths.iter().map(|d| d * scale).collect(),
+            };
+        }
+        hatch.extrusion_direction = planar.normal;
+    }
+    fn transform_dimension_base(matrix: &TransformationMatrix, dimension: &mut DimensionBase) {
+        // the text location is in the dimension's OCS
+        let planar = PlanarTransform::new(matrix, &dimension.normal);
+        dimension.definition_point_1 = matrix.transform_point(&dimension.definition_point_1);
+        dimension.text_mid_point = planar.point(&dimension.text_mid_point);
+        dimension.normal = planar.normal;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+    use std::f64::consts::PI;
+
+    fn transformed(specific: EntityType, matrix: &TransformationMatrix) -> EntityType {
+        let mut entity = Entity::new(specific);
+        entity.transform(matrix);
+        entity.specific
+    }
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1.0e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn transform_line() {
+        let matrix = TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0));
+        match transformed(
+            EntityType::Line(Line::new(Point::origin(), Point::new(1.0, 0.0, 0.0))),
+            &matrix,
+        ) {
+            EntityType::Line(line) => {
+                assert_eq!(Point::new(1.0, 2.0, 3.0), line.p1);
+                assert_eq!(Point::new(2.0, 2.0, 3.0), line.p2);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn transform_arc_uniformly() {
+        let matrix = TransformationMatrix::rotation(&Vector::z_axis(), PI / 2.0)
+            * TransformationMatrix::scale(2.0, 2.0, 2.0);
+        match transformed(
+            EntityType::Arc(Arc::new(Point::new(1.0, 0.0, 0.0), 1.0, 0.0, 90.0)),
+            &matrix,
+        ) {
+            EntityType::Arc(arc) => {
+                assert!(arc.center.is_close_to(&Point::new(0.0, 2.0, 0.0), 1.0e-12));
+                assert_close(2.0, arc.radius);
+                assert_close(90.0, arc.start_angle);
+                assert_close(180.0, arc.end_angle);
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn transform_mirrored_arc_keeps_end_points() {
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::x_axis());
+        let arc = Arc::new(Point::new(2.0, 0.0, 0.0), 1.0, 0.0, 90.0);
+        let expected_start = matrix.transform_point(&arc.start_point_wcs());
+        let expected_end = matrix.transform_point(&arc.end_point_wcs());
+        match transformed(EntityType::Arc(arc), &matrix) {
+            EntityType::Arc(arc) => {
+                // the normal flips instead of the arc's direction
+                assert_eq!(Vector::new(0.0, 0.0, -1.0), arc.normal);
+                assert!(arc.start_point_wcs().is_close_to(&expected_start, 1.0e-12));
+                assert!(arc.end_point_wcs().is_close_to(&expected_end, 1.0e-12));
+                assert!(arc
+                    .center_wcs()
+                    .is_close_to(&Point::new(-2.0, 0.0, 0.0), 1.0e-12));
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn transform_circle_non_uniformly_becomes_ellipse() {
+        let matrix = TransformationMatrix::scale(2.0, 1.0, 1.0);
+        match transformed(
+            EntityType::Circle(Circle::new(Point::new(1.0, 1.0, 0.0), 1.0)),
+            &matrix,
+        ) {
+            EntityType::Ellipse(ellipse) => {
+                assert!(ellipse
+                    .center
+                    .is_close_to(&Point::new(2.0, 1.0, 0.0), 1.0e-12));
+                assert_close(2.0, ellipse.major_axis.length());
+                assert_close(0.0, ellipse.major_axis.y);
+                assert_close(0.5, ellipse.minor_axis_ratio);
+                assert_close(0.0, ellipse.start_parameter);
+                assert_close(2.0 * PI, ellipse.end_parameter);
+            }
+            _ => panic!("expected an ellipse"),
+        }
+    }
+
+    #[test]
+    fn transform_arc_non_uniformly_keeps_end_points() {
+        let matrix = TransformationMatrix::scale(1.0, 3.0, 1.0);
+        let arc = Arc::new(Point::origin(), 1.0, 30.0, 120.0);
+        let expected_start = matrix.transform_point(&arc.start_point_wcs());
+        let expected_end = matrix.transform_point(&arc.end_point_wcs());
+        match transformed(EntityType::Arc(arc), &matrix) {
+            EntityType::Ellipse(ellipse) => {
+                let point_at = |t: f64| {
+                    let minor_axis =
+                        ellipse.normal.cross(&ellipse.major_axis) * ellipse.minor_axis_ratio;
+                    &ellipse.center + &(&ellipse.major_axis * t.cos()) + &minor_axis * t.sin()
+                };
+                assert!(point_at(ellipse.start_parameter).is_close_to(&expected_start, 1.0e-9));
+                assert!(point_at(ellipse.end_parameter).is_close_to(&expected_end, 1.0e-9));
+            }
+            _ => panic!("expected an ellipse"),
+        }
+    }
+
+    #[test]
+    fn transform_text() {
+        let matrix = TransformationMatrix::rotation(&Vector::z_axis(), PI / 2.0)
+            * TransformationMatrix::scale(3.0, 3.0, 3.0);
+        let text = Text {
+            location: Point::new(1.0, 0.0, 0.0),
+            text_height: 2.0,
+            ..Default::default()
+        };
+        match transformed(EntityType::Text(text), &matrix) {
+            EntityType::Text(text) => {
+                assert!(text
+                    .location
+                    .is_close_to(&Point::new(0.0, 3.0, 0.0), 1.0e-12));
+                assert_close(6.0, text.text_height);
+                assert_close(90.0, text.rotation);
+                assert_close(1.0, text.relative_x_scale_factor);
+            }
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn transform_lw_polyline() {
+        let matrix = TransformationMatrix::translation(&Vector::new(0.0, 0.0, 5.0))
+            * TransformationMatrix::scale(2.0, 2.0, 2.0);
+        let poly = LwPolyline {
+            vertices: vec![
+                LwPolylineVertex {
+                    x: 1.0,
+                    y: 1.0,
+                    bulge: 1.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 2.0,
+                    y: 1.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut entity = Entity::new(EntityType::LwPolyline(poly));
+        entity.common.elevation = 1.0;
+        entity.transform(&matrix);
+        assert_close(7.0, entity.common.elevation);
+        match entity.specific {
+            EntityType::LwPolyline(poly) => {
+                assert_close(2.0, poly.vertices[0].x);
+                assert_close(4.0, poly.vertices[1].x);
+                assert_close(1.0, poly.vertices[0].bulge);
+            }
+            _ => panic!("expected a polyline"),
+        }
+    }
+}
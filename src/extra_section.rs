@@ -0,0 +1,77 @@
+use std::io::{Read, Write};
+
+use crate::{CodePair, DxfError, DxfResult};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::code_pair_writer::CodePairWriter;
+
+/// A drawing section not otherwise supported, e.g., `ACDSDATA`.  The section's code pairs are
+/// preserved verbatim so it can be written back unchanged.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ExtraSection {
+    /// The section name, e.g., `ACDSDATA`.
+    pub name: String,
+    /// The name of the supported section this section followed when read, or an empty string if
+    /// it was the first section.  The section is written back immediately after that one.
+    pub preceding_section: String,
+    /// The code pairs between `2/<name>` and `0/ENDSEC`.
+    pub pairs: Vec<CodePair>,
+}
+
+impl ExtraSection {
+    /// Creates a new section that is written after the `OBJECTS` section.
+    pub fn new(name: &str, pairs: Vec<CodePair>) -> Self {
+        ExtraSection {
+            name: String::from(name),
+            preceding_section: String::from("OBJECTS"),
+            pairs,
+        }
+    }
+}
+
+// internal visibility only
+impl ExtraSection {
+    pub(crate) fn read<I>(
+        name: String,
+        preceding_section: String,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<ExtraSection>
+    where
+        I: Read,
+    {
+        let mut section = ExtraSection {
+            name,
+            preceding_section,
+            pairs: vec![],
+        };
+        loop {
+            match iter.next() {
+                Some(Ok(pair)) => {
+                    if pair.code == 0 && pair.assert_string()? == "ENDSEC" {
+                        iter.put_back(Ok(pair));
+                        break;
+                    }
+
+                    section.pairs.push(pair);
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            }
+        }
+
+        Ok(section)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(0, "SECTION"))?;
+        writer.write_code_pair(&CodePair::new_string(2, &self.name))?;
+        for pair in &self.pairs {
+            writer.write_code_pair(pair)?;
+        }
+        writer.write_code_pair(&CodePair::new_str(0, "ENDSEC"))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,536 @@
+use std::io::{Read, Write};
+
+use crate::{CodePair, DxfError, DxfResult, Handle, Point, Vector};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::code_pair_writer::CodePairWriter;
+use crate::enums::AcadVersion;
+use crate::helper_functions::*;
+
+/// A single vertex of a polyline `HatchBoundaryPath`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchPolylineVertex {
+    pub x: f64,
+    pub y: f64,
+    pub bulge: f64,
+}
+
+impl HatchPolylineVertex {
+    pub fn new(x: f64, y: f64, bulge: f64) -> Self {
+        HatchPolylineVertex { x, y, bulge }
+    }
+}
+
+/// A single edge of a non-polyline `HatchBoundaryPath`.  All points are in OCS.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum HatchBoundaryPathEdge {
+    Line {
+        start: Point,
+        end: Point,
+    },
+    /// Angles are in degrees.
+    CircularArc {
+        center: Point,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    /// `major_axis` is relative to `center`; angles are in degrees.
+    EllipticArc {
+        center: Point,
+        major_axis: Vector,
+        minor_axis_ratio: f64,
+        start_angle: f64,
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    Spline {
+        degree_of_curve: i32,
+        is_rational: bool,
+        is_periodic: bool,
+        knot_values: Vec<f64>,
+        control_points: Vec<Point>,
+        weights: Vec<f64>,
+        fit_points: Vec<Point>,
+        start_tangent: Vector,
+        end_tangent: Vector,
+    },
+}
+
+/// The geometry of a `HatchBoundaryPath`; either a polyline or a collection of edges.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum HatchBoundaryPathData {
+    Polyline {
+        is_closed: bool,
+        vertices: Vec<HatchPolylineVertex>,
+    },
+    Edges(Vec<HatchBoundaryPathEdge>),
+}
+
+/// A single boundary loop of a `Hatch` entity.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchBoundaryPath {
+    /// Boundary path type flags.  The polyline flag is controlled by `data`.
+    pub flags: i32,
+    pub data: HatchBoundaryPathData,
+    pub source_boundary_object_handles: Vec<Handle>,
+}
+
+impl Default for HatchBoundaryPath {
+    fn default() -> Self {
+        HatchBoundaryPath {
+            flags: 1,
+            data: HatchBoundaryPathData::Edges(vec![]),
+            source_boundary_object_handles: vec![],
+        }
+    }
+}
+
+impl HatchBoundaryPath {
+    pub fn polyline(vertices: Vec<HatchPolylineVertex>, is_closed: bool) -> Self {
+        HatchBoundaryPath {
+            data: HatchBoundaryPathData::Polyline {
+                is_closed,
+                vertices,
+            },
+            ..Default::default()
+        }
+    }
+    pub fn edges(edges: Vec<HatchBoundaryPathEdge>) -> Self {
+        HatchBoundaryPath {
+            data: HatchBoundaryPathData::Edges(edges),
+            ..Default::default()
+        }
+    }
+    pub fn get_is_external(&self) -> bool {
+        self.flags & 1 != 0
+    }
+    pub fn set_is_external(&mut self, val: bool) {
+        self.set_flag(1, val);
+    }
+    pub fn get_is_polyline(&self) -> bool {
+        match self.data {
+            HatchBoundaryPathData::Polyline { .. } => true,
+            HatchBoundaryPathData::Edges(_) => false,
+        }
+    }
+    pub fn get_is_derived(&self) -> bool {
+        self.flags & 4 != 0
+    }
+    pub fn set_is_derived(&mut self, val: bool) {
+        self.set_flag(4, val);
+    }
+    pub fn get_is_text_box(&self) -> bool {
+        self.flags & 8 != 0
+    }
+    pub fn set_is_text_box(&mut self, val: bool) {
+        self.set_flag(8, val);
+    }
+    pub fn get_is_outermost(&self) -> bool {
+        self.flags & 16 != 0
+    }
+    pub fn set_is_outermost(&mut self, val: bool) {
+        self.set_flag(16, val);
+    }
+    fn set_flag(&mut self, mask: i32, val: bool) {
+        if val {
+            self.flags |= mask;
+        } else {
+            self.flags &= !mask;
+        }
+    }
+}
+
+// internal visibility only
+impl HatchBoundaryPath {
+    pub(crate) fn read<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<Option<HatchBoundaryPath>>
+    where
+        I: Read,
+    {
+        // check the first pair; only code 92 can start one of these
+        let flags = match iter.next() {
+            Some(Ok(pair @ CodePair { code: 92, .. })) => pair.assert_i32()?,
+            Some(Ok(pair)) => {
+                iter.put_back(Ok(pair));
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        };
+
+        let data = if flags & 2 != 0 {
+            HatchBoundaryPathData::Polyline {
+                is_closed: false,
+                vertices: vec![],
+            }
+        } else {
+            HatchBoundaryPathData::Edges(vec![])
+        };
+        let mut path = HatchBoundaryPath {
+            flags: flags & !2,
+            data,
+            source_boundary_object_handles: vec![],
+        };
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(Some(path)),
+            };
+            match (&mut path.data, pair.code) {
+                (HatchBoundaryPathData::Polyline { .. }, 72) => (), // has bulge flag; recomputed on write
+                (HatchBoundaryPathData::Polyline { is_closed, .. }, 73) => {
+                    *is_closed = as_bool(pair.assert_i16()?);
+                }
+                (HatchBoundaryPathData::Polyline { vertices, .. }, 10) => {
+                    vertices.push(HatchPolylineVertex {
+                        x: pair.assert_f64()?,
+                        ..Default::default()
+                    });
+                }
+                (HatchBoundaryPathData::Polyline { vertices, .. }, 20) => {
+                    vec_last!(vertices).y = pair.assert_f64()?;
+                }
+                (HatchBoundaryPathData::Polyline { vertices, .. }, 42) => {
+                    vec_last!(vertices).bulge = pair.assert_f64()?;
+                }
+                (HatchBoundaryPathData::Edges(edges), 72) => {
+                    edges.push(HatchBoundaryPathEdge::read(&pair, iter)?);
+                }
+                (_, 93) => (), // vertex or edge count; we just read as many as we're given
+                (_, 97) => (), // source boundary object count; we just read as many as we're given
+                (_, 330) => {
+                    path.source_boundary_object_handles.push(pair.as_handle()?);
+                }
+                _ => {
+                    // the path is complete; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    return Ok(Some(path));
+                }
+            }
+        }
+    }
+    pub(crate) fn write<T>(
+        &self,
+        version: AcadVersion,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        let flags = if self.get_is_polyline() {
+            self.flags | 2
+        } else {
+            self.flags & !2
+        };
+        writer.write_code_pair(&CodePair::new_i32(92, flags))?;
+        match self.data {
+            HatchBoundaryPathData::Polyline {
+                is_closed,
+                ref vertices,
+            } => {
+                let has_bulge = vertices.iter().any(|v| v.bulge != 0.0);
+                writer.write_code_pair(&CodePair::new_i16(72, as_i16(has_bulge)))?;
+                writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_closed)))?;
+                writer.write_code_pair(&CodePair::new_i32(93, vertices.len() as i32))?;
+                for v in vertices {
+                    writer.write_code_pair(&CodePair::new_f64(10, v.x))?;
+                    writer.write_code_pair(&CodePair::new_f64(20, v.y))?;
+                    if has_bulge {
+                        writer.write_code_pair(&CodePair::new_f64(42, v.bulge))?;
+                    }
+                }
+            }
+            HatchBoundaryPathData::Edges(ref edges) => {
+                writer.write_code_pair(&CodePair::new_i32(93, edges.len() as i32))?;
+                for edge in edges {
+                    edge.write(version, writer)?;
+                }
+            }
+        }
+        writer.write_code_pair(&CodePair::new_i32(
+            97,
+            self.source_boundary_object_handles.len() as i32,
+        ))?;
+        for handle in &self.source_boundary_object_handles {
+            writer.write_code_pair(&CodePair::new_string(330, &handle.as_string()))?;
+        }
+        Ok(())
+    }
+}
+
+// internal visibility only
+impl HatchBoundaryPathEdge {
+    fn read<I>(
+        edge_type: &CodePair,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<HatchBoundaryPathEdge>
+    where
+        I: Read,
+    {
+        let mut edge = match edge_type.assert_i16()? {
+            1 => HatchBoundaryPathEdge::Line {
+                start: Point::origin(),
+                end: Point::origin(),
+            },
+            2 => HatchBoundaryPathEdge::CircularArc {
+                center: Point::origin(),
+                radius: 0.0,
+                start_angle: 0.0,
+                end_angle: 360.0,
+                is_counter_clockwise: true,
+            },
+            3 => HatchBoundaryPathEdge::EllipticArc {
+                center: Point::origin(),
+                major_axis: Vector::x_axis(),
+                minor_axis_ratio: 1.0,
+                start_angle: 0.0,
+                end_angle: 360.0,
+                is_counter_clockwise: true,
+            },
+            4 => HatchBoundaryPathEdge::Spline {
+                degree_of_curve: 3,
+                is_rational: false,
+                is_periodic: false,
+                knot_values: vec![],
+                control_points: vec![],
+                weights: vec![],
+                fit_points: vec![],
+                start_tangent: Vector::zero(),
+                end_tangent: Vector::zero(),
+            },
+            _ => return Err(DxfError::UnexpectedEnumValue(edge_type.offset)),
+        };
+
+        let mut read_fit_point_count = false;
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(edge),
+            };
+            match (&mut edge, pair.code) {
+                (HatchBoundaryPathEdge::Line { start, .. }, 10) => start.x = pair.assert_f64()?,
+                (HatchBoundaryPathEdge::Line { start, .. }, 20) => start.y = pair.assert_f64()?,
+                (HatchBoundaryPathEdge::Line { end, .. }, 11) => end.x = pair.assert_f64()?,
+                (HatchBoundaryPathEdge::Line { end, .. }, 21) => end.y = pair.assert_f64()?,
+
+                (HatchBoundaryPathEdge::CircularArc { center, .. }, 10) => {
+                    center.x = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::CircularArc { center, .. }, 20) => {
+                    center.y = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::CircularArc { radius, .. }, 40) => {
+                    *radius = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::CircularArc { start_angle, .. }, 50) => {
+                    *start_angle = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::CircularArc { end_angle, .. }, 51) => {
+                    *end_angle = pair.assert_f64()?
+                }
+                (
+                    HatchBoundaryPathEdge::CircularArc {
+                        is_counter_clockwise,
+                        ..
+                    },
+                    73,
+                ) => *is_counter_clockwise = as_bool(pair.assert_i16()?),
+
+                (HatchBoundaryPathEdge::EllipticArc { center, .. }, 10) => {
+                    center.x = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::EllipticArc { center, .. }, 20) => {
+                    center.y = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::EllipticArc { major_axis, .. }, 11) => {
+                    major_axis.x = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::EllipticArc { major_axis, .. }, 21) => {
+                    major_axis.y = pair.assert_f64()?
+                }
+                (
+                    HatchBoundaryPathEdge::EllipticArc {
+                        minor_axis_ratio, ..
+                    },
+                    40,
+                ) => *minor_axis_ratio = pair.assert_f64()?,
+                (HatchBoundaryPathEdge::EllipticArc { start_angle, .. }, 50) => {
+                    *start_angle = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::EllipticArc { end_angle, .. }, 51) => {
+                    *end_angle = pair.assert_f64()?
+                }
+                (
+                    HatchBoundaryPathEdge::EllipticArc {
+                        is_counter_clockwise,
+                        ..
+                    },
+                    73,
+                ) => *is_counter_clockwise = as_bool(pair.assert_i16()?),
+
+                (
+                    HatchBoundaryPathEdge::Spline {
+                        degree_of_curve, ..
+                    },
+                    94,
+                ) => *degree_of_curve = pair.assert_i32()?,
+                (HatchBoundaryPathEdge::Spline { is_rational, .. }, 73) => {
+                    *is_rational = as_bool(pair.assert_i16()?)
+                }
+                (HatchBoundaryPathEdge::Spline { is_periodic, .. }, 74) => {
+                    *is_periodic = as_bool(pair.assert_i16()?)
+                }
+                (HatchBoundaryPathEdge::Spline { .. }, 95) => (), // knot count; we just read as many as we're given
+                (HatchBoundaryPathEdge::Spline { .. }, 96) => (), // control point count; we just read as many as we're given
+                (HatchBoundaryPathEdge::Spline { knot_values, .. }, 40) => {
+                    knot_values.push(pair.assert_f64()?)
+                }
+                (HatchBoundaryPathEdge::Spline { control_points, .. }, 10) => {
+                    control_points.push(Point::new(pair.assert_f64()?, 0.0, 0.0))
+                }
+                (HatchBoundaryPathEdge::Spline { control_points, .. }, 20) => {
+                    vec_last!(control_points).y = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::Spline { weights, .. }, 42) => {
+                    weights.push(pair.assert_f64()?)
+                }
+                (HatchBoundaryPathEdge::Spline { .. }, 97) if !read_fit_point_count => {
+                    // code 97 is also the boundary path's source object count, so only treat it as the
+                    // fit point count when it's followed by fit data or another code 97
+                    let next = match iter.next() {
+                        Some(Ok(next)) => next,
+                        Some(Err(e)) => return Err(e),
+                        None => {
+                            iter.put_back(Ok(pair));
+                            return Ok(edge);
+                        }
+                    };
+                    let is_fit_data = matches!(next.code, 11 | 12 | 13 | 97);
+                    iter.put_back(Ok(next));
+                    if !is_fit_data {
+                        iter.put_back(Ok(pair));
+                        return Ok(edge);
+                    }
+                    read_fit_point_count = true;
+                }
+                (HatchBoundaryPathEdge::Spline { fit_points, .. }, 11) => {
+                    fit_points.push(Point::new(pair.assert_f64()?, 0.0, 0.0))
+                }
+                (HatchBoundaryPathEdge::Spline { fit_points, .. }, 21) => {
+                    vec_last!(fit_points).y = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::Spline { start_tangent, .. }, 12) => {
+                    start_tangent.x = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::Spline { start_tangent, .. }, 22) => {
+                    start_tangent.y = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::Spline { end_tangent, .. }, 13) => {
+                    end_tangent.x = pair.assert_f64()?
+                }
+                (HatchBoundaryPathEdge::Spline { end_tangent, .. }, 23) => {
+                    end_tangent.y = pair.assert_f64()?
+                }
+                _ => {
+                    // the edge is complete; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    return Ok(edge);
+                }
+            }
+        }
+    }
+    fn write<T>(&self, version: AcadVersion, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        match *self {
+            HatchBoundaryPathEdge::Line { ref start, ref end } => {
+                writer.write_code_pair(&CodePair::new_i16(72, 1))?;
+                writer.write_code_pair(&CodePair::new_f64(10, start.x))?;
+                writer.write_code_pair(&CodePair::new_f64(20, start.y))?;
+                writer.write_code_pair(&CodePair::new_f64(11, end.x))?;
+                writer.write_code_pair(&CodePair::new_f64(21, end.y))?;
+            }
+            HatchBoundaryPathEdge::CircularArc {
+                ref center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                writer.write_code_pair(&CodePair::new_i16(72, 2))?;
+                writer.write_code_pair(&CodePair::new_f64(10, center.x))?;
+                writer.write_code_pair(&CodePair::new_f64(20, center.y))?;
+                writer.write_code_pair(&CodePair::new_f64(40, radius))?;
+                writer.write_code_pair(&CodePair::new_f64(50, start_angle))?;
+                writer.write_code_pair(&CodePair::new_f64(51, end_angle))?;
+                writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_counter_clockwise)))?;
+            }
+            HatchBoundaryPathEdge::EllipticArc {
+                ref center,
+                ref major_axis,
+                minor_axis_ratio,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                writer.write_code_pair(&CodePair::new_i16(72, 3))?;
+                writer.write_code_pair(&CodePair::new_f64(10, center.x))?;
+                writer.write_code_pair(&CodePair::new_f64(20, center.y))?;
+                writer.write_code_pair(&CodePair::new_f64(11, major_axis.x))?;
+                writer.write_code_pair(&CodePair::new_f64(21, major_axis.y))?;
+                writer.write_code_pair(&CodePair::new_f64(40, minor_axis_ratio))?;
+                writer.write_code_pair(&CodePair::new_f64(50, start_angle))?;
+                writer.write_code_pair(&CodePair::new_f64(51, end_angle))?;
+                writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_counter_clockwise)))?;
+            }
+            HatchBoundaryPathEdge::Spline {
+                degree_of_curve,
+                is_rational,
+                is_periodic,
+                ref knot_values,
+                ref control_points,
+                ref weights,
+                ref fit_points,
+                ref start_tangent,
+                ref end_tangent,
+            } => {
+                writer.write_code_pair(&CodePair::new_i16(72, 4))?;
+                writer.write_code_pair(&CodePair::new_i32(94, degree_of_curve))?;
+                writer.write_code_pair(&CodePair::new_i16(73, as_i16(is_rational)))?;
+                writer.write_code_pair(&CodePair::new_i16(74, as_i16(is_periodic)))?;
+                writer.write_code_pair(&CodePair::new_i32(95, knot_values.len() as i32))?;
+                writer.write_code_pair(&CodePair::new_i32(96, control_points.len() as i32))?;
+                for knot in knot_values {
+                    writer.write_code_pair(&CodePair::new_f64(40, *knot))?;
+                }
+                for point in control_points {
+                    writer.write_code_pair(&CodePair::new_f64(10, point.x))?;
+                    writer.write_code_pair(&CodePair::new_f64(20, point.y))?;
+                }
+                for weight in weights {
+                    writer.write_code_pair(&CodePair::new_f64(42, *weight))?;
+                }
+                if version >= AcadVersion::R2010 {
+                    writer.write_code_pair(&CodePair::new_i32(97, fit_points.len() as i32))?;
+                    for point in fit_points {
+                        writer.write_code_pair(&CodePair::new_f64(11, point.x))?;
+                        writer.write_code_pair(&CodePair::new_f64(21, point.y))?;
+                    }
+                    writer.write_code_pair(&CodePair::new_f64(12, start_tangent.x))?;
+                    writer.write_code_pair(&CodePair::new_f64(22, start_tangent.y))?;
+                    writer.write_code_pair(&CodePair::new_f64(13, end_tangent.x))?;
+                    writer.write_code_pair(&CodePair::new_f64(23, end_tangent.y))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{Read, Write};
+
+use crate::{CodePair, DxfResult, Point, Vector};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::code_pair_writer::CodePairWriter;
+
+/// A single line of a `Hatch` entity's fill pattern.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchPatternDefinitionLine {
+    /// Pattern line angle in degrees.
+    pub angle: f64,
+    pub base_point: Point,
+    pub offset: Vector,
+    pub dash_lengths: Vec<f64>,
+}
+
+impl Default for HatchPatternDefinitionLine {
+    fn default() -> Self {
+        HatchPatternDefinitionLine {
+            angle: 0.0,
+            base_point: Point::origin(),
+            offset: Vector::zero(),
+            dash_lengths: vec![],
+        }
+    }
+}
+
+// internal visibility only
+impl HatchPatternDefinitionLine {
+    pub(crate) fn read<I>(
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<Option<HatchPatternDefinitionLine>>
+    where
+        I: Read,
+    {
+        // check the first pair; only code 53 can start one of these
+        let mut line = match iter.next() {
+            Some(Ok(pair @ CodePair { code: 53, .. })) => HatchPatternDefinitionLine {
+                angle: pair.assert_f64()?,
+                ..Default::default()
+            },
+            Some(Ok(pair)) => {
+                iter.put_back(Ok(pair));
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        };
+
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(Some(line)),
+            };
+            match pair.code {
+                43 => {
+                    line.base_point.x = pair.assert_f64()?;
+                }
+                44 => {
+                    line.base_point.y = pair.assert_f64()?;
+                }
+                45 => {
+                    line.offset.x = pair.assert_f64()?;
+                }
+                46 => {
+                    line.offset.y = pair.assert_f64()?;
+                }
+                49 => {
+                    line.dash_lengths.push(pair.assert_f64()?);
+                }
+                79 => (), // dash length count; we just read as many as we're given
+                _ => {
+                    // found another line or the end of the pattern; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    return Ok(Some(line));
+                }
+            }
+        }
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_f64(53, self.angle))?;
+        writer.write_code_pair(&CodePair::new_f64(43, self.base_point.x))?;
+        writer.write_code_pair(&CodePair::new_f64(44, self.base_point.y))?;
+        writer.write_code_pair(&CodePair::new_f64(45, self.offset.x))?;
+        writer.write_code_pair(&CodePair::new_f64(46, self.offset.y))?;
+        writer.write_code_pair(&CodePair::new_i16(79, self.dash_lengths.len() as i16))?;
+        for dash_length in &self.dash_lengths {
+            writer.write_code_pair(&CodePair::new_f64(49, *dash_length))?;
+        }
+        Ok(())
+    }
+}
@@ -44,13 +44,19 @@ impl Header {
                                             iter.put_back(Ok(pair));
                                             break;
                                         } else {
-                                            header
-                                                .set_header_value(&last_header_variable, &pair)?;
+                                            if let Err(e) = header
+                                                .set_header_value(&last_header_variable, &pair)
+                                            {
+                                                iter.recover(e)?;
+                                            }
                                             if last_header_variable == "$ACADVER"
                                                 && header.version >= AcadVersion::R2007
                                             {
                                                 iter.read_as_utf8();
                                             }
+                                            if last_header_variable == "$DWGCODEPAGE" {
+                                                iter.read_with_code_page(&header.drawing_code_page);
+                                            }
                                         }
                                     }
                                     Some(Err(e)) => return Err(e),
@@ -58,7 +64,7 @@ impl Header {
                                 }
                             }
                         }
-                        _ => return Err(DxfError::UnexpectedCodePair(pair, String::from(""))),
+                        _ => iter.recover(DxfError::UnexpectedCodePair(pair, String::from("")))?,
                     }
                 }
                 Some(Err(e)) => return Err(e),
@@ -19,7 +19,7 @@ use enum_primitive::FromPrimitive;
 
 use crate::enums::*;
 use crate::tables::Layer;
-use crate::{CodePair, Color, DxfError, DxfResult};
+use crate::{CodePair, Color, DxfError, DxfResult, Point, Vector};
 
 pub(crate) fn verify_code(pair: &CodePair, expected: i32) -> DxfResult<()> {
     if expected == pair.code {
@@ -188,6 +188,78 @@ pub(crate) fn combine_points_3<F, T>(
     v3.clear();
 }
 
+/// Returns the circular arc described by the bulge of a polyline segment as `(center, radius,
+/// start_angle, included_angle)` with angles in radians, or `None` if the segment is straight.  The
+/// bulge is the tangent of a quarter of the included angle; a positive bulge is counter-clockwise.
+pub(crate) fn bulge_to_arc(
+    start: &Point,
+    end: &Point,
+    bulge: f64,
+) -> Option<(Point, f64, f64, f64)> {
+    let chord = end - start;
+    let chord_length = (chord.x * chord.x + chord.y * chord.y).sqrt();
+    if bulge.abs() < 1.0e-12 || chord_length < 1.0e-12 {
+        return None;
+    }
+
+    let included_angle = 4.0 * bulge.atan();
+    let radius = chord_length * (1.0 + bulge * bulge) / (4.0 * bulge.abs());
+    let left = Vector::new(-chord.y, chord.x, 0.0) / chord_length;
+    let offset = chord_length * (1.0 - bulge * bulge) / (4.0 * bulge);
+    let center = start.lerp(end, 0.5) + left * offset;
+    let start_angle = (start.y - center.y).atan2(start.x - center.x);
+    Some((center, radius, start_angle, included_angle))
+}
+
+/// Finds the maximum of a unimodal function on `[a, b]` by golden section search.
+pub(crate) fn golden_section_maximum<F>(f: F, a: f64, b: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (a, b);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut fc, mut fd) = (f(c), f(d));
+    for _ in 0..64 {
+        if fc > fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+    }
+
+    (a + b) / 2.0
+}
+
+/// Integrates `f` over `[a, b]` with the five point Gauss-Legendre rule.
+pub(crate) fn gauss_legendre<F>(f: &F, a: f64, b: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    const NODES: [(f64, f64); 5] = [
+        (0.0, 0.568_888_888_888_888_9),
+        (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    ];
+    let (half, mid) = ((b - a) * 0.5, (a + b) * 0.5);
+    NODES
+        .iter()
+        .map(|(x, w)| w * f(mid + half * x))
+        .sum::<f64>()
+        * half
+}
+
 pub(crate) fn default_if_empty(val: &mut String, default: &str) {
     if val.is_empty() {
         *val = String::from(default);
@@ -245,6 +317,17 @@ fn parse_i32_test() {
     assert_eq!(2, parse_i32("  2 ".to_string(), 0).unwrap());
 }
 
+pub(crate) fn index_from_i32(value: i32, offset: usize) -> DxfResult<usize> {
+    use std::convert::TryFrom;
+    usize::try_from(value).map_err(|_| DxfError::ParseError(offset))
+}
+
+#[test]
+fn index_from_i32_test() {
+    assert_eq!(2, index_from_i32(2, 0).unwrap());
+    assert!(index_from_i32(-1, 0).is_err());
+}
+
 pub(crate) fn parse_i64(s: String, offset: usize) -> DxfResult<i64> {
     match s.trim().parse::<i64>() {
         Ok(l) => Ok(l),
@@ -373,6 +456,38 @@ macro_rules! try_option_io_result_into_err {
     };
 }
 
+// implements a binary operator for every combination of owned and borrowed operands; the body
+// sees both operands as references
+macro_rules! impl_binary_op {
+    ($trait: ident, $method: ident, $lhs: ty, $rhs: ty, $output: ty, |$a: ident, $b: ident| $body: expr) => {
+        impl ::std::ops::$trait<$rhs> for $lhs {
+            type Output = $output;
+            fn $method(self, other: $rhs) -> $output {
+                (&self).$method(&other)
+            }
+        }
+        impl<'a> ::std::ops::$trait<&'a $rhs> for $lhs {
+            type Output = $output;
+            fn $method(self, other: &'a $rhs) -> $output {
+                (&self).$method(other)
+            }
+        }
+        impl<'a> ::std::ops::$trait<$rhs> for &'a $lhs {
+            type Output = $output;
+            fn $method(self, other: $rhs) -> $output {
+                self.$method(&other)
+            }
+        }
+        impl<'a, 'b> ::std::ops::$trait<&'b $rhs> for &'a $lhs {
+            type Output = $output;
+            fn $method(self, $b: &'b $rhs) -> $output {
+                let $a = self;
+                $body
+            }
+        }
+    };
+}
+
 // verifies that an actual value matches the expected value
 macro_rules! assert_or_err {
     ($actual: expr, $expected: expr, $offset: expr) => {
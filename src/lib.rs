@@ -127,6 +127,11 @@ extern crate serde_derive;
 #[cfg(feature = "serialize")]
 extern crate serde;
 
+mod acds_data;
+pub use crate::acds_data::{AcDsData, AcDsRecord};
+
+mod code_page;
+
 mod code_pair;
 pub use crate::code_pair::CodePair;
 
@@ -161,18 +166,74 @@ pub use crate::drawing::Drawing;
 mod drawing_item;
 pub use crate::drawing_item::{DrawingItem, DrawingItemMut};
 
+mod dxf_reader;
+pub use crate::dxf_reader::DxfReader;
+
+mod dxf_writer;
+pub use crate::dxf_writer::DxfWriter;
+
+mod audit_report;
+pub use crate::audit_report::{AuditProblem, AuditProblemKind, AuditReport};
+
+mod diagnostic;
+pub use crate::diagnostic::Diagnostic;
+
+mod load_options;
+pub use crate::load_options::LoadOptions;
+
+mod save_options;
+pub use crate::save_options::SaveOptions;
+
+mod purge_options;
+pub use crate::purge_options::PurgeOptions;
+
+mod purge_report;
+pub use crate::purge_report::PurgeReport;
+
+mod extra_section;
+pub use crate::extra_section::ExtraSection;
+
+mod hatch_boundary_path;
+pub use crate::hatch_boundary_path::{
+    HatchBoundaryPath, HatchBoundaryPathData, HatchBoundaryPathEdge, HatchPolylineVertex,
+};
+
+mod hatch_pattern_definition_line;
+pub use crate::hatch_pattern_definition_line::HatchPatternDefinitionLine;
+
+mod mleader_context_data;
+pub use crate::mleader_context_data::{
+    MLeaderBlockContent, MLeaderBreak, MLeaderContextData, MLeaderLine, MLeaderLineBreak,
+    MLeaderRoot, MLeaderTextContent,
+};
+
+mod polyline_segment;
+pub use crate::polyline_segment::PolylineSegment;
+
 mod section_geometry_settings;
 pub use crate::section_geometry_settings::SectionGeometrySettings;
 
 mod section_type_settings;
 pub use crate::section_type_settings::SectionTypeSettings;
 
+mod table_cell;
+pub use crate::table_cell::{
+    TableCell, TableCellBlockAttribute, TableCellOverrides, TableCellRange, TableCellValue,
+    TableCellValueData,
+};
+
 mod table_cell_style;
 pub use crate::table_cell_style::TableCellStyle;
 
 mod transformation_matrix;
 pub use crate::transformation_matrix::TransformationMatrix;
 
+mod transparency;
+pub use crate::transparency::Transparency;
+
+mod true_color;
+pub use crate::true_color::TrueColor;
+
 pub mod enums;
 
 mod color;
@@ -204,6 +265,9 @@ mod code_pair_writer;
 mod block;
 pub use crate::block::Block;
 
+mod bounding_box;
+pub use crate::bounding_box::BoundingBox;
+
 mod class;
 pub use crate::class::Class;
 
@@ -214,7 +278,10 @@ mod line_weight;
 pub use crate::line_weight::LineWeight;
 
 mod entity;
-pub use crate::entity::LwPolylineVertex;
+pub use crate::entity::{
+    HatchGradientColor, LwPolylineVertex, MLeaderArrowheadOverride, MLeaderBlockAttribute,
+    MeshEdge, MeshFace,
+};
 
 mod object;
 pub use crate::object::{GeoMeshPoint, MLineStyleElement};
@@ -226,7 +293,10 @@ mod dxf_result;
 pub use crate::dxf_result::DxfResult;
 
 mod entity_iter;
+mod entity_transform;
 mod object_iter;
+mod tessellation;
+mod version_conversion;
 
 //------------------------------- test imports ---------------------------------
 
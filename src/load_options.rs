@@ -0,0 +1,9 @@
+/// Options controlling how a `Drawing` is loaded.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct LoadOptions {
+    /// When `true`, code pairs with malformed values and items that can't be read are skipped
+    /// and reported as `Diagnostic`s instead of failing the whole load.  Skipped values keep
+    /// their defaults.  When `false`, any entity or object that can't be read fails the load.
+    pub recovery: bool,
+}
@@ -57,12 +57,76 @@ fn empty_file_no_trailing_newline() {
 
 #[test]
 fn unsupported_section() {
-    let _file = from_section(
+    let file = from_section(
         "UNSUPPORTED_SECTION",
         vec!["1", "garbage value 1", "2", "garbage value 2"]
             .join("\n")
             .as_str(),
     );
+    assert_eq!(1, file.extra_sections.len());
+    let section = &file.extra_sections[0];
+    assert_eq!("UNSUPPORTED_SECTION", section.name);
+    assert_eq!("", section.preceding_section);
+    assert_eq!(
+        vec![
+            CodePair::new_str(1, "garbage value 1"),
+            CodePair::new_str(2, "garbage value 2"),
+        ],
+        section.pairs
+    );
+}
+
+#[test]
+fn unsupported_section_is_written_in_place() {
+    let file = parse_drawing(
+        [
+            "  0",
+            "SECTION",
+            "  2",
+            "ENTITIES",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "UNSUPPORTED_SECTION",
+            "  1",
+            "garbage value",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "OBJECTS",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "EOF",
+        ]
+        .join("\r\n")
+        .as_str(),
+    );
+    assert_eq!("ENTITIES", file.extra_sections[0].preceding_section);
+    assert_contains(
+        &file,
+        [
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "UNSUPPORTED_SECTION",
+            "  1",
+            "garbage value",
+            "  0",
+            "ENDSEC",
+            "  0",
+            "SECTION",
+            "  2",
+            "OBJECTS",
+        ]
+        .join("\r\n"),
+    );
 }
 
 #[test]
@@ -403,15 +467,85 @@ fn dont_write_utf8_bom() {
 
 #[test]
 fn write_unicode_as_ascii() {
+    // characters not in the drawing's code page are escaped
     let mut drawing = Drawing::new();
     drawing.header.version = AcadVersion::R2004;
-    drawing.header.project_name = String::from("è");
+    drawing.header.project_name = String::from("你");
     assert_contains(
         &drawing,
-        vec!["  9", "$PROJECTNAME", "  1", "\\U+00E8"].join("\r\n"),
+        vec!["  9", "$PROJECTNAME", "  1", "\\U+4F60"].join("\r\n"),
     );
 }
 
+#[test]
+fn write_text_in_drawing_code_page() {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2004;
+    drawing.header.drawing_code_page = String::from("ANSI_1251");
+    drawing.header.project_name = String::from("Аа");
+    let mut buf = Cursor::new(vec![]);
+    drawing.save(&mut buf).ok().unwrap();
+    let expected = [
+        b"$PROJECTNAME\r\n  1\r\n".to_vec(),
+        vec![0xC0, 0xE0],
+        b"\r\n".to_vec(),
+    ]
+    .concat();
+    let actual = buf.into_inner();
+    assert!(actual.windows(expected.len()).any(|w| w == &expected[..]));
+}
+
+#[test]
+fn read_text_in_drawing_code_page() {
+    let head = vec![
+        "  0",
+        "SECTION",
+        "  2",
+        "HEADER",
+        "  9",
+        "$ACADVER",
+        "  1",
+        "AC1015",
+        "  9",
+        "$DWGCODEPAGE",
+        "  3",
+        "ANSI_932",
+        "  9",
+        "$PROJECTNAME",
+        "  1",
+        "",
+    ]
+    .join("\r\n");
+    let tail = ["", "  0", "ENDSEC", "  0", "EOF"].join("\r\n");
+    let mut bytes = head.as_bytes().to_vec();
+    bytes.extend_from_slice(&[0x93, 0xFA, 0x96, 0x7B]); // `日本` in Shift-JIS
+    bytes.extend_from_slice(tail.as_bytes());
+
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("ANSI_932", drawing.header.drawing_code_page);
+    assert_eq!("日本", drawing.header.project_name);
+
+    // an explicit encoding isn't overridden by the code page
+    let drawing = unwrap_drawing(Drawing::load_with_encoding(
+        &mut bytes.as_slice(),
+        encoding_rs::WINDOWS_1252,
+    ));
+    assert_ne!("日本", drawing.header.project_name);
+}
+
+#[test]
+fn round_trip_text_in_drawing_code_page() {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2004;
+    drawing.header.drawing_code_page = String::from("ANSI_1251");
+    drawing.header.project_name = String::from("Чертёж 你");
+    let mut buf = Cursor::new(vec![]);
+    drawing.save(&mut buf).ok().unwrap();
+    let bytes = buf.into_inner();
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("Чертёж 你", drawing.header.project_name);
+}
+
 #[test]
 fn write_unicode_as_utf8() {
     let mut drawing = Drawing::new();
@@ -0,0 +1,674 @@
+use std::io::{Read, Write};
+
+use crate::{CodePair, DxfError, DxfResult, Handle, Point, TransformationMatrix, Vector};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::code_pair_writer::CodePairWriter;
+use crate::enums::AcadVersion;
+
+/// The contents of an `MLeader`'s `CONTEXT_DATA{` group.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderContextData {
+    pub content_scale: f64,
+    pub content_base_point: Point,
+    pub text_height: f64,
+    pub arrowhead_size: f64,
+    pub landing_gap: f64,
+    pub text_left_attachment: i16,
+    pub text_right_attachment: i16,
+    pub text_alignment_type: i16,
+    pub block_content_connection_type: i16,
+    /// The MText content, if any.
+    pub text_content: Option<MLeaderTextContent>,
+    /// The block content, if any.
+    pub block_content: Option<MLeaderBlockContent>,
+    pub plane_origin: Point,
+    pub plane_x_axis: Vector,
+    pub plane_y_axis: Vector,
+    pub is_plane_normal_reversed: bool,
+    /// The leader roots, each contained in a `LEADER{` group.
+    pub roots: Vec<MLeaderRoot>,
+    pub text_top_attachment: i16,
+    pub text_bottom_attachment: i16,
+    /// Code pairs that aren't modelled, each with its position in the group so it can be written
+    /// back in place.  A nested group counts as a single position.
+    #[doc(hidden)]
+    pub __unsupported_pairs: Vec<(usize, CodePair)>,
+}
+
+impl Default for MLeaderContextData {
+    fn default() -> Self {
+        MLeaderContextData {
+            content_scale: 1.0,
+            content_base_point: Point::origin(),
+            text_height: 1.0,
+            arrowhead_size: 1.0,
+            landing_gap: 0.0,
+            text_left_attachment: 1,
+            text_right_attachment: 1,
+            text_alignment_type: 0,
+            block_content_connection_type: 0,
+            text_content: None,
+            block_content: None,
+            plane_origin: Point::origin(),
+            plane_x_axis: Vector::x_axis(),
+            plane_y_axis: Vector::y_axis(),
+            is_plane_normal_reversed: false,
+            roots: vec![],
+            text_top_attachment: 9,
+            text_bottom_attachment: 9,
+            __unsupported_pairs: vec![],
+        }
+    }
+}
+
+/// The MText content of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderTextContent {
+    pub text: String,
+    pub normal: Vector,
+    pub text_style_handle: Handle,
+    pub location: Point,
+    pub direction: Vector,
+    pub rotation: f64,
+    pub boundary_width: f64,
+    pub boundary_height: f64,
+    pub line_spacing_factor: f64,
+    pub line_spacing_style: i16,
+    pub color: i32,
+    pub attachment: i16,
+    pub flow_direction: i16,
+    pub background_color: i32,
+    pub background_scale_factor: f64,
+    pub background_transparency: i32,
+    pub is_background_window_color: bool,
+    pub is_background_fill_on: bool,
+    pub column_type: i16,
+    pub is_text_height_automatic: bool,
+    pub column_width: f64,
+    pub column_gutter: f64,
+    pub is_column_flow_reversed: bool,
+    pub column_sizes: Vec<f64>,
+    pub use_word_break: bool,
+}
+
+impl Default for MLeaderTextContent {
+    fn default() -> Self {
+        MLeaderTextContent {
+            text: String::new(),
+            normal: Vector::z_axis(),
+            text_style_handle: Handle::empty(),
+            location: Point::origin(),
+            direction: Vector::x_axis(),
+            rotation: 0.0,
+            boundary_width: 0.0,
+            boundary_height: 0.0,
+            line_spacing_factor: 1.0,
+            line_spacing_style: 1,
+            color: 0,
+            attachment: 1,
+            flow_direction: 1,
+            background_color: 0,
+            background_scale_factor: 1.5,
+            background_transparency: 0,
+            is_background_window_color: false,
+            is_background_fill_on: false,
+            column_type: 0,
+            is_text_height_automatic: false,
+            column_width: 0.0,
+            column_gutter: 0.0,
+            is_column_flow_reversed: false,
+            column_sizes: vec![],
+            use_word_break: true,
+        }
+    }
+}
+
+/// The block content of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderBlockContent {
+    pub block_record_handle: Handle,
+    pub normal: Vector,
+    pub location: Point,
+    pub scale: Vector,
+    pub rotation: f64,
+    pub color: i32,
+    pub transformation_matrix: TransformationMatrix,
+}
+
+impl Default for MLeaderBlockContent {
+    fn default() -> Self {
+        MLeaderBlockContent {
+            block_record_handle: Handle::empty(),
+            normal: Vector::z_axis(),
+            location: Point::origin(),
+            scale: Vector::new(1.0, 1.0, 1.0),
+            rotation: 0.0,
+            color: 0,
+            transformation_matrix: TransformationMatrix::identity(),
+        }
+    }
+}
+
+/// A single leader root of an `MLeader`, contained in a `LEADER{` group.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderRoot {
+    pub last_leader_line_point: Option<Point>,
+    pub dogleg_vector: Option<Vector>,
+    pub breaks: Vec<MLeaderBreak>,
+    pub leader_branch_index: i32,
+    pub dogleg_length: f64,
+    /// The leader lines, each contained in a `LEADER_LINE{` group.
+    pub lines: Vec<MLeaderLine>,
+    pub attachment_direction: i16,
+    /// Code pairs that aren't modelled, each with its position in the group.
+    #[doc(hidden)]
+    pub __unsupported_pairs: Vec<(usize, CodePair)>,
+}
+
+impl Default for MLeaderRoot {
+    fn default() -> Self {
+        MLeaderRoot {
+            last_leader_line_point: None,
+            dogleg_vector: None,
+            breaks: vec![],
+            leader_branch_index: 0,
+            dogleg_length: 0.0,
+            lines: vec![],
+            attachment_direction: 0,
+            __unsupported_pairs: vec![],
+        }
+    }
+}
+
+/// A single leader line of an `MLeaderRoot`, contained in a `LEADER_LINE{` group.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderLine {
+    pub vertices: Vec<Point>,
+    pub breaks: Vec<MLeaderLineBreak>,
+    pub leader_line_index: i32,
+    /// Code pairs that aren't modelled, each with its position in the group.
+    #[doc(hidden)]
+    pub __unsupported_pairs: Vec<(usize, CodePair)>,
+}
+
+/// A break in an `MLeaderRoot`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderBreak {
+    pub start: Point,
+    pub end: Point,
+}
+
+/// A break in a single segment of an `MLeaderLine`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderLineBreak {
+    pub segment_index: i32,
+    pub start: Point,
+    pub end: Point,
+}
+
+// internal visibility only
+impl MLeaderContextData {
+    /// Reads the body of a `CONTEXT_DATA{` group; the opening pair has already been consumed.
+    pub(crate) fn read<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<MLeaderContextData>
+    where
+        I: Read,
+    {
+        let mut context = MLeaderContextData::default();
+        let mut matrix_values = vec![];
+        let mut pair_count = 0;
+        while let Some(pair) = next_group_pair(iter)? {
+            let position = pair_count;
+            pair_count += 1;
+            match pair.code {
+                301 => break, // end of group
+                40 => context.content_scale = pair.assert_f64()?,
+                10 => context.content_base_point.x = pair.assert_f64()?,
+                20 => context.content_base_point.y = pair.assert_f64()?,
+                30 => context.content_base_point.z = pair.assert_f64()?,
+                41 => context.text_height = pair.assert_f64()?,
+                140 => context.arrowhead_size = pair.assert_f64()?,
+                145 => context.landing_gap = pair.assert_f64()?,
+                174 => context.text_left_attachment = pair.assert_i16()?,
+                175 => context.text_right_attachment = pair.assert_i16()?,
+                176 => context.text_alignment_type = pair.assert_i16()?,
+                177 => context.block_content_connection_type = pair.assert_i16()?,
+                290 => {
+                    context.text_content = if pair.assert_bool()? {
+                        Some(MLeaderTextContent::default())
+                    } else {
+                        None
+                    };
+                }
+                296 => {
+                    context.block_content = if pair.assert_bool()? {
+                        Some(MLeaderBlockContent::default())
+                    } else {
+                        None
+                    };
+                }
+                110 => context.plane_origin.x = pair.assert_f64()?,
+                120 => context.plane_origin.y = pair.assert_f64()?,
+                130 => context.plane_origin.z = pair.assert_f64()?,
+                111 => context.plane_x_axis.x = pair.assert_f64()?,
+                121 => context.plane_x_axis.y = pair.assert_f64()?,
+                131 => context.plane_x_axis.z = pair.assert_f64()?,
+                112 => context.plane_y_axis.x = pair.assert_f64()?,
+                122 => context.plane_y_axis.y = pair.assert_f64()?,
+                132 => context.plane_y_axis.z = pair.assert_f64()?,
+                297 => context.is_plane_normal_reversed = pair.assert_bool()?,
+                302 => context.roots.push(MLeaderRoot::read(iter)?),
+                272 => context.text_top_attachment = pair.assert_i16()?,
+                273 => context.text_bottom_attachment = pair.assert_i16()?,
+                _ => {
+                    if let Some(ref mut text) = context.text_content {
+                        if text.apply_code_pair(&pair)? {
+                            continue;
+                        }
+                    }
+                    if let Some(ref mut block) = context.block_content {
+                        match pair.code {
+                            47 => {
+                                matrix_values.push(pair.assert_f64()?);
+                                continue;
+                            }
+                            _ => {
+                                if block.apply_code_pair(&pair)? {
+                                    continue;
+                                }
+                            }
+                        }
+                    }
+                    context.__unsupported_pairs.push((position, pair));
+                }
+            }
+        }
+
+        if let Some(ref mut block) = context.block_content {
+            if !matrix_values.is_empty() {
+                block.transformation_matrix = TransformationMatrix::from_vec(&matrix_values);
+            }
+        }
+
+        Ok(context)
+    }
+    pub(crate) fn write<T>(
+        &self,
+        version: AcadVersion,
+        writer: &mut CodePairWriter<T>,
+    ) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        for pair in self.pairs(version) {
+            writer.write_code_pair(&pair)?;
+        }
+        Ok(())
+    }
+    fn pairs(&self, version: AcadVersion) -> Vec<CodePair> {
+        let mut items = vec![];
+        items.push(vec![CodePair::new_f64(40, self.content_scale)]);
+        add_point(&mut items, 10, &self.content_base_point);
+        items.push(vec![CodePair::new_f64(41, self.text_height)]);
+        items.push(vec![CodePair::new_f64(140, self.arrowhead_size)]);
+        items.push(vec![CodePair::new_f64(145, self.landing_gap)]);
+        items.push(vec![CodePair::new_i16(174, self.text_left_attachment)]);
+        items.push(vec![CodePair::new_i16(175, self.text_right_attachment)]);
+        items.push(vec![CodePair::new_i16(176, self.text_alignment_type)]);
+        items.push(vec![CodePair::new_i16(
+            177,
+            self.block_content_connection_type,
+        )]);
+        items.push(vec![CodePair::new_bool(290, self.text_content.is_some())]);
+        if let Some(ref text) = self.text_content {
+            text.add_pairs(&mut items);
+        }
+        items.push(vec![CodePair::new_bool(296, self.block_content.is_some())]);
+        if let Some(ref block) = self.block_content {
+            block.add_pairs(&mut items);
+        }
+        add_point(&mut items, 110, &self.plane_origin);
+        add_vector(&mut items, 111, &self.plane_x_axis);
+        add_vector(&mut items, 112, &self.plane_y_axis);
+        items.push(vec![CodePair::new_bool(297, self.is_plane_normal_reversed)]);
+        for root in &self.roots {
+            items.push(root.pairs(version));
+        }
+        items.push(vec![CodePair::new_i16(272, self.text_top_attachment)]);
+        items.push(vec![CodePair::new_i16(273, self.text_bottom_attachment)]);
+        group_pairs(
+            CodePair::new_str(300, "CONTEXT_DATA{"),
+            items,
+            &self.__unsupported_pairs,
+            CodePair::new_str(301, "}"),
+        )
+    }
+}
+
+// internal visibility only
+impl MLeaderTextContent {
+    fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            304 => self.text = pair.assert_string()?,
+            11 => self.normal.x = pair.assert_f64()?,
+            21 => self.normal.y = pair.assert_f64()?,
+            31 => self.normal.z = pair.assert_f64()?,
+            340 => self.text_style_handle = pair.as_handle()?,
+            12 => self.location.x = pair.assert_f64()?,
+            22 => self.location.y = pair.assert_f64()?,
+            32 => self.location.z = pair.assert_f64()?,
+            13 => self.direction.x = pair.assert_f64()?,
+            23 => self.direction.y = pair.assert_f64()?,
+            33 => self.direction.z = pair.assert_f64()?,
+            42 => self.rotation = pair.assert_f64()?,
+            43 => self.boundary_width = pair.assert_f64()?,
+            44 => self.boundary_height = pair.assert_f64()?,
+            45 => self.line_spacing_factor = pair.assert_f64()?,
+            170 => self.line_spacing_style = pair.assert_i16()?,
+            90 => self.color = pair.assert_i32()?,
+            171 => self.attachment = pair.assert_i16()?,
+            172 => self.flow_direction = pair.assert_i16()?,
+            91 => self.background_color = pair.assert_i32()?,
+            141 => self.background_scale_factor = pair.assert_f64()?,
+            92 => self.background_transparency = pair.assert_i32()?,
+            291 => self.is_background_window_color = pair.assert_bool()?,
+            292 => self.is_background_fill_on = pair.assert_bool()?,
+            173 => self.column_type = pair.assert_i16()?,
+            293 => self.is_text_height_automatic = pair.assert_bool()?,
+            142 => self.column_width = pair.assert_f64()?,
+            143 => self.column_gutter = pair.assert_f64()?,
+            294 => self.is_column_flow_reversed = pair.assert_bool()?,
+            144 => self.column_sizes.push(pair.assert_f64()?),
+            295 => self.use_word_break = pair.assert_bool()?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+    fn add_pairs(&self, items: &mut Vec<Vec<CodePair>>) {
+        items.push(vec![CodePair::new_string(304, &self.text)]);
+        add_vector(items, 11, &self.normal);
+        items.push(vec![CodePair::new_string(
+            340,
+            &self.text_style_handle.as_string(),
+        )]);
+        add_point(items, 12, &self.location);
+        add_vector(items, 13, &self.direction);
+        items.push(vec![CodePair::new_f64(42, self.rotation)]);
+        items.push(vec![CodePair::new_f64(43, self.boundary_width)]);
+        items.push(vec![CodePair::new_f64(44, self.boundary_height)]);
+        items.push(vec![CodePair::new_f64(45, self.line_spacing_factor)]);
+        items.push(vec![CodePair::new_i16(170, self.line_spacing_style)]);
+        items.push(vec![CodePair::new_i32(90, self.color)]);
+        items.push(vec![CodePair::new_i16(171, self.attachment)]);
+        items.push(vec![CodePair::new_i16(172, self.flow_direction)]);
+        items.push(vec![CodePair::new_i32(91, self.background_color)]);
+        items.push(vec![CodePair::new_f64(141, self.background_scale_factor)]);
+        items.push(vec![CodePair::new_i32(92, self.background_transparency)]);
+        items.push(vec![CodePair::new_bool(
+            291,
+            self.is_background_window_color,
+        )]);
+        items.push(vec![CodePair::new_bool(292, self.is_background_fill_on)]);
+        items.push(vec![CodePair::new_i16(173, self.column_type)]);
+        items.push(vec![CodePair::new_bool(293, self.is_text_height_automatic)]);
+        items.push(vec![CodePair::new_f64(142, self.column_width)]);
+        items.push(vec![CodePair::new_f64(143, self.column_gutter)]);
+        items.push(vec![CodePair::new_bool(294, self.is_column_flow_reversed)]);
+        for column_size in &self.column_sizes {
+            items.push(vec![CodePair::new_f64(144, *column_size)]);
+        }
+        items.push(vec![CodePair::new_bool(295, self.use_word_break)]);
+    }
+}
+
+// internal visibility only
+impl MLeaderBlockContent {
+    fn apply_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
+        match pair.code {
+            341 => self.block_record_handle = pair.as_handle()?,
+            14 => self.normal.x = pair.assert_f64()?,
+            24 => self.normal.y = pair.assert_f64()?,
+            34 => self.normal.z = pair.assert_f64()?,
+            15 => self.location.x = pair.assert_f64()?,
+            25 => self.location.y = pair.assert_f64()?,
+            35 => self.location.z = pair.assert_f64()?,
+            16 => self.scale.x = pair.assert_f64()?,
+            26 => self.scale.y = pair.assert_f64()?,
+            36 => self.scale.z = pair.assert_f64()?,
+            46 => self.rotation = pair.assert_f64()?,
+            93 => self.color = pair.assert_i32()?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+    fn add_pairs(&self, items: &mut Vec<Vec<CodePair>>) {
+        items.push(vec![CodePair::new_string(
+            341,
+            &self.block_record_handle.as_string(),
+        )]);
+        add_vector(items, 14, &self.normal);
+        add_point(items, 15, &self.location);
+        add_vector(items, 16, &self.scale);
+        items.push(vec![CodePair::new_f64(46, self.rotation)]);
+        items.push(vec![CodePair::new_i32(93, self.color)]);
+        for value in self.transformation_matrix.get_values() {
+            items.push(vec![CodePair::new_f64(47, value)]);
+        }
+    }
+}
+
+// internal visibility only
+impl MLeaderRoot {
+    /// Reads the body of a `LEADER{` group; the opening pair has already been consumed.
+    fn read<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<MLeaderRoot>
+    where
+        I: Read,
+    {
+        let mut root = MLeaderRoot::default();
+        let mut pair_count = 0;
+        while let Some(pair) = next_group_pair(iter)? {
+            let position = pair_count;
+            pair_count += 1;
+            match pair.code {
+                303 => break, // end of group
+                290 => {
+                    root.last_leader_line_point = if pair.assert_bool()? {
+                        Some(Point::origin())
+                    } else {
+                        None
+                    };
+                }
+                291 => {
+                    root.dogleg_vector = if pair.assert_bool()? {
+                        Some(Vector::zero())
+                    } else {
+                        None
+                    };
+                }
+                10 => {
+                    root.last_leader_line_point
+                        .get_or_insert_with(Point::origin)
+                        .x = pair.assert_f64()?
+                }
+                20 => {
+                    root.last_leader_line_point
+                        .get_or_insert_with(Point::origin)
+                        .y = pair.assert_f64()?
+                }
+                30 => {
+                    root.last_leader_line_point
+                        .get_or_insert_with(Point::origin)
+                        .z = pair.assert_f64()?
+                }
+                11 => root.dogleg_vector.get_or_insert_with(Vector::zero).x = pair.assert_f64()?,
+                21 => root.dogleg_vector.get_or_insert_with(Vector::zero).y = pair.assert_f64()?,
+                31 => root.dogleg_vector.get_or_insert_with(Vector::zero).z = pair.assert_f64()?,
+                12 => {
+                    // start a new break
+                    root.breaks.push(MLeaderBreak::default());
+                    vec_last!(root.breaks).start.x = pair.assert_f64()?;
+                }
+                22 => vec_last!(root.breaks).start.y = pair.assert_f64()?,
+                32 => vec_last!(root.breaks).start.z = pair.assert_f64()?,
+                13 => vec_last!(root.breaks).end.x = pair.assert_f64()?,
+                23 => vec_last!(root.breaks).end.y = pair.assert_f64()?,
+                33 => vec_last!(root.breaks).end.z = pair.assert_f64()?,
+                90 => root.leader_branch_index = pair.assert_i32()?,
+                40 => root.dogleg_length = pair.assert_f64()?,
+                304 => root.lines.push(MLeaderLine::read(iter)?),
+                271 => root.attachment_direction = pair.assert_i16()?,
+                _ => root.__unsupported_pairs.push((position, pair)),
+            }
+        }
+        Ok(root)
+    }
+    fn pairs(&self, version: AcadVersion) -> Vec<CodePair> {
+        let mut items = vec![];
+        items.push(vec![CodePair::new_bool(
+            290,
+            self.last_leader_line_point.is_some(),
+        )]);
+        items.push(vec![CodePair::new_bool(291, self.dogleg_vector.is_some())]);
+        if let Some(ref point) = self.last_leader_line_point {
+            add_point(&mut items, 10, point);
+        }
+        if let Some(ref vector) = self.dogleg_vector {
+            add_vector(&mut items, 11, vector);
+        }
+        for b in &self.breaks {
+            add_point(&mut items, 12, &b.start);
+            add_point(&mut items, 13, &b.end);
+        }
+        items.push(vec![CodePair::new_i32(90, self.leader_branch_index)]);
+        items.push(vec![CodePair::new_f64(40, self.dogleg_length)]);
+        for line in &self.lines {
+            items.push(line.pairs());
+        }
+        if version >= AcadVersion::R2010 {
+            items.push(vec![CodePair::new_i16(271, self.attachment_direction)]);
+        }
+        group_pairs(
+            CodePair::new_str(302, "LEADER{"),
+            items,
+            &self.__unsupported_pairs,
+            CodePair::new_str(303, "}"),
+        )
+    }
+}
+
+// internal visibility only
+impl MLeaderLine {
+    /// Reads the body of a `LEADER_LINE{` group; the opening pair has already been consumed.
+    fn read<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<MLeaderLine>
+    where
+        I: Read,
+    {
+        let mut line = MLeaderLine::default();
+        let mut pair_count = 0;
+        while let Some(pair) = next_group_pair(iter)? {
+            let position = pair_count;
+            pair_count += 1;
+            match pair.code {
+                305 => break, // end of group
+                10 => {
+                    // start a new vertex
+                    line.vertices.push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                }
+                20 => vec_last!(line.vertices).y = pair.assert_f64()?,
+                30 => vec_last!(line.vertices).z = pair.assert_f64()?,
+                90 => {
+                    // start a new break
+                    line.breaks.push(MLeaderLineBreak {
+                        segment_index: pair.assert_i32()?,
+                        ..Default::default()
+                    });
+                }
+                11 => vec_last!(line.breaks).start.x = pair.assert_f64()?,
+                21 => vec_last!(line.breaks).start.y = pair.assert_f64()?,
+                31 => vec_last!(line.breaks).start.z = pair.assert_f64()?,
+                12 => vec_last!(line.breaks).end.x = pair.assert_f64()?,
+                22 => vec_last!(line.breaks).end.y = pair.assert_f64()?,
+                32 => vec_last!(line.breaks).end.z = pair.assert_f64()?,
+                91 => line.leader_line_index = pair.assert_i32()?,
+                _ => line.__unsupported_pairs.push((position, pair)),
+            }
+        }
+        Ok(line)
+    }
+    fn pairs(&self) -> Vec<CodePair> {
+        let mut items = vec![];
+        for vertex in &self.vertices {
+            add_point(&mut items, 10, vertex);
+        }
+        for b in &self.breaks {
+            items.push(vec![CodePair::new_i32(90, b.segment_index)]);
+            add_point(&mut items, 11, &b.start);
+            add_point(&mut items, 12, &b.end);
+        }
+        items.push(vec![CodePair::new_i32(91, self.leader_line_index)]);
+        group_pairs(
+            CodePair::new_str(304, "LEADER_LINE{"),
+            items,
+            &self.__unsupported_pairs,
+            CodePair::new_str(305, "}"),
+        )
+    }
+}
+
+// private implementation
+
+/// Returns the next pair in a `{`/`}` group, or `None` if the entity ended before the group was closed.
+fn next_group_pair<I>(iter: &mut CodePairPutBack<I>) -> DxfResult<Option<CodePair>>
+where
+    I: Read,
+{
+    match iter.next() {
+        Some(Ok(pair @ CodePair { code: 0, .. })) => {
+            iter.put_back(Ok(pair));
+            Ok(None)
+        }
+        Some(Ok(pair)) => Ok(Some(pair)),
+        Some(Err(e)) => Err(e),
+        None => Ok(None),
+    }
+}
+
+/// Returns the pairs of a `{`/`}` group made of the items, each of which is a single pair or a
+/// nested group, with the unsupported pairs put back at their positions.
+fn group_pairs(
+    start: CodePair,
+    items: Vec<Vec<CodePair>>,
+    unsupported_pairs: &[(usize, CodePair)],
+    end: CodePair,
+) -> Vec<CodePair> {
+    let mut pairs = vec![start];
+    let mut unsupported_pairs = unsupported_pairs.iter().peekable();
+    for (position, item) in items.into_iter().enumerate() {
+        while let Some((_, pair)) = unsupported_pairs.next_if(|(p, _)| *p <= position) {
+            pairs.push(pair.clone());
+        }
+        pairs.extend(item);
+    }
+    pairs.extend(unsupported_pairs.map(|(_, pair)| pair.clone()));
+    pairs.push(end);
+    pairs
+}
+
+fn add_point(items: &mut Vec<Vec<CodePair>>, code: i32, point: &Point) {
+    items.push(vec![CodePair::new_f64(code, point.x)]);
+    items.push(vec![CodePair::new_f64(code + 10, point.y)]);
+    items.push(vec![CodePair::new_f64(code + 20, point.z)]);
+}
+
+fn add_vector(items: &mut Vec<Vec<CodePair>>, code: i32, vector: &Vector) {
+    items.push(vec![CodePair::new_f64(code, vector.x)]);
+    items.push(vec![CodePair::new_f64(code + 10, vector.y)]);
+    items.push(vec![CodePair::new_f64(code + 20, vector.z)]);
+}
@@ -18,6 +18,7 @@ use crate::code_pair_writer::CodePairWriter;
 use crate::enums::*;
 use crate::helper_functions::*;
 use crate::objects::*;
+use crate::x_data;
 
 //------------------------------------------------------------------------------
 //                                                                  GeoMeshPoint
@@ -100,65 +101,49 @@ impl Object {
     where
         I: Read,
     {
-        loop {
-            match iter.next() {
-                // first code pair must be 0/object-type
-                Some(Ok(pair @ CodePair { code: 0, .. })) => {
-                    let type_string = pair.assert_string()?;
-                    if type_string == "ENDSEC" || type_string == "ENDBLK" {
-                        iter.put_back(Ok(pair));
-                        return Ok(None);
-                    }
-
-                    match ObjectType::from_type_string(&type_string) {
-                        Some(e) => {
-                            let mut obj = Object::new(e);
-                            if !obj.apply_custom_reader(iter)? {
-                                // no custom reader, use the auto-generated one
-                                loop {
-                                    match iter.next() {
-                                        Some(Ok(pair @ CodePair { code: 0, .. })) => {
-                                            // new object or ENDSEC
-                                            iter.put_back(Ok(pair));
-                                            break;
-                                        }
-                                        Some(Ok(pair)) => obj.apply_code_pair(&pair, iter)?,
-                                        Some(Err(e)) => return Err(e),
-                                        None => return Err(DxfError::UnexpectedEndOfInput),
-                                    }
-                                }
-
-                                obj.post_parse()?;
-                            }
+        match iter.next() {
+            // first code pair must be 0/object-type
+            Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                let type_string = pair.assert_string()?;
+                if type_string == "ENDSEC" || type_string == "ENDBLK" {
+                    iter.put_back(Ok(pair));
+                    return Ok(None);
+                }
 
-                            return Ok(Some(obj));
-                        }
-                        None => {
-                            // swallow unsupported object
-                            loop {
-                                match iter.next() {
-                                    Some(Ok(pair @ CodePair { code: 0, .. })) => {
-                                        // found another object or ENDSEC
-                                        iter.put_back(Ok(pair));
-                                        break;
-                                    }
-                                    Some(Ok(_)) => (), // part of the unsupported object
-                                    Some(Err(e)) => return Err(e),
-                                    None => return Err(DxfError::UnexpectedEndOfInput),
-                                }
+                // unsupported objects keep their raw code pairs so they can be written back
+                let specific = ObjectType::from_type_string(&type_string).unwrap_or_else(|| {
+                    ObjectType::Unknown {
+                        type_string,
+                        pairs: vec![],
+                    }
+                });
+                let mut obj = Object::new(specific);
+                if !obj.apply_custom_reader(iter)? {
+                    // no custom reader, use the auto-generated one
+                    loop {
+                        match iter.next() {
+                            Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                                // new object or ENDSEC
+                                iter.put_back(Ok(pair));
+                                break;
                             }
+                            Some(Ok(pair)) => obj.apply_code_pair(&pair, iter)?,
+                            Some(Err(e)) => return Err(e),
+                            None => return Err(DxfError::UnexpectedEndOfInput),
                         }
                     }
+
+                    obj.post_parse()?;
                 }
-                Some(Ok(pair)) => {
-                    return Err(DxfError::UnexpectedCodePair(
-                        pair,
-                        String::from("expected 0/object-type or 0/ENDSEC"),
-                    ))
-                }
-                Some(Err(e)) => return Err(e),
-                None => return Err(DxfError::UnexpectedEndOfInput),
+
+                Ok(Some(obj))
             }
+            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                pair,
+                String::from("expected 0/object-type or 0/ENDSEC"),
+            )),
+            Some(Err(e)) => Err(e),
+            None => Err(DxfError::UnexpectedEndOfInput),
         }
     }
     fn apply_code_pair<I>(
@@ -326,6 +311,9 @@ impl Object {
             ObjectType::XRecordObject(ref mut xr) => {
                 Object::apply_custom_reader_xrecordobject(&mut self.common, xr, iter)
             }
+            ObjectType::Unknown { ref mut pairs, .. } => {
+                Object::apply_custom_reader_unknown(&mut self.common, pairs, iter)
+            }
             _ => Ok(false), // no custom reader
         }
     }
@@ -1262,24 +1250,8 @@ impl Object {
                     } else {
                         matrix_list.push(pair.assert_f64()?);
                         if matrix_list.len() == 12 {
-                            let matrix = TransformationMatrix::from_vec(&[
-                                matrix_list[0],
-                                matrix_list[1],
-                                matrix_list[2],
-                                0.0,
-                                matrix_list[3],
-                                matrix_list[4],
-                                matrix_list[5],
-                                0.0,
-                                matrix_list[6],
-                                matrix_list[7],
-                                matrix_list[8],
-                                0.0,
-                                matrix_list[9],
-                                matrix_list[10],
-                                matrix_list[11],
-                                0.0,
-                            ]);
+                            let matrix =
+                                TransformationMatrix::from_4x3_values_row_major(&matrix_list);
                             matrix_list.clear();
                             if !set_inverse_matrix {
                                 sf.inverse_transformation_matrix = matrix;
@@ -1526,6 +1498,28 @@ impl Object {
             }
         }
     }
+    fn apply_custom_reader_unknown<I>(
+        common: &mut ObjectCommon,
+        pairs: &mut Vec<CodePair>,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        // the common values come first; everything from the first object-specific pair on is kept
+        let mut in_common_section = true;
+        loop {
+            let pair = next_pair!(iter);
+            if in_common_section {
+                in_common_section = matches!(pair.code, 5 | 102 | 330);
+            }
+            if in_common_section || pair.code == x_data::XDATA_APPLICATIONNAME {
+                common.apply_individual_pair(&pair, iter)?;
+            } else {
+                pairs.push(pair);
+            }
+        }
+    }
     pub(crate) fn write<T>(
         &self,
         version: AcadVersion,
@@ -1735,6 +1729,11 @@ impl Object {
                     writer.write_code_pair(&pair)?;
                 }
             }
+            ObjectType::Unknown { ref pairs, .. } => {
+                for pair in pairs {
+                    writer.write_code_pair(pair)?;
+                }
+            }
             _ => return Ok(false), // no custom writer
         }
 
@@ -1800,7 +1799,164 @@ mod tests {
             .join("\r\n")
             .as_str(),
         );
-        assert_eq!(0, drawing.objects().count());
+        let objects = drawing.objects().collect::<Vec<_>>();
+        assert_eq!(1, objects.len());
+        match objects[0].specific {
+            ObjectType::Unknown {
+                ref type_string,
+                ref pairs,
+            } => {
+                assert_eq!("UNSUPPORTED_OBJECT", type_string);
+                assert_eq!(vec![CodePair::new_str(1, "unsupported string")], *pairs);
+            }
+            _ => panic!("expected an unknown object"),
+        }
+    }
+
+    #[test]
+    fn read_unsupported_object_with_common_values() {
+        let obj = read_object(
+            "SCALE",
+            vec![
+                "  5",
+                "2A",
+                "102",
+                "{ACAD_REACTORS",
+                "330",
+                "1F",
+                "102",
+                "}",
+                "330",
+                "1F",
+                "100",
+                "AcDbScale",
+                " 70",
+                "     0",
+                "300",
+                "1:1",
+                "330",
+                "A0",
+            ]
+            .join("\r\n"),
+        );
+        assert_eq!(Handle(0x2A), obj.common.handle);
+        assert_eq!(Handle(0x1F), obj.common.__owner_handle);
+        assert_eq!(1, obj.common.extension_data_groups.len());
+        match obj.specific {
+            ObjectType::Unknown {
+                ref type_string,
+                ref pairs,
+            } => {
+                assert_eq!("SCALE", type_string);
+                assert_eq!(
+                    vec![
+                        CodePair::new_str(100, "AcDbScale"),
+                        CodePair::new_i16(70, 0),
+                        CodePair::new_str(300, "1:1"),
+                        CodePair::new_str(330, "A0"),
+                    ],
+                    *pairs
+                );
+            }
+            _ => panic!("expected an unknown object"),
+        }
+    }
+
+    #[test]
+    fn get_unsupported_object_by_handle() {
+        let drawing = from_section(
+            "OBJECTS",
+            ["  0", "SCALE", "  5", "2A", "100", "AcDbScale"]
+                .join("\r\n")
+                .as_str(),
+        );
+        match drawing.get_item_by_handle(Handle(0x2A)) {
+            Some(DrawingItem::Object(obj)) => match obj.specific {
+                ObjectType::Unknown {
+                    ref type_string, ..
+                } => assert_eq!("SCALE", type_string),
+                _ => panic!("expected an unknown object"),
+            },
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn round_trip_spatial_filter_matrices() {
+        let matrix = TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0))
+            * TransformationMatrix::rotation(&Vector::z_axis(), 0.5);
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_object(Object::new(ObjectType::SpatialFilter(SpatialFilter {
+            transformation_matrix: matrix,
+            inverse_transformation_matrix: matrix.inverse().unwrap(),
+            ..Default::default()
+        })));
+        let mut buf = vec![];
+        drawing.save(&mut buf).unwrap();
+        let drawing = Drawing::load(&mut buf.as_slice()).unwrap();
+        let sf = drawing
+            .objects()
+            .find_map(|o| match o.specific {
+                ObjectType::SpatialFilter(ref sf) => Some(sf),
+                _ => None,
+            })
+            .unwrap();
+        let p = Point::new(4.0, 5.0, 6.0);
+        assert!(matrix
+            .transform_point(&p)
+            .is_close_to(&sf.transformation_matrix.transform_point(&p), 1e-9));
+        assert!(p.is_close_to(
+            &sf.inverse_transformation_matrix
+                .transform_point(&sf.transformation_matrix.transform_point(&p)),
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn round_trip_unsupported_object() {
+        let drawing = from_section(
+            "OBJECTS",
+            [
+                "  0",
+                "SCALE",
+                "  5",
+                "2A",
+                "100",
+                "AcDbScale",
+                " 70",
+                "     0",
+                "300",
+                "1:1",
+                "140",
+                "1.0",
+                "290",
+                "     1",
+            ]
+            .join("\r\n")
+            .as_str(),
+        );
+        assert_contains(
+            &drawing,
+            [
+                "  0",
+                "SCALE",
+                "  5",
+                "2A",
+                "100",
+                "AcDbScale",
+                " 70",
+                "     0",
+                "300",
+                "1:1",
+                "140",
+                "1.0",
+                "290",
+                "1",
+                "  0",
+            ]
+            .join("\r\n"),
+        );
     }
 
     #[test]
@@ -1828,12 +1984,20 @@ mod tests {
             .as_str(),
         );
         let objects = drawing.objects().collect::<Vec<_>>();
-        assert_eq!(2, objects.len());
+        assert_eq!(3, objects.len());
         match objects[0].specific {
             ObjectType::DictionaryVariable(_) => (),
             _ => panic!("expected a dictionary variable"),
         }
         match objects[1].specific {
+            ObjectType::Unknown {
+                ref type_string, ..
+            } => {
+                assert_eq!("UNSUPPORTED_OBJECT", type_string)
+            }
+            _ => panic!("expected an unknown object"),
+        }
+        match objects[2].specific {
             ObjectType::ImageDefinition(_) => (),
             _ => panic!("expected an image definition"),
         }
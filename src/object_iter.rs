@@ -2,18 +2,39 @@ use std::io::Read;
 
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::objects::Object;
+use crate::DxfError;
 
 pub(crate) struct ObjectIter<'a, I: 'a + Read> {
     pub iter: &'a mut CodePairPutBack<I>,
+    /// The error that stopped the iteration, if any.
+    pub error: Option<DxfError>,
+}
+
+impl<'a, I: 'a + Read> ObjectIter<'a, I> {
+    pub(crate) fn new(iter: &'a mut CodePairPutBack<I>) -> Self {
+        ObjectIter { iter, error: None }
+    }
 }
 
 impl<'a, I: 'a + Read> Iterator for ObjectIter<'a, I> {
     type Item = Object;
 
     fn next(&mut self) -> Option<Object> {
-        match Object::read(self.iter) {
-            Ok(Some(o)) => Some(o),
-            Ok(None) | Err(_) => None,
+        loop {
+            match Object::read(self.iter) {
+                Ok(Some(o)) => return Some(o),
+                Ok(None) => return None,
+                Err(e) => {
+                    // in recovery mode the broken object is dropped and reading resumes at the next one
+                    if let Err(e) = self.iter.recover(e) {
+                        self.error = Some(e);
+                        return None;
+                    }
+                    if !self.iter.skip_until(|p| p.code == 0) {
+                        return None;
+                    }
+                }
+            }
         }
     }
 }
@@ -1,4 +1,6 @@
-use crate::{CodePair, DxfError, DxfResult};
+use std::ops::Neg;
+
+use crate::{CodePair, DxfError, DxfResult, TransformationMatrix, Vector};
 
 /// Represents a simple point in Cartesian space.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -21,6 +23,46 @@ impl Point {
     pub fn origin() -> Point {
         Point::new(0.0, 0.0, 0.0)
     }
+    /// Returns the vector from the origin to this point.
+    pub fn to_vector(&self) -> Vector {
+        Vector::new(self.x, self.y, self.z)
+    }
+    /// Returns the distance between this point and another.
+    pub fn distance_to(&self, other: &Point) -> f64 {
+        (other - self).length()
+    }
+    /// Returns the point at parameter `t` along the line from this point to another, where `0.0`
+    /// is this point and `1.0` is the other.
+    pub fn lerp(&self, other: &Point, t: f64) -> Point {
+        self + (other - self) * t
+    }
+    /// Returns `true` if every coordinate is within `tolerance` of the other point's.
+    pub fn is_close_to(&self, other: &Point, tolerance: f64) -> bool {
+        (self.x - other.x).abs() <= tolerance
+            && (self.y - other.y).abs() <= tolerance
+            && (self.z - other.z).abs() <= tolerance
+    }
+    /// Converts the point from the object coordinate system (OCS) defined by the specified
+    /// extrusion direction to the world coordinate system (WCS).
+    pub fn ocs_to_wcs(&self, normal: &Vector) -> Point {
+        if *normal == Vector::z_axis() {
+            return self.clone();
+        }
+
+        TransformationMatrix::from_ocs_normal(normal).transform_point(self)
+    }
+    /// Converts the point from the world coordinate system (WCS) to the object coordinate system
+    /// (OCS) defined by the specified extrusion direction.
+    pub fn wcs_to_ocs(&self, normal: &Vector) -> Point {
+        if *normal == Vector::z_axis() {
+            return self.clone();
+        }
+
+        // the OCS axes are orthonormal so the transpose is the inverse
+        TransformationMatrix::from_ocs_normal(normal)
+            .transpose()
+            .transform_point(self)
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.assert_f64()?,
@@ -37,3 +79,88 @@ impl Point {
         Ok(())
     }
 }
+
+impl_binary_op!(Add, add, Point, Vector, Point, |p, v| Point::new(
+    p.x + v.x,
+    p.y + v.y,
+    p.z + v.z
+));
+impl_binary_op!(Sub, sub, Point, Vector, Point, |p, v| Point::new(
+    p.x - v.x,
+    p.y - v.y,
+    p.z - v.z
+));
+impl_binary_op!(Sub, sub, Point, Point, Vector, |a, b| Vector::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+impl_binary_op!(Mul, mul, Point, f64, Point, |p, s| Point::new(
+    p.x * s,
+    p.y * s,
+    p.z * s
+));
+
+impl Neg for Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Neg for &Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl From<Vector> for Point {
+    fn from(v: Vector) -> Point {
+        Point::new(v.x, v.y, v.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn point_vector_arithmetic() {
+        let p = Point::new(1.0, 2.0, 3.0);
+        let v = Vector::new(1.0, 1.0, 1.0);
+        assert_eq!(Point::new(2.0, 3.0, 4.0), &p + &v);
+        assert_eq!(Point::new(0.0, 1.0, 2.0), &p - &v);
+        assert_eq!(Vector::new(0.0, 1.0, 2.0), &p - Point::new(1.0, 1.0, 1.0));
+        assert_eq!(Point::new(2.0, 4.0, 6.0), &p * 2.0);
+        assert_eq!(Point::new(-1.0, -2.0, -3.0), -p);
+    }
+
+    #[test]
+    fn distance_and_lerp() {
+        let a = Point::new(1.0, 1.0, 0.0);
+        let b = Point::new(4.0, 5.0, 0.0);
+        assert_eq!(5.0, a.distance_to(&b));
+        assert_eq!(Point::new(2.5, 3.0, 0.0), a.lerp(&b, 0.5));
+        assert_eq!(b, a.lerp(&b, 1.0));
+    }
+
+    #[test]
+    fn ocs_round_trip() {
+        let normal = Vector::new(1.0, 2.0, 3.0);
+        let p = Point::new(4.0, 5.0, 6.0);
+        assert!(p.is_close_to(&p.ocs_to_wcs(&normal).wcs_to_ocs(&normal), 1e-12));
+        assert_eq!(p, p.ocs_to_wcs(&Vector::z_axis()));
+        assert_eq!(
+            Point::new(-4.0, 5.0, -6.0),
+            p.ocs_to_wcs(&Vector::new(0.0, 0.0, -1.0))
+        );
+    }
+
+    #[test]
+    fn is_close_to() {
+        let p = Point::new(1.0, 2.0, 3.0);
+        assert!(p.is_close_to(&Point::new(1.0005, 2.0, 2.9995), 1e-3));
+        assert!(!p.is_close_to(&Point::new(1.01, 2.0, 3.0), 1e-3));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::helper_functions::*;
+use crate::Point;
+
+/// A single segment of a `LwPolyline` or `Polyline`.  Points of 2D polylines are in OCS.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum PolylineSegment {
+    Line {
+        start: Point,
+        end: Point,
+    },
+    /// Angles are in degrees; `included_angle` is negative for clockwise arcs.
+    Arc {
+        start: Point,
+        end: Point,
+        center: Point,
+        radius: f64,
+        start_angle: f64,
+        included_angle: f64,
+    },
+}
+
+// public implementation
+impl PolylineSegment {
+    /// Creates the segment between two vertices where `bulge` is the tangent of a quarter of the
+    /// included angle.  A positive bulge is counter-clockwise.
+    pub fn from_bulge(start: Point, end: Point, bulge: f64) -> Self {
+        match bulge_to_arc(&start, &end, bulge) {
+            Some((center, radius, start_angle, included_angle)) => PolylineSegment::Arc {
+                start,
+                end,
+                center,
+                radius,
+                start_angle: start_angle.to_degrees(),
+                included_angle: included_angle.to_degrees(),
+            },
+            None => PolylineSegment::Line { start, end },
+        }
+    }
+    pub fn start(&self) -> &Point {
+        match self {
+            PolylineSegment::Line { start, .. } => start,
+            PolylineSegment::Arc { start, .. } => start,
+        }
+    }
+    pub fn end(&self) -> &Point {
+        match self {
+            PolylineSegment::Line { end, .. } => end,
+            PolylineSegment::Arc { end, .. } => end,
+        }
+    }
+    /// The bulge of the segment; `0.0` for lines.
+    pub fn bulge(&self) -> f64 {
+        match self {
+            PolylineSegment::Line { .. } => 0.0,
+            PolylineSegment::Arc { included_angle, .. } => {
+                (included_angle.to_radians() / 4.0).tan()
+            }
+        }
+    }
+    pub fn length(&self) -> f64 {
+        match self {
+            PolylineSegment::Line { start, end } => start.distance_to(end),
+            PolylineSegment::Arc {
+                radius,
+                included_angle,
+                ..
+            } => radius * included_angle.to_radians().abs(),
+        }
+    }
+    /// The point the specified distance along the segment, which is clamped to the segment.
+    pub fn point_at_distance(&self, distance: f64) -> Point {
+        let length = self.length();
+        let fraction = if length == 0.0 {
+            0.0
+        } else {
+            (distance / length).clamp(0.0, 1.0)
+        };
+        match self {
+            PolylineSegment::Line { start, end } => start.lerp(end, fraction),
+            PolylineSegment::Arc {
+                start,
+                end,
+                center,
+                radius,
+                start_angle,
+                included_angle,
+            } => {
+                let angle = (start_angle + included_angle * fraction).to_radians();
+                let z = start.z + (end.z - start.z) * fraction;
+                Point::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                    z,
+                )
+            }
+        }
+    }
+}
+
+// internal visibility only
+
+/// Creates the segments between consecutive `(location, bulge)` vertices, including the segment
+/// from the last vertex to the first if the polyline is closed.
+pub(crate) fn segments_from_vertices(
+    vertices: Vec<(Point, f64)>,
+    is_closed: bool,
+) -> Vec<PolylineSegment> {
+    let count = if is_closed && vertices.len() > 1 {
+        vertices.len()
+    } else {
+        vertices.len().saturating_sub(1)
+    };
+    (0..count)
+        .map(|i| {
+            let (start, bulge) = &vertices[i];
+            let (end, _) = &vertices[(i + 1) % vertices.len()];
+            PolylineSegment::from_bulge(start.clone(), end.clone(), *bulge)
+        })
+        .collect()
+}
+
+/// The signed area enclosed by the segments, which are closed with a straight line if the last
+/// segment doesn't end at the start of the first.  Counter-clockwise areas are positive.
+pub(crate) fn segments_area(segments: &[PolylineSegment]) -> f64 {
+    let mut area = 0.0;
+    for (i, segment) in segments.iter().enumerate() {
+        let (start, end) = (segment.start(), segment.end());
+        area += start.x * end.y - end.x * start.y;
+        if let PolylineSegment::Arc {
+            radius,
+            included_angle,
+            ..
+        } = segment
+        {
+            // the circular segment between the chord and the arc
+            let angle = included_angle.to_radians();
+            area += radius * radius * (angle - angle.sin());
+        }
+        if i == segments.len() - 1 {
+            let first = segments[0].start();
+            area += end.x * first.y - first.x * end.y;
+        }
+    }
+
+    area / 2.0
+}
+
+/// The point the specified distance along the segments, or `None` if the distance is negative or
+/// exceeds their length.
+pub(crate) fn segments_point_at_distance(
+    segments: &[PolylineSegment],
+    distance: f64,
+) -> Option<Point> {
+    if distance < 0.0 {
+        return None;
+    }
+
+    let mut remaining = distance;
+    for segment in segments {
+        let length = segment.length();
+        if remaining <= length {
+            return Some(segment.point_at_distance(remaining));
+        }
+        remaining -= length;
+    }
+
+    // allow for rounding at the very end
+    match segments.last() {
+        Some(segment) if remaining <= 1.0e-9 * distance.max(1.0) => Some(segment.end().clone()),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the point is inside the region enclosed by the segments, closed as in
+/// `segments_area`.  Only X and Y are considered.
+pub(crate) fn segments_contain_point(segments: &[PolylineSegment], p: &Point) -> bool {
+    // the region is the polygon of the chords with the circular segments between each arc and its
+    // chord added or removed, so the parities of the point being in each combine
+    let mut inside = false;
+    let mut chord = |start: &Point, end: &Point| {
+        if (start.y > p.y) != (end.y > p.y) {
+            let x = start.x + (p.y - start.y) * (end.x - start.x) / (end.y - start.y);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+    };
+    for segment in segments {
+        chord(segment.start(), segment.end());
+    }
+    if let (Some(first), Some(last)) = (segments.first(), segments.last()) {
+        chord(last.end(), first.start());
+    }
+
+    for segment in segments {
+        if let PolylineSegment::Arc {
+            start,
+            end,
+            center,
+            radius,
+            start_angle,
+            included_angle,
+        } = segment
+        {
+            let distance = ((p.x - center.x).powi(2) + (p.y - center.y).powi(2)).sqrt();
+            if distance >= *radius {
+                continue;
+            }
+
+            // the circular segment is the part of the disc on the same side of the chord as the
+            // middle of the arc
+            let middle = (start_angle + included_angle / 2.0).to_radians();
+            let middle = Point::new(
+                center.x + radius * middle.cos(),
+                center.y + radius * middle.sin(),
+                0.0,
+            );
+            let side = |q: &Point| {
+                (end.x - start.x) * (q.y - start.y) - (end.y - start.y) * (q.x - start.x)
+            };
+            if side(p) * side(&middle) > 0.0 {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::polyline_segment::*;
+
+    #[test]
+    fn line_segment_from_zero_bulge() {
+        let segment = PolylineSegment::from_bulge(Point::origin(), Point::new(3.0, 4.0, 0.0), 0.0);
+        assert_eq!(
+            PolylineSegment::Line {
+                start: Point::origin(),
+                end: Point::new(3.0, 4.0, 0.0),
+            },
+            segment
+        );
+        assert_eq!(5.0, segment.length());
+        assert_eq!(Point::new(1.5, 2.0, 0.0), segment.point_at_distance(2.5));
+    }
+
+    #[test]
+    fn arc_segment_from_bulge() {
+        // a bulge of -1 is a clockwise half circle, which passes above the chord
+        let segment = PolylineSegment::from_bulge(Point::origin(), Point::new(2.0, 0.0, 0.0), -1.0);
+        match segment {
+            PolylineSegment::Arc {
+                ref center,
+                radius,
+                start_angle,
+                included_angle,
+                ..
+            } => {
+                assert!(center.is_close_to(&Point::new(1.0, 0.0, 0.0), 1e-12));
+                assert!((1.0 - radius).abs() < 1e-12);
+                assert!((180.0 - start_angle).abs() < 1e-12);
+                assert!((-180.0 - included_angle).abs() < 1e-12);
+            }
+            _ => panic!("expected an arc"),
+        }
+        assert!((PI - segment.length()).abs() < 1e-12);
+        assert!((-1.0 - segment.bulge()).abs() < 1e-12);
+        assert!(segment
+            .point_at_distance(PI / 2.0)
+            .is_close_to(&Point::new(1.0, 1.0, 0.0), 1e-12));
+    }
+}
@@ -0,0 +1,32 @@
+/// Options controlling what `Drawing::purge` removes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct PurgeOptions {
+    /// When `true`, unreferenced `Layer`s are removed.
+    pub layers: bool,
+    /// When `true`, unreferenced `LineType`s are removed.
+    pub line_types: bool,
+    /// When `true`, unreferenced text `Style`s are removed.
+    pub text_styles: bool,
+    /// When `true`, unreferenced `DimStyle`s are removed.
+    pub dim_styles: bool,
+    /// When `true`, unreferenced `Block`s and their `BlockRecord`s are removed.
+    pub blocks: bool,
+    /// When `true`, unreferenced `MLineStyle` objects are removed.
+    pub mline_styles: bool,
+}
+
+impl Default for PurgeOptions {
+    /// Purges everything.
+    fn default() -> Self {
+        PurgeOptions {
+            layers: true,
+            line_types: true,
+            text_styles: true,
+            dim_styles: true,
+            blocks: true,
+            mline_styles: true,
+        }
+    }
+}
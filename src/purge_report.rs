@@ -0,0 +1,33 @@
+/// The result of `Drawing::purge`, listing the names of the removed items.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct PurgeReport {
+    /// The removed `Layer`s.
+    pub layers: Vec<String>,
+    /// The removed `LineType`s.
+    pub line_types: Vec<String>,
+    /// The removed text `Style`s.
+    pub text_styles: Vec<String>,
+    /// The removed `DimStyle`s.
+    pub dim_styles: Vec<String>,
+    /// The removed blocks.  Their `BlockRecord`s were removed as well.
+    pub blocks: Vec<String>,
+    /// The removed `MLineStyle` objects.
+    pub mline_styles: Vec<String>,
+}
+
+impl PurgeReport {
+    /// Returns `true` if nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+    /// Returns the number of removed items.
+    pub fn count(&self) -> usize {
+        self.layers.len()
+            + self.line_types.len()
+            + self.text_styles.len()
+            + self.dim_styles.len()
+            + self.blocks.len()
+            + self.mline_styles.len()
+    }
+}
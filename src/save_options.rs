@@ -0,0 +1,11 @@
+/// Options controlling how a `Drawing` is saved.  Start from `SaveOptions::default()` and set the
+/// options that are needed; more options may be added later.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SaveOptions {
+    /// When `true`, content that isn't supported by the drawing's `$ACADVER` is converted as
+    /// described by `Drawing::convert_to` instead of being dropped, and files older than R13 are
+    /// written without the sections, tables, and code pairs that were introduced in R13.  The
+    /// drawing itself isn't changed.
+    pub downgrade: bool,
+}
@@ -0,0 +1,441 @@
+use enum_primitive::FromPrimitive;
+use std::io::{Read, Write};
+
+use crate::{CodePair, Color, DxfResult, Handle, Point};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::code_pair_writer::CodePairWriter;
+use crate::enums::*;
+use crate::helper_functions::*;
+
+/// A single cell of a `Table` entity.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCell {
+    pub cell_type: TableCellType,
+    pub flags: i16,
+    pub is_merged: bool,
+    pub is_auto_fit: bool,
+    /// The number of columns spanned by a merged cell.
+    pub merged_column_count: i32,
+    /// The number of rows spanned by a merged cell.
+    pub merged_row_count: i32,
+    pub override_flags: i32,
+    pub virtual_edge_flags: i16,
+    pub rotation: f64,
+    /// The `Field` object providing the cell's value, if any.
+    pub field_handle: Handle,
+    pub text: String,
+    pub text_style_name: String,
+    pub block_record_handle: Handle,
+    pub block_scale: f64,
+    pub block_attributes: Vec<TableCellBlockAttribute>,
+    pub overrides: TableCellOverrides,
+    /// The typed value from the cell's `CELL_VALUE` group, if any.
+    pub value: Option<TableCellValue>,
+    #[doc(hidden)]
+    pub __unsupported_pairs: Vec<CodePair>,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        TableCell {
+            cell_type: TableCellType::Text,
+            flags: 0,
+            is_merged: false,
+            is_auto_fit: false,
+            merged_column_count: 1,
+            merged_row_count: 1,
+            override_flags: 0,
+            virtual_edge_flags: 0,
+            rotation: 0.0,
+            field_handle: Handle::empty(),
+            text: String::new(),
+            text_style_name: String::new(),
+            block_record_handle: Handle::empty(),
+            block_scale: 1.0,
+            block_attributes: vec![],
+            overrides: Default::default(),
+            value: None,
+            __unsupported_pairs: vec![],
+        }
+    }
+}
+
+/// The value of a single attribute of a block `TableCell`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellBlockAttribute {
+    pub attribute_definition_handle: Handle,
+    pub text: String,
+}
+
+impl Default for TableCellBlockAttribute {
+    fn default() -> Self {
+        TableCellBlockAttribute {
+            attribute_definition_handle: Handle::empty(),
+            text: String::new(),
+        }
+    }
+}
+
+/// The typed value of a `TableCell`, as stored in its `CELL_VALUE` group.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellValue {
+    pub flags: i32,
+    /// The kind of value, e.g., `1` for a long, `2` for a double, `4` for a string, or `8` for a
+    /// date.
+    pub data_type: i32,
+    pub data: TableCellValueData,
+    pub unit_type: i32,
+    pub format_string: String,
+    /// The value formatted for display.
+    pub value_string: String,
+    #[doc(hidden)]
+    pub __unsupported_pairs: Vec<CodePair>,
+}
+
+impl Default for TableCellValue {
+    fn default() -> Self {
+        TableCellValue {
+            flags: 0,
+            data_type: 0,
+            data: TableCellValueData::None,
+            unit_type: 0,
+            format_string: String::new(),
+            value_string: String::new(),
+            __unsupported_pairs: vec![],
+        }
+    }
+}
+
+/// The data of a `TableCellValue`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum TableCellValueData {
+    None,
+    Long(i32),
+    Double(f64),
+    String(String),
+    Point(Point),
+    /// Binary data, e.g., a date.
+    Binary(Vec<u8>),
+}
+
+/// The properties of a `TableCell` that override those of the table's `TableStyle`.  A value of
+/// `None` indicates the style's value is used.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellOverrides {
+    pub alignment: Option<i16>,
+    pub fill_color: Option<Color>,
+    pub content_color: Option<Color>,
+    pub text_height: Option<f64>,
+    pub is_background_fill_enabled: Option<bool>,
+    pub top_border_color: Option<Color>,
+    pub right_border_color: Option<Color>,
+    pub bottom_border_color: Option<Color>,
+    pub left_border_color: Option<Color>,
+    pub top_border_line_weight: Option<i16>,
+    pub right_border_line_weight: Option<i16>,
+    pub bottom_border_line_weight: Option<i16>,
+    pub left_border_line_weight: Option<i16>,
+    pub is_top_border_visible: Option<bool>,
+    pub is_right_border_visible: Option<bool>,
+    pub is_bottom_border_visible: Option<bool>,
+    pub is_left_border_visible: Option<bool>,
+}
+
+/// A rectangular range of `TableCell`s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellRange {
+    pub top_row: usize,
+    pub left_column: usize,
+    pub bottom_row: usize,
+    pub right_column: usize,
+}
+
+// internal visibility only
+impl TableCell {
+    /// Applies a single cell-level code pair.  Returns `false` if the pair isn't part of a cell.
+    pub(crate) fn apply_code_pair<I>(
+        &mut self,
+        pair: &CodePair,
+        iter: &mut CodePairPutBack<I>,
+    ) -> DxfResult<bool>
+    where
+        I: Read,
+    {
+        match pair.code {
+            171 => {
+                self.cell_type =
+                    enum_from_number!(TableCellType, Text, from_i16, pair.assert_i16()?);
+            }
+            172 => self.flags = pair.assert_i16()?,
+            173 => self.is_merged = as_bool(pair.assert_i16()?),
+            174 => self.is_auto_fit = as_bool(pair.assert_i16()?),
+            175 => self.merged_column_count = i32::from(pair.assert_i16()?),
+            176 => self.merged_row_count = i32::from(pair.assert_i16()?),
+            91 => self.override_flags = pair.assert_i32()?,
+            178 => self.virtual_edge_flags = pair.assert_i16()?,
+            145 => self.rotation = pair.assert_f64()?,
+            344 => self.field_handle = pair.as_handle()?,
+            // long text is split into chunks of code 2 followed by the remainder in code 1
+            2 | 1 => self.text.push_str(&pair.assert_string()?),
+            7 => self.text_style_name = pair.assert_string()?,
+            340 => self.block_record_handle = pair.as_handle()?,
+            144 => self.block_scale = pair.assert_f64()?,
+            179 => (), // block attribute count; we just read as many as we're given
+            331 => {
+                // start a new block attribute
+                self.block_attributes.push(TableCellBlockAttribute {
+                    attribute_definition_handle: pair.as_handle()?,
+                    ..Default::default()
+                });
+            }
+            300 => {
+                if let Some(attribute) = self.block_attributes.last_mut() {
+                    attribute.text = pair.assert_string()?;
+                }
+            }
+            301 => self.read_cell_value(iter)?,
+            170 => self.overrides.alignment = Some(pair.assert_i16()?),
+            63 => self.overrides.fill_color = Some(Color::from_raw_value(pair.assert_i16()?)),
+            64 => self.overrides.content_color = Some(Color::from_raw_value(pair.assert_i16()?)),
+            140 => self.overrides.text_height = Some(pair.assert_f64()?),
+            283 => self.overrides.is_background_fill_enabled = Some(as_bool(pair.assert_i16()?)),
+            69 => self.overrides.top_border_color = Some(Color::from_raw_value(pair.assert_i16()?)),
+            65 => {
+                self.overrides.right_border_color = Some(Color::from_raw_value(pair.assert_i16()?))
+            }
+            66 => {
+                self.overrides.bottom_border_color = Some(Color::from_raw_value(pair.assert_i16()?))
+            }
+            68 => {
+                self.overrides.left_border_color = Some(Color::from_raw_value(pair.assert_i16()?))
+            }
+            279 => self.overrides.top_border_line_weight = Some(pair.assert_i16()?),
+            275 => self.overrides.right_border_line_weight = Some(pair.assert_i16()?),
+            276 => self.overrides.bottom_border_line_weight = Some(pair.assert_i16()?),
+            278 => self.overrides.left_border_line_weight = Some(pair.assert_i16()?),
+            289 => self.overrides.is_top_border_visible = Some(as_bool(pair.assert_i16()?)),
+            285 => self.overrides.is_right_border_visible = Some(as_bool(pair.assert_i16()?)),
+            286 => self.overrides.is_bottom_border_visible = Some(as_bool(pair.assert_i16()?)),
+            288 => self.overrides.is_left_border_visible = Some(as_bool(pair.assert_i16()?)),
+            // the cell override and extended cell flags aren't modeled but are kept
+            177 | 92 => self.__unsupported_pairs.push(pair.clone()),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+    pub(crate) fn write<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_i16(171, self.cell_type as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(172, self.flags))?;
+        writer.write_code_pair(&CodePair::new_i16(173, as_i16(self.is_merged)))?;
+        writer.write_code_pair(&CodePair::new_i16(174, as_i16(self.is_auto_fit)))?;
+        writer.write_code_pair(&CodePair::new_i16(175, self.merged_column_count as i16))?;
+        writer.write_code_pair(&CodePair::new_i16(176, self.merged_row_count as i16))?;
+        writer.write_code_pair(&CodePair::new_i32(91, self.override_flags))?;
+        writer.write_code_pair(&CodePair::new_i16(178, self.virtual_edge_flags))?;
+        writer.write_code_pair(&CodePair::new_f64(145, self.rotation))?;
+        self.write_overrides(writer)?;
+        if !self.field_handle.is_empty() {
+            writer.write_code_pair(&CodePair::new_string(344, &self.field_handle.as_string()))?;
+        }
+        match self.cell_type {
+            TableCellType::Text => {
+                write_chunked_string(writer, &self.text)?;
+                writer.write_code_pair(&CodePair::new_string(7, &self.text_style_name))?;
+            }
+            TableCellType::Block => {
+                writer.write_code_pair(&CodePair::new_string(
+                    340,
+                    &self.block_record_handle.as_string(),
+                ))?;
+                writer.write_code_pair(&CodePair::new_f64(144, self.block_scale))?;
+                writer
+                    .write_code_pair(&CodePair::new_i16(179, self.block_attributes.len() as i16))?;
+                for attribute in &self.block_attributes {
+                    writer.write_code_pair(&CodePair::new_string(
+                        331,
+                        &attribute.attribute_definition_handle.as_string(),
+                    ))?;
+                    writer.write_code_pair(&CodePair::new_string(300, &attribute.text))?;
+                }
+            }
+        }
+        if let Some(ref value) = self.value {
+            TableCell::write_cell_value(value, writer)?;
+        }
+        for pair in &self.__unsupported_pairs {
+            writer.write_code_pair(pair)?;
+        }
+        Ok(())
+    }
+}
+
+// private implementation
+impl TableCell {
+    fn read_cell_value<I>(&mut self, iter: &mut CodePairPutBack<I>) -> DxfResult<()>
+    where
+        I: Read,
+    {
+        // newer files store a typed value in a `CELL_VALUE` group
+        let mut value = TableCellValue::default();
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                    iter.put_back(Ok(pair));
+                    break;
+                }
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => break,
+            };
+            match pair.code {
+                304 => break, // value should be "ACVALUE_END"
+                93 => value.flags = pair.assert_i32()?,
+                90 => value.data_type = pair.assert_i32()?,
+                91 => value.data = TableCellValueData::Long(pair.assert_i32()?),
+                140 => value.data = TableCellValueData::Double(pair.assert_f64()?),
+                // long strings are split into chunks of code 2 followed by the remainder in code 1
+                2 | 1 => match value.data {
+                    TableCellValueData::String(ref mut s) => s.push_str(&pair.assert_string()?),
+                    _ => value.data = TableCellValueData::String(pair.assert_string()?),
+                },
+                11 => {
+                    value.data = TableCellValueData::Point(Point::new(pair.assert_f64()?, 0.0, 0.0))
+                }
+                21 => {
+                    if let TableCellValueData::Point(ref mut p) = value.data {
+                        p.y = pair.assert_f64()?;
+                    }
+                }
+                31 => {
+                    if let TableCellValueData::Point(ref mut p) = value.data {
+                        p.z = pair.assert_f64()?;
+                    }
+                }
+                92 => (), // binary data size; we just read as many bytes as we're given
+                310 => match value.data {
+                    TableCellValueData::Binary(ref mut data) => data.extend(pair.assert_binary()?),
+                    _ => value.data = TableCellValueData::Binary(pair.assert_binary()?),
+                },
+                94 => value.unit_type = pair.assert_i32()?,
+                300 => value.format_string = pair.assert_string()?,
+                302 => value.value_string = pair.assert_string()?,
+                _ => value.__unsupported_pairs.push(pair),
+            }
+        }
+
+        if self.text.is_empty() {
+            self.text = value.value_string.clone();
+        }
+        self.value = Some(value);
+        Ok(())
+    }
+    fn write_cell_value<T>(value: &TableCellValue, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        writer.write_code_pair(&CodePair::new_str(301, "CELL_VALUE"))?;
+        writer.write_code_pair(&CodePair::new_i32(93, value.flags))?;
+        writer.write_code_pair(&CodePair::new_i32(90, value.data_type))?;
+        match value.data {
+            TableCellValueData::None => (),
+            TableCellValueData::Long(l) => writer.write_code_pair(&CodePair::new_i32(91, l))?,
+            TableCellValueData::Double(d) => writer.write_code_pair(&CodePair::new_f64(140, d))?,
+            TableCellValueData::String(ref s) => write_chunked_string(writer, s)?,
+            TableCellValueData::Point(ref p) => {
+                writer.write_code_pair(&CodePair::new_f64(11, p.x))?;
+                writer.write_code_pair(&CodePair::new_f64(21, p.y))?;
+                writer.write_code_pair(&CodePair::new_f64(31, p.z))?;
+            }
+            TableCellValueData::Binary(ref data) => {
+                writer.write_code_pair(&CodePair::new_i32(92, data.len() as i32))?;
+                for chunk in data.chunks(127) {
+                    writer.write_code_pair(&CodePair::new_binary(310, chunk.to_vec()))?;
+                }
+            }
+        }
+        for pair in &value.__unsupported_pairs {
+            writer.write_code_pair(pair)?;
+        }
+        writer.write_code_pair(&CodePair::new_i32(94, value.unit_type))?;
+        writer.write_code_pair(&CodePair::new_string(300, &value.format_string))?;
+        writer.write_code_pair(&CodePair::new_string(302, &value.value_string))?;
+        writer.write_code_pair(&CodePair::new_str(304, "ACVALUE_END"))?;
+        Ok(())
+    }
+    fn write_overrides<T>(&self, writer: &mut CodePairWriter<T>) -> DxfResult<()>
+    where
+        T: Write + ?Sized,
+    {
+        let o = &self.overrides;
+        if let Some(alignment) = o.alignment {
+            writer.write_code_pair(&CodePair::new_i16(170, alignment))?;
+        }
+        let colors = [
+            (63, &o.fill_color),
+            (64, &o.content_color),
+            (69, &o.top_border_color),
+            (65, &o.right_border_color),
+            (66, &o.bottom_border_color),
+            (68, &o.left_border_color),
+        ];
+        for &(code, color) in &colors {
+            if let Some(color) = color {
+                writer.write_code_pair(&CodePair::new_i16(code, color.get_raw_value()))?;
+            }
+        }
+        if let Some(text_height) = o.text_height {
+            writer.write_code_pair(&CodePair::new_f64(140, text_height))?;
+        }
+        if let Some(is_enabled) = o.is_background_fill_enabled {
+            writer.write_code_pair(&CodePair::new_i16(283, as_i16(is_enabled)))?;
+        }
+        let line_weights = [
+            (279, o.top_border_line_weight),
+            (275, o.right_border_line_weight),
+            (276, o.bottom_border_line_weight),
+            (278, o.left_border_line_weight),
+        ];
+        for &(code, line_weight) in &line_weights {
+            if let Some(line_weight) = line_weight {
+                writer.write_code_pair(&CodePair::new_i16(code, line_weight))?;
+            }
+        }
+        let visibilities = [
+            (289, o.is_top_border_visible),
+            (285, o.is_right_border_visible),
+            (286, o.is_bottom_border_visible),
+            (288, o.is_left_border_visible),
+        ];
+        for &(code, is_visible) in &visibilities {
+            if let Some(is_visible) = is_visible {
+                writer.write_code_pair(&CodePair::new_i16(code, as_i16(is_visible)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes long text as chunks of code 2 followed by the remainder in code 1.
+fn write_chunked_string<T>(writer: &mut CodePairWriter<T>, s: &str) -> DxfResult<()>
+where
+    T: Write + ?Sized,
+{
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut chunks = chars.chunks(250).collect::<Vec<_>>();
+    let last = chunks.pop().unwrap_or(&[]);
+    for chunk in chunks {
+        writer.write_code_pair(&CodePair::new_string(2, &chunk.iter().collect::<String>()))?;
+    }
+    writer.write_code_pair(&CodePair::new_string(1, &last.iter().collect::<String>()))?;
+    Ok(())
+}
@@ -0,0 +1,647 @@
+use std::f64::consts::PI;
+
+use crate::entities::*;
+use crate::enums::LeaderPathType;
+use crate::helper_functions::*;
+use crate::{Point, TransformationMatrix, Vector};
+
+/// The smallest chord tolerance honored; smaller values are clamped to this.
+const MIN_TOLERANCE: f64 = 1.0e-9;
+
+/// The maximum number of segments a single arc is split into.
+const MAX_ARC_SEGMENTS: usize = 65536;
+
+/// The maximum number of times a spline knot span is halved.
+const MAX_SPLINE_DEPTH: usize = 16;
+
+// public implementation
+impl Entity {
+    /// Approximates the entity's geometry as a list of polylines in world coordinates.  Curves
+    /// are split so no point of the curve is further than `tolerance` from its chords, and
+    /// closed curves end with their first point.  Entities without curve geometry, e.g., text,
+    /// hatches, and block references, produce no polylines.
+    pub fn to_polylines(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        let tolerance = tolerance.max(MIN_TOLERANCE);
+        match self.specific {
+            EntityType::Arc(ref arc) => {
+                let (start, end) =
+                    normalized_sweep(arc.start_angle.to_radians(), arc.end_angle.to_radians());
+                let (u, v) = ocs_axes(&arc.normal, arc.radius);
+                let ocs = TransformationMatrix::from_ocs_normal(&arc.normal);
+                vec![elliptical_arc(
+                    &ocs.transform_point(&arc.center),
+                    &u,
+                    &v,
+                    start,
+                    end,
+                    tolerance,
+                )]
+            }
+            EntityType::Circle(ref circle) => {
+                let (u, v) = ocs_axes(&circle.normal, circle.radius);
+                let ocs = TransformationMatrix::from_ocs_normal(&circle.normal);
+                vec![elliptical_arc(
+                    &ocs.transform_point(&circle.center),
+                    &u,
+                    &v,
+                    0.0,
+                    2.0 * PI,
+                    tolerance,
+                )]
+            }
+            EntityType::Ellipse(ref ellipse) => {
+                let (start, end) = normalized_sweep(ellipse.start_parameter, ellipse.end_parameter);
+                let v = ellipse.normal.normalize().cross(&ellipse.major_axis)
+                    * ellipse.minor_axis_ratio;
+                vec![elliptical_arc(
+                    &ellipse.center,
+                    &ellipse.major_axis,
+                    &v,
+                    start,
+                    end,
+                    tolerance,
+                )]
+            }
+            EntityType::Line(ref line) => vec![vec![line.p1.clone(), line.p2.clone()]],
+            EntityType::LwPolyline(ref poly) => {
+                let vertices = poly
+                    .vertices
+                    .iter()
+                    .map(|v| (Point::new(v.x, v.y, self.common.elevation), v.bulge))
+                    .collect::<Vec<_>>();
+                vec![bulged_polyline(
+                    &vertices,
+                    poly.get_is_closed(),
+                    &poly.extrusion_direction,
+                    tolerance,
+                )]
+            }
+            EntityType::Polyline(ref poly) => polyline_polylines(poly, tolerance),
+            EntityType::Spline(ref spline) => {
+                if spline.has_valid_knots() {
+                    vec![spline_polyline(spline, tolerance)]
+                } else {
+                    // only fit points are present; recreate the curve through them
+                    let mut spline = spline.clone();
+                    if spline.compute_control_points() {
+                        vec![spline_polyline(&spline, tolerance)]
+                    } else {
+                        vec![]
+                    }
+                }
+            }
+            EntityType::Helix(ref helix) => vec![helix_polyline(helix, tolerance)],
+            EntityType::Leader(ref leader) => {
+                let spline = if leader.path_type == LeaderPathType::Spline {
+                    Spline::from_fit_points(&leader.vertices, 3, None, None)
+                } else {
+                    None
+                };
+                match spline {
+                    Some(spline) => vec![spline_polyline(&spline, tolerance)],
+                    None if leader.vertices.len() >= 2 => vec![leader.vertices.clone()],
+                    None => vec![],
+                }
+            }
+            EntityType::Face3D(ref face) => vec![closed_loop(vec![
+                face.first_corner.clone(),
+                face.second_corner.clone(),
+                face.third_corner.clone(),
+                face.fourth_corner.clone(),
+            ])],
+            EntityType::Solid(ref solid) => vec![quadrilateral(solid.corners_wcs())],
+            EntityType::Trace(ref trace) => vec![quadrilateral(trace.corners_wcs())],
+            EntityType::MLine(ref mline) if mline.vertices.len() >= 2 => {
+                let mut points = mline.vertices.clone();
+                if mline.get_is_closed() {
+                    points.push(points[0].clone());
+                }
+                vec![points]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+// private implementation
+
+/// Returns `(start, end)` with `0 <= start < 2π` and `start < end <= start + 2π`.  Non-finite
+/// angles give the whole turn.
+fn normalized_sweep(start: f64, end: f64) -> (f64, f64) {
+    if !start.is_finite() || !end.is_finite() {
+        return (0.0, 2.0 * PI);
+    }
+    let start = start.rem_euclid(2.0 * PI);
+    let mut end = end.rem_euclid(2.0 * PI);
+    if end <= start {
+        end += 2.0 * PI;
+    }
+    (start, end)
+}
+
+/// The world coordinate directions of the OCS X and Y axes, scaled to `radius`.
+fn ocs_axes(normal: &Vector, radius: f64) -> (Vector, Vector) {
+    let ocs = TransformationMatrix::from_ocs_normal(normal);
+    (
+        ocs.transform_vector(&Vector::x_axis()) * radius,
+        ocs.transform_vector(&Vector::y_axis()) * radius,
+    )
+}
+
+/// The number of equal segments needed to keep a sweep of a curve with the specified maximum
+/// radius of curvature within the tolerance.
+fn arc_segment_count(radius: f64, sweep: f64, tolerance: f64) -> usize {
+    let step = if tolerance < radius {
+        2.0 * (1.0 - tolerance / radius).acos()
+    } else {
+        PI
+    };
+    let count = (sweep.abs() / step).ceil();
+    if count.is_finite() {
+        (count as usize).clamp(1, MAX_ARC_SEGMENTS)
+    } else {
+        1
+    }
+}
+
+/// Samples `center + u * cos(t) + v * sin(t)` for `t` from `start` to `end`.  The axes need not
+/// be perpendicular.
+fn elliptical_arc(
+    center: &Point,
+    u: &Vector,
+    v: &Vector,
+    start: f64,
+    end: f64,
+    tolerance: f64,
+) -> Vec<Point> {
+    // with even parameter steps the chord error is at most that of a circle whose radius is the
+    // largest semi-axis, i.e., the largest singular value of [u v]
+    let uu = u.dot(u);
+    let vv = v.dot(v);
+    let uv = u.dot(v);
+    let radius = (0.5 * (uu + vv + ((uu - vv).powi(2) + 4.0 * uv * uv).sqrt())).sqrt();
+    let count = arc_segment_count(radius, end - start, tolerance);
+    let point_at = |t: f64| center + &(u * t.cos() + v * t.sin());
+    let mut points = (0..=count)
+        .map(|i| point_at(start + (end - start) * i as f64 / count as f64))
+        .collect::<Vec<_>>();
+    if (end - start - 2.0 * PI).abs() < 1.0e-12 {
+        points[count] = points[0].clone();
+    }
+    points
+}
+
+/// Samples a polyline of `(location, bulge)` vertices in the object coordinate system.
+fn bulged_polyline(
+    vertices: &[(Point, f64)],
+    is_closed: bool,
+    normal: &Vector,
+    tolerance: f64,
+) -> Vec<Point> {
+    let mut points = vec![];
+    if let Some((first, _)) = vertices.first() {
+        points.push(first.clone());
+    }
+    let segment_count = if is_closed && vertices.len() > 1 {
+        vertices.len()
+    } else {
+        vertices.len().saturating_sub(1)
+    };
+    for i in 0..segment_count {
+        let (start, bulge) = &vertices[i];
+        let (end, _) = &vertices[(i + 1) % vertices.len()];
+        if let Some((center, radius, start_angle, included_angle)) =
+            bulge_to_arc(start, end, *bulge)
+        {
+            let count = arc_segment_count(radius, included_angle, tolerance);
+            for j in 1..count {
+                let angle = start_angle + included_angle * j as f64 / count as f64;
+                points.push(Point::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                    start.z,
+                ));
+            }
+        }
+        points.push(end.clone());
+    }
+
+    let ocs = TransformationMatrix::from_ocs_normal(normal);
+    points.iter().map(|p| ocs.transform_point(p)).collect()
+}
+
+fn polyline_polylines(poly: &Polyline, tolerance: f64) -> Vec<Vec<Point>> {
+    if poly.get_is_polyface_mesh() {
+        let locations = poly
+            .vertices()
+            .filter(|v| v.get_is_3d_polygon_mesh())
+            .map(|v| v.location.clone())
+            .collect::<Vec<_>>();
+        return poly
+            .vertices()
+            .filter(|v| v.get_is_polyface_mesh_vertex() && !v.get_is_3d_polygon_mesh())
+            .map(|face| {
+                // indices are 1-based, negative for invisible edges, and 0 when unused
+                let points = [
+                    face.polyface_mesh_vertex_index1,
+                    face.polyface_mesh_vertex_index2,
+                    face.polyface_mesh_vertex_index3,
+                    face.polyface_mesh_vertex_index4,
+                ]
+                .iter()
+                .filter(|i| **i != 0)
+                .filter_map(|i| locations.get(i.unsigned_abs() as usize - 1).cloned())
+                .collect::<Vec<_>>();
+                closed_loop(points)
+            })
+            .filter(|points| points.len() >= 2)
+            .collect();
+    }
+
+    let vertices = poly
+        .vertices()
+        .filter(|v| !v.get_is_spline_frame_control_point())
+        .collect::<Vec<_>>();
+    if poly.get_is_3d_polygon_mesh() {
+        let m = poly.polygon_mesh_m_vertex_count.max(0) as usize;
+        let n = poly.polygon_mesh_n_vertex_count.max(0) as usize;
+        if m == 0 || n == 0 || vertices.len() < m * n {
+            return vec![];
+        }
+        let location = |row: usize, column: usize| vertices[row * n + column].location.clone();
+        let mut polylines = vec![];
+        for row in 0..m {
+            let mut points = (0..n)
+                .map(|column| location(row, column))
+                .collect::<Vec<_>>();
+            if poly.get_is_polygon_mesh_closed_in_n_direction() {
+                points.push(points[0].clone());
+            }
+            polylines.push(points);
+        }
+        for column in 0..n {
+            let mut points = (0..m).map(|row| location(row, column)).collect::<Vec<_>>();
+            if poly.get_is_closed() {
+                points.push(points[0].clone());
+            }
+            polylines.push(points);
+        }
+        polylines
+    } else if poly.get_is_3d_polyline() {
+        let mut points = vertices
+            .iter()
+            .map(|v| v.location.clone())
+            .collect::<Vec<_>>();
+        if poly.get_is_closed() && points.len() > 1 {
+            points.push(points[0].clone());
+        }
+        vec![points]
+    } else {
+        let vertices = vertices
+            .iter()
+            .map(|v| {
+                let p = Point::new(v.location.x, v.location.y, poly.location.z);
+                (p, v.bulge)
+            })
+            .collect::<Vec<_>>();
+        vec![bulged_polyline(
+            &vertices,
+            poly.get_is_closed(),
+            &poly.normal,
+            tolerance,
+        )]
+    }
+}
+
+/// Samples each knot span of the spline, halving it until the curve is within the tolerance of
+/// the chords.  Requires valid knots.
+fn spline_polyline(spline: &Spline, tolerance: f64) -> Vec<Point> {
+    // a few initial subdivisions keep symmetric spans from looking straight at their midpoint
+    let min_depth = if spline.degree_of_curve == 1 { 0 } else { 2 };
+    let (t0, t1) = spline.domain();
+    let mut points = vec![spline.point_at(t0)];
+    let mut knots = spline
+        .knot_values
+        .iter()
+        .cloned()
+        .filter(|k| *k > t0 && *k < t1)
+        .collect::<Vec<_>>();
+    knots.dedup();
+    knots.push(t1);
+    let mut start = t0;
+    for end in knots {
+        let end_point = spline.point_at(end);
+        let start_point = points[points.len() - 1].clone();
+        subdivide_spline(
+            spline,
+            (start, &start_point),
+            (end, &end_point),
+            tolerance,
+            min_depth,
+            0,
+            &mut points,
+        );
+        start = end;
+    }
+    points
+}
+
+fn subdivide_spline(
+    spline: &Spline,
+    start: (f64, &Point),
+    end: (f64, &Point),
+    tolerance: f64,
+    min_depth: usize,
+    depth: usize,
+    points: &mut Vec<Point>,
+) {
+    let t = (start.0 + end.0) * 0.5;
+    let mid = spline.point_at(t);
+    if depth < min_depth
+        || (depth < MAX_SPLINE_DEPTH && distance_to_segment(&mid, start.1, end.1) > tolerance)
+    {
+        subdivide_spline(
+            spline,
+            start,
+            (t, &mid),
+            tolerance,
+            min_depth,
+            depth + 1,
+            points,
+        );
+        subdivide_spline(
+            spline,
+            (t, &mid),
+            end,
+            tolerance,
+            min_depth,
+            depth + 1,
+            points,
+        );
+    } else {
+        points.push(end.1.clone());
+    }
+}
+
+fn distance_to_segment(p: &Point, start: &Point, end: &Point) -> f64 {
+    let segment = end - start;
+    let length_squared = segment.dot(&segment);
+    if length_squared == 0.0 {
+        return p.distance_to(start);
+    }
+    let t = ((p - start).dot(&segment) / length_squared).clamp(0.0, 1.0);
+    p.distance_to(&(start + &(segment * t)))
+}
+
+fn helix_polyline(helix: &Helix, tolerance: f64) -> Vec<Point> {
+    let axis = helix.axis_vector.normalize();
+    let offset = &helix.start_point - &helix.axis_base_point;
+    let radial = &offset - &(&axis * offset.dot(&axis));
+    let tangential = axis.cross(&radial);
+    let handedness = if helix.is_right_handed { 1.0 } else { -1.0 };
+    let sweep = 2.0 * PI * helix.number_of_turns.abs();
+    let count = arc_segment_count(radial.length(), sweep, tolerance);
+    (0..=count)
+        .map(|i| {
+            let angle = sweep * i as f64 / count as f64;
+            let rise = helix.turn_height * angle / (2.0 * PI);
+            let (sin, cos) = (handedness * angle).sin_cos();
+            &helix.start_point + &(&radial * (cos - 1.0) + &tangential * sin + &axis * rise)
+        })
+        .collect()
+}
+
+/// Closes the corners of a `Solid` or `Trace`, which are stored in zig-zag order.
+fn quadrilateral(corners: [Point; 4]) -> Vec<Point> {
+    let [first, second, third, fourth] = corners;
+    closed_loop(vec![first, second, fourth, third])
+}
+
+/// Removes a repeated last corner and appends the first point.
+fn closed_loop(mut points: Vec<Point>) -> Vec<Point> {
+    if points.len() > 3 && points[points.len() - 1] == points[points.len() - 2] {
+        points.pop();
+    }
+    if let Some(first) = points.first().cloned() {
+        points.push(first);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::entities::*;
+    use crate::enums::LeaderPathType;
+    use crate::{LwPolylineVertex, Point, Vector};
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            expected.is_close_to(actual, 1.0e-9),
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn single_polyline(specific: EntityType, tolerance: f64) -> Vec<Point> {
+        let mut polylines = Entity::new(specific).to_polylines(tolerance);
+        assert_eq!(1, polylines.len());
+        polylines.remove(0)
+    }
+
+    #[test]
+    fn circle_is_within_tolerance() {
+        let tolerance = 0.01;
+        let points = single_polyline(
+            EntityType::Circle(Circle::new(Point::new(1.0, 2.0, 0.0), 10.0)),
+            tolerance,
+        );
+        assert_eq!(points[0], points[points.len() - 1]);
+        for w in points.windows(2) {
+            let mid = w[0].lerp(&w[1], 0.5);
+            let sagitta = 10.0 - mid.distance_to(&Point::new(1.0, 2.0, 0.0));
+            assert!(sagitta <= tolerance + 1.0e-12);
+            assert!(sagitta > tolerance / 4.0);
+        }
+    }
+
+    #[test]
+    fn arc_end_points() {
+        let points = single_polyline(
+            EntityType::Arc(Arc::new(Point::origin(), 2.0, 90.0, 0.0)),
+            0.001,
+        );
+        assert_close(&Point::new(0.0, 2.0, 0.0), &points[0]);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+        // the arc runs counter-clockwise through the bottom
+        assert!(points.iter().any(|p| p.y < -1.9));
+    }
+
+    #[test]
+    fn arc_with_out_of_range_angles() {
+        // 450 to -360 degrees is the same arc as 90 to 0
+        let points = single_polyline(
+            EntityType::Arc(Arc::new(Point::origin(), 2.0, 450.0, -360.0)),
+            0.001,
+        );
+        assert_close(&Point::new(0.0, 2.0, 0.0), &points[0]);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+
+        // huge angles can't be stepped through a turn at a time
+        let points = single_polyline(
+            EntityType::Arc(Arc::new(Point::origin(), 2.0, 1.0e300, 0.0)),
+            0.001,
+        );
+        assert!(points.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+
+        // non-finite angles fall back to the whole circle
+        let points = single_polyline(
+            EntityType::Arc(Arc::new(Point::origin(), 2.0, f64::NAN, 90.0)),
+            0.001,
+        );
+        assert_close(&points[0], &points[points.len() - 1]);
+        assert!(points.iter().any(|p| p.y < -1.9));
+    }
+
+    #[test]
+    fn arc_respects_normal() {
+        let mut arc = Arc::new(Point::origin(), 1.0, 0.0, 90.0);
+        arc.normal = Vector::new(0.0, 0.0, -1.0);
+        let points = single_polyline(EntityType::Arc(arc), 0.001);
+        assert_close(&Point::new(-1.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(0.0, 1.0, 0.0), &points[points.len() - 1]);
+    }
+
+    #[test]
+    fn lw_polyline_with_bulge() {
+        let poly = LwPolyline {
+            vertices: vec![
+                LwPolylineVertex {
+                    x: 0.0,
+                    y: 0.0,
+                    bulge: 1.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 2.0,
+                    y: 0.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::LwPolyline(poly), 0.001);
+        assert_close(&Point::origin(), &points[0]);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+        // a bulge of 1 is a counter-clockwise half circle, which passes below the chord
+        for p in &points {
+            assert!((p.distance_to(&Point::new(1.0, 0.0, 0.0)) - 1.0).abs() < 1.0e-9);
+            assert!(p.y <= 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn rational_spline_is_exact_circle() {
+        let spline = Spline {
+            degree_of_curve: 2,
+            knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            control_points: vec![
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            weights: vec![1.0, 0.5f64.sqrt(), 1.0],
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::Spline(spline), 0.0001);
+        assert!(points.len() > 4);
+        assert_close(&Point::new(1.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(0.0, 1.0, 0.0), &points[points.len() - 1]);
+        for p in &points {
+            assert!((p.distance_to(&Point::origin()) - 1.0).abs() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn fit_point_spline_passes_through_fit_points() {
+        let fit_points = vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+            Point::new(4.0, 3.0, 1.0),
+        ];
+        let spline = Spline {
+            degree_of_curve: 3,
+            fit_points: fit_points.clone(),
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::Spline(spline), 0.0001);
+        assert_close(&fit_points[0], &points[0]);
+        assert_close(&fit_points[3], &points[points.len() - 1]);
+        for fit_point in &fit_points {
+            let closest = points
+                .iter()
+                .map(|p| p.distance_to(fit_point))
+                .fold(f64::MAX, f64::min);
+            assert!(closest < 0.01);
+        }
+    }
+
+    #[test]
+    fn helix_turns() {
+        let helix = Helix {
+            axis_base_point: Point::origin(),
+            start_point: Point::new(1.0, 0.0, 0.0),
+            axis_vector: Vector::z_axis(),
+            radius: 1.0,
+            number_of_turns: 2.0,
+            turn_height: 0.5,
+            is_right_handed: true,
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::Helix(helix), 0.001);
+        assert_close(&Point::new(1.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(1.0, 0.0, 1.0), &points[points.len() - 1]);
+        // a right-handed helix turns counter-clockwise about its axis
+        let quarter = points.iter().find(|p| (p.z - 0.125).abs() < 0.01).unwrap();
+        assert!(quarter.y > 0.9);
+    }
+
+    #[test]
+    fn spline_leader_is_smooth() {
+        let leader = Leader {
+            path_type: LeaderPathType::Spline,
+            vertices: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(2.0, 0.0, 0.0),
+            ],
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::Leader(leader), 0.001);
+        assert!(points.len() > 3);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[points.len() - 1]);
+    }
+
+    #[test]
+    fn text_has_no_polylines() {
+        let text = Entity::new(EntityType::Text(Text::default()));
+        assert!(text.to_polylines(0.01).is_empty());
+    }
+
+    #[test]
+    fn full_ellipse_is_closed() {
+        let ellipse = Ellipse {
+            major_axis: Vector::new(2.0, 0.0, 0.0),
+            minor_axis_ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: 2.0 * PI,
+            ..Default::default()
+        };
+        let points = single_polyline(EntityType::Ellipse(ellipse), 0.001);
+        assert_eq!(points[0], points[points.len() - 1]);
+        assert!(points.iter().any(|p| (p.y - 1.0).abs() < 1.0e-3));
+    }
+}
@@ -1,4 +1,12 @@
+use std::ops::Mul;
+
+use crate::entities::Insert;
+use crate::{Point, Vector};
+
 /// Applies a transformation to a point.
+///
+/// Points are treated as column vectors, so `m14`, `m24`, and `m34` hold the translation and
+/// `a * b` applies `b` first, then `a`.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct TransformationMatrix {
@@ -31,6 +39,208 @@ impl TransformationMatrix {
             ..Default::default()
         }
     }
+    /// Creates a matrix that moves points by the specified offset.
+    pub fn translation(offset: &Vector) -> Self {
+        TransformationMatrix {
+            m14: offset.x,
+            m24: offset.y,
+            m34: offset.z,
+            ..TransformationMatrix::identity()
+        }
+    }
+    /// Creates a matrix that scales about the origin by the specified factors.
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        TransformationMatrix {
+            m11: x,
+            m22: y,
+            m33: z,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Creates a matrix that rotates counter-clockwise about the specified axis through the origin
+    /// by `angle` radians.
+    pub fn rotation(axis: &Vector, angle: f64) -> Self {
+        let a = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+        TransformationMatrix {
+            m11: t * a.x * a.x + cos,
+            m12: t * a.x * a.y - sin * a.z,
+            m13: t * a.x * a.z + sin * a.y,
+            m21: t * a.x * a.y + sin * a.z,
+            m22: t * a.y * a.y + cos,
+            m23: t * a.y * a.z - sin * a.x,
+            m31: t * a.x * a.z - sin * a.y,
+            m32: t * a.y * a.z + sin * a.x,
+            m33: t * a.z * a.z + cos,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Creates a matrix that mirrors across the plane through `point` with the specified normal.
+    pub fn mirror(point: &Point, normal: &Vector) -> Self {
+        let n = normal.normalize();
+        let d = n.dot(&point.to_vector());
+        TransformationMatrix {
+            m11: 1.0 - 2.0 * n.x * n.x,
+            m12: -2.0 * n.x * n.y,
+            m13: -2.0 * n.x * n.z,
+            m14: 2.0 * d * n.x,
+            m21: -2.0 * n.x * n.y,
+            m22: 1.0 - 2.0 * n.y * n.y,
+            m23: -2.0 * n.y * n.z,
+            m24: 2.0 * d * n.y,
+            m31: -2.0 * n.x * n.z,
+            m32: -2.0 * n.y * n.z,
+            m33: 1.0 - 2.0 * n.z * n.z,
+            m34: 2.0 * d * n.z,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Creates a matrix that converts from the object coordinate system (OCS) defined by the
+    /// specified extrusion direction to the world coordinate system (WCS), using AutoCAD's
+    /// arbitrary axis algorithm.  The inverse converts from WCS to OCS.
+    pub fn from_ocs_normal(normal: &Vector) -> Self {
+        // the arbitrary axis algorithm picks the world Y axis when the normal is close to Z
+        const LIMIT: f64 = 1.0 / 64.0;
+        let z = normal.normalize();
+        let x = if z.x.abs() < LIMIT && z.y.abs() < LIMIT {
+            Vector::y_axis().cross(&z)
+        } else {
+            Vector::z_axis().cross(&z)
+        }
+        .normalize();
+        let y = z.cross(&x).normalize();
+        TransformationMatrix {
+            m11: x.x,
+            m12: y.x,
+            m13: z.x,
+            m21: x.y,
+            m22: y.y,
+            m23: z.y,
+            m31: x.z,
+            m32: y.z,
+            m33: z.z,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Creates a matrix that converts a point of the inserted block, relative to the block's base
+    /// point, to world coordinates.  The insert's row and column offsets aren't included.
+    pub fn from_insert(insert: &Insert) -> Self {
+        TransformationMatrix::from_ocs_normal(&insert.extrusion_direction)
+            * TransformationMatrix::translation(&insert.location.to_vector())
+            * TransformationMatrix::rotation(&Vector::z_axis(), insert.rotation.to_radians())
+            * TransformationMatrix::scale(
+                insert.x_scale_factor,
+                insert.y_scale_factor,
+                insert.z_scale_factor,
+            )
+    }
+    /// Returns the determinant of the matrix.
+    pub fn determinant(&self) -> f64 {
+        let mut rows = self.to_rows();
+        let mut determinant = 1.0;
+        for column in 0..4 {
+            let pivot = match TransformationMatrix::find_pivot(&rows, column) {
+                Some(pivot) => pivot,
+                None => return 0.0,
+            };
+            if pivot != column {
+                rows.swap(pivot, column);
+                determinant = -determinant;
+            }
+
+            let pivot_row = rows[column];
+            determinant *= pivot_row[column];
+            for row in rows.iter_mut().skip(column + 1) {
+                let factor = row[column] / pivot_row[column];
+                for (value, pivot_value) in row.iter_mut().zip(pivot_row.iter()).skip(column) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+
+        determinant
+    }
+    /// Returns the inverse of the matrix, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut rows = self.to_rows();
+        let mut inverse = TransformationMatrix::identity().to_rows();
+        for column in 0..4 {
+            let pivot = TransformationMatrix::find_pivot(&rows, column)?;
+            rows.swap(pivot, column);
+            inverse.swap(pivot, column);
+
+            let divisor = rows[column][column];
+            for c in 0..4 {
+                rows[column][c] /= divisor;
+                inverse[column][c] /= divisor;
+            }
+
+            for row in 0..4 {
+                if row != column {
+                    let factor = rows[row][column];
+                    for c in 0..4 {
+                        rows[row][c] -= factor * rows[column][c];
+                        inverse[row][c] -= factor * inverse[column][c];
+                    }
+                }
+            }
+        }
+
+        Some(TransformationMatrix::from_rows(&inverse))
+    }
+    /// Returns the transposed matrix.
+    pub fn transpose(&self) -> Self {
+        let rows = self.to_rows();
+        let mut transposed = [[0.0; 4]; 4];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                transposed[c][r] = *value;
+            }
+        }
+
+        TransformationMatrix::from_rows(&transposed)
+    }
+    /// Applies the transformation to a point.
+    pub fn transform_point(&self, point: &Point) -> Point {
+        let x = self.m11 * point.x + self.m12 * point.y + self.m13 * point.z + self.m14;
+        let y = self.m21 * point.x + self.m22 * point.y + self.m23 * point.z + self.m24;
+        let z = self.m31 * point.x + self.m32 * point.y + self.m33 * point.z + self.m34;
+        let w = self.m41 * point.x + self.m42 * point.y + self.m43 * point.z + self.m44;
+        if w == 1.0 || w == 0.0 {
+            Point::new(x, y, z)
+        } else {
+            Point::new(x / w, y / w, z / w)
+        }
+    }
+    /// Applies the transformation to a vector.  Translation doesn't affect vectors.
+    pub fn transform_vector(&self, vector: &Vector) -> Vector {
+        Vector::new(
+            self.m11 * vector.x + self.m12 * vector.y + self.m13 * vector.z,
+            self.m21 * vector.x + self.m22 * vector.y + self.m23 * vector.z,
+            self.m31 * vector.x + self.m32 * vector.y + self.m33 * vector.z,
+        )
+    }
+}
+
+impl Mul for TransformationMatrix {
+    type Output = TransformationMatrix;
+    fn mul(self, other: TransformationMatrix) -> TransformationMatrix {
+        let a = self.to_rows();
+        let b = other.to_rows();
+        let mut result = [[0.0; 4]; 4];
+        for (r, row) in result.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|i| a[r][i] * b[i][c]).sum();
+            }
+        }
+
+        TransformationMatrix::from_rows(&result)
+    }
 }
 
 // internal visibility only
@@ -61,6 +271,26 @@ impl TransformationMatrix {
             self.m31, self.m32, self.m33, self.m34, self.m41, self.m42, self.m43, self.m44,
         ]
     }
+    /// The inverse of `get_4x3_values_row_major()`.
+    pub(crate) fn from_4x3_values_row_major(values: &[f64]) -> Self {
+        let value = |index| TransformationMatrix::get_value_or_default(values, index);
+        TransformationMatrix {
+            m11: value(0),
+            m21: value(1),
+            m31: value(2),
+            m12: value(3),
+            m22: value(4),
+            m32: value(5),
+            m13: value(6),
+            m23: value(7),
+            m33: value(8),
+            m14: value(9),
+            m24: value(10),
+            m34: value(11),
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
     pub(crate) fn get_4x3_values_row_major(&self) -> Vec<f64> {
         vec![
             self.m11, self.m21, self.m31, self.m12, self.m22, self.m32, self.m13, self.m23,
@@ -71,6 +301,34 @@ impl TransformationMatrix {
 
 // private implementation
 impl TransformationMatrix {
+    fn to_rows(self) -> [[f64; 4]; 4] {
+        [
+            [self.m11, self.m12, self.m13, self.m14],
+            [self.m21, self.m22, self.m23, self.m24],
+            [self.m31, self.m32, self.m33, self.m34],
+            [self.m41, self.m42, self.m43, self.m44],
+        ]
+    }
+    fn from_rows(rows: &[[f64; 4]; 4]) -> Self {
+        TransformationMatrix::from_vec(&rows.concat())
+    }
+    /// Returns the row at or below `column` with the largest value in that column, or `None` if
+    /// they're all zero.
+    fn find_pivot(rows: &[[f64; 4]; 4], column: usize) -> Option<usize> {
+        let pivot = (column..4)
+            .max_by(|&a, &b| {
+                rows[a][column]
+                    .abs()
+                    .partial_cmp(&rows[b][column].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(column);
+        if rows[pivot][column].abs() < 1e-12 {
+            None
+        } else {
+            Some(pivot)
+        }
+    }
     fn get_value_or_default(values: &[f64], index: usize) -> f64 {
         if values.len() > index {
             values[index]
@@ -79,3 +337,114 @@ impl TransformationMatrix {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+    use std::f64::consts::PI;
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            expected.is_close_to(actual, 1e-9),
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn translate_and_scale() {
+        let m = TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0))
+            * TransformationMatrix::scale(2.0, 3.0, 4.0);
+        assert_close(
+            &Point::new(3.0, 5.0, 7.0),
+            &m.transform_point(&Point::new(1.0, 1.0, 1.0)),
+        );
+        assert_eq!(
+            Vector::new(2.0, 3.0, 4.0),
+            m.transform_vector(&Vector::new(1.0, 1.0, 1.0))
+        );
+        assert_eq!(24.0, m.determinant());
+    }
+
+    #[test]
+    fn rotate_about_axis() {
+        let m = TransformationMatrix::rotation(&Vector::z_axis(), PI / 2.0);
+        assert_close(
+            &Point::new(0.0, 1.0, 0.0),
+            &m.transform_point(&Point::new(1.0, 0.0, 0.0)),
+        );
+        let m = TransformationMatrix::rotation(&Vector::new(1.0, 1.0, 1.0), 2.0 * PI / 3.0);
+        assert_close(
+            &Point::new(0.0, 1.0, 0.0),
+            &m.transform_point(&Point::new(1.0, 0.0, 0.0)),
+        );
+    }
+
+    #[test]
+    fn mirror_across_plane() {
+        let m = TransformationMatrix::mirror(&Point::new(1.0, 0.0, 0.0), &Vector::x_axis());
+        assert_close(
+            &Point::new(-1.0, 2.0, 0.0),
+            &m.transform_point(&Point::new(3.0, 2.0, 0.0)),
+        );
+        assert!((m.determinant() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn inverse() {
+        let m = TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0))
+            * TransformationMatrix::rotation(&Vector::new(0.0, 1.0, 1.0), 0.3)
+            * TransformationMatrix::scale(2.0, 2.0, 2.0);
+        let p = Point::new(4.0, 5.0, 6.0);
+        let inverse = m.inverse().unwrap();
+        assert_close(&p, &inverse.transform_point(&m.transform_point(&p)));
+        assert!(TransformationMatrix::scale(1.0, 0.0, 1.0)
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn ocs_to_wcs() {
+        // the OCS of the Z axis is the WCS
+        assert_eq!(
+            TransformationMatrix::identity(),
+            TransformationMatrix::from_ocs_normal(&Vector::z_axis())
+        );
+
+        // arbitrary axis algorithm for a normal along X
+        let m = TransformationMatrix::from_ocs_normal(&Vector::x_axis());
+        assert_close(
+            &Point::new(0.0, 1.0, 0.0),
+            &m.transform_point(&Point::new(1.0, 0.0, 0.0)),
+        );
+        assert_close(
+            &Point::new(0.0, 0.0, 1.0),
+            &m.transform_point(&Point::new(0.0, 1.0, 0.0)),
+        );
+    }
+
+    #[test]
+    fn insert_transform() {
+        let insert = Insert {
+            location: Point::new(10.0, 0.0, 0.0),
+            x_scale_factor: 2.0,
+            rotation: 90.0,
+            ..Default::default()
+        };
+        let m = TransformationMatrix::from_insert(&insert);
+        assert_close(
+            &Point::new(10.0, 2.0, 0.0),
+            &m.transform_point(&Point::new(1.0, 0.0, 0.0)),
+        );
+    }
+
+    #[test]
+    fn round_trip_4x3_values() {
+        let m = TransformationMatrix::translation(&Vector::new(1.0, 2.0, 3.0))
+            * TransformationMatrix::rotation(&Vector::x_axis(), 0.5);
+        let values = m.get_4x3_values_row_major();
+        assert_eq!(m, TransformationMatrix::from_4x3_values_row_major(&values));
+    }
+}
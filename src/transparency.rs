@@ -0,0 +1,64 @@
+/// Represents the transparency of an entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Transparency {
+    raw_value: i32,
+}
+
+impl Transparency {
+    /// Creates a `Transparency` from its raw value, as stored with code 440.
+    pub fn from_raw_value(v: i32) -> Transparency {
+        Transparency { raw_value: v }
+    }
+    /// Gets the raw value of the `Transparency`, as stored with code 440.
+    pub fn get_raw_value(&self) -> i32 {
+        self.raw_value
+    }
+    /// Creates a new `Transparency` that defaults back to the item's layer's transparency.
+    pub fn by_layer() -> Transparency {
+        Transparency::from_raw_value(0)
+    }
+    /// Creates a new `Transparency` that defaults back to the containing block's transparency.
+    pub fn by_block() -> Transparency {
+        Transparency::from_raw_value(0x0100_0000)
+    }
+    /// Creates a new `Transparency` from the specified opacity, where 0 is fully transparent and
+    /// 255 is opaque.
+    pub fn from_alpha(alpha: u8) -> Transparency {
+        Transparency::from_raw_value(0x0200_0000 | i32::from(alpha))
+    }
+    /// Returns `true` if the `Transparency` is BYLAYER.
+    pub fn is_by_layer(&self) -> bool {
+        self.raw_value == 0
+    }
+    /// Returns `true` if the `Transparency` is BYBLOCK.
+    pub fn is_by_block(&self) -> bool {
+        self.raw_value == 0x0100_0000
+    }
+    /// Gets the opacity, where 0 is fully transparent and 255 is opaque, or `None` if the
+    /// `Transparency` is BYLAYER or BYBLOCK.
+    pub fn alpha(&self) -> Option<u8> {
+        if self.raw_value & 0x0200_0000 != 0 {
+            Some(self.raw_value as u8)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Transparency;
+
+    #[test]
+    fn transparency_values() {
+        assert!(Transparency::default().is_by_layer());
+        assert!(Transparency::by_block().is_by_block());
+        assert_eq!(None, Transparency::by_block().alpha());
+
+        let transparency = Transparency::from_alpha(0x7F);
+        assert_eq!(0x0200_007F, transparency.get_raw_value());
+        assert_eq!(Some(0x7F), transparency.alpha());
+        assert!(!transparency.is_by_layer() && !transparency.is_by_block());
+    }
+}
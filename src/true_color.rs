@@ -0,0 +1,64 @@
+use crate::Color;
+
+/// Represents a 24-bit RGB color, e.g., an entity's `color_24_bit`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TrueColor {
+    raw_value: i32,
+}
+
+impl TrueColor {
+    /// Creates a `TrueColor` from its raw value, `0x00RRGGBB`.
+    pub fn from_raw_value(v: i32) -> TrueColor {
+        TrueColor {
+            raw_value: v & 0x00FF_FFFF,
+        }
+    }
+    /// Gets the raw value of the `TrueColor`, `0x00RRGGBB`.
+    pub fn get_raw_value(&self) -> i32 {
+        self.raw_value
+    }
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> TrueColor {
+        TrueColor::from_raw_value(i32::from(r) << 16 | i32::from(g) << 8 | i32::from(b))
+    }
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        (self.r(), self.g(), self.b())
+    }
+    pub fn r(&self) -> u8 {
+        (self.raw_value >> 16) as u8
+    }
+    pub fn g(&self) -> u8 {
+        (self.raw_value >> 8) as u8
+    }
+    pub fn b(&self) -> u8 {
+        self.raw_value as u8
+    }
+    /// Gets the closest index color.
+    pub fn to_index_color(&self) -> Color {
+        Color::from_rgb(self.r(), self.g(), self.b())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, TrueColor};
+
+    #[test]
+    fn true_color_components() {
+        let color = TrueColor::from_rgb(0x12, 0x34, 0x56);
+        assert_eq!(0x0012_3456, color.get_raw_value());
+        assert_eq!((0x12, 0x34, 0x56), color.to_rgb());
+        assert_eq!(color, TrueColor::from_raw_value(0x0012_3456));
+
+        // AutoCAD sometimes stores a color method in the upper byte
+        assert_eq!(color, TrueColor::from_raw_value(0xC212_3456_u32 as i32));
+    }
+
+    #[test]
+    fn true_color_to_index_color() {
+        assert_eq!(
+            Color::from_index(3),
+            TrueColor::from_rgb(0, 250, 0).to_index_color()
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{CodePair, DxfError, DxfResult};
+use std::ops::Neg;
+
+use crate::{CodePair, DxfError, DxfResult, Point};
 
 /// Represents a simple vector in Cartesian space.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -33,6 +35,57 @@ impl Vector {
     pub fn z_axis() -> Vector {
         Vector::new(0.0, 0.0, 1.0)
     }
+    /// Returns the dot product of this vector and another.
+    pub fn dot(&self, other: &Vector) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    /// Returns the cross product of this vector and another.
+    pub fn cross(&self, other: &Vector) -> Vector {
+        Vector::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    /// Returns a vector with the same direction and a length of 1, or the zero vector if this
+    /// vector has no length.
+    pub fn normalize(&self) -> Vector {
+        let length = self.length();
+        if length == 0.0 {
+            Vector::zero()
+        } else {
+            self / length
+        }
+    }
+    /// Returns the vector at parameter `t` between this vector and another, where `0.0` is this
+    /// vector and `1.0` is the other.
+    pub fn lerp(&self, other: &Vector, t: f64) -> Vector {
+        self + (other - self) * t
+    }
+    /// Returns the angle between this vector and another in radians, in the range `[0, π]`.
+    pub fn angle_between(&self, other: &Vector) -> f64 {
+        // `atan2` stays accurate for nearly parallel vectors where `acos` of the dot product doesn't
+        self.cross(other).length().atan2(self.dot(other))
+    }
+    /// Returns `true` if every component is within `tolerance` of the other vector's.
+    pub fn is_close_to(&self, other: &Vector, tolerance: f64) -> bool {
+        (self.x - other.x).abs() <= tolerance
+            && (self.y - other.y).abs() <= tolerance
+            && (self.z - other.z).abs() <= tolerance
+    }
+    /// Returns `true` if the vector's length is within `tolerance` of zero.
+    pub fn is_zero(&self, tolerance: f64) -> bool {
+        self.length() <= tolerance
+    }
+    /// Returns `true` if this vector points in the same or opposite direction as another,
+    /// within `tolerance`.  Zero vectors are considered parallel to everything.
+    pub fn is_parallel_to(&self, other: &Vector, tolerance: f64) -> bool {
+        self.normalize().cross(&other.normalize()).length() <= tolerance
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.assert_f64()?,
@@ -49,3 +102,104 @@ impl Vector {
         Ok(())
     }
 }
+
+impl_binary_op!(Add, add, Vector, Vector, Vector, |a, b| Vector::new(
+    a.x + b.x,
+    a.y + b.y,
+    a.z + b.z
+));
+impl_binary_op!(Sub, sub, Vector, Vector, Vector, |a, b| Vector::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+impl_binary_op!(Mul, mul, Vector, f64, Vector, |v, s| Vector::new(
+    v.x * s,
+    v.y * s,
+    v.z * s
+));
+impl_binary_op!(Mul, mul, f64, Vector, Vector, |s, v| Vector::new(
+    s * v.x,
+    s * v.y,
+    s * v.z
+));
+impl_binary_op!(Div, div, Vector, f64, Vector, |v, s| Vector::new(
+    v.x / s,
+    v.y / s,
+    v.z / s
+));
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Neg for &Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl From<Point> for Vector {
+    fn from(p: Point) -> Vector {
+        Vector::new(p.x, p.y, p.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn vector_arithmetic() {
+        let a = Vector::new(1.0, 2.0, 3.0);
+        let b = Vector::new(3.0, 2.0, 1.0);
+        assert_eq!(Vector::new(4.0, 4.0, 4.0), &a + &b);
+        assert_eq!(Vector::new(-2.0, 0.0, 2.0), &a - &b);
+        assert_eq!(Vector::new(2.0, 4.0, 6.0), &a * 2.0);
+        assert_eq!(Vector::new(2.0, 4.0, 6.0), 2.0 * &a);
+        assert_eq!(Vector::new(0.5, 1.0, 1.5), &a / 2.0);
+        assert_eq!(Vector::new(-1.0, -2.0, -3.0), -a);
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        assert_eq!(0.0, Vector::x_axis().dot(&Vector::y_axis()));
+        assert_eq!(
+            10.0,
+            Vector::new(1.0, 2.0, 3.0).dot(&Vector::new(3.0, 2.0, 1.0))
+        );
+        assert_eq!(Vector::z_axis(), Vector::x_axis().cross(&Vector::y_axis()));
+        assert_eq!(-Vector::z_axis(), Vector::y_axis().cross(&Vector::x_axis()));
+    }
+
+    #[test]
+    fn length_and_normalize() {
+        let v = Vector::new(3.0, 4.0, 0.0);
+        assert_eq!(5.0, v.length());
+        assert_eq!(Vector::new(0.6, 0.8, 0.0), v.normalize());
+        assert_eq!(Vector::zero(), Vector::zero().normalize());
+    }
+
+    #[test]
+    fn angle_between() {
+        let angle = Vector::x_axis().angle_between(&Vector::new(1.0, 1.0, 0.0));
+        assert!((angle - PI / 4.0).abs() < 1e-12);
+        let angle = Vector::x_axis().angle_between(&-Vector::x_axis());
+        assert!((angle - PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn lerp_and_tolerance() {
+        let v = Vector::zero().lerp(&Vector::new(2.0, 4.0, 6.0), 0.25);
+        assert_eq!(Vector::new(0.5, 1.0, 1.5), v);
+        assert!(v.is_close_to(&Vector::new(0.5001, 1.0, 1.5), 1e-3));
+        assert!(Vector::new(1e-9, 0.0, 0.0).is_zero(1e-6));
+        assert!(Vector::x_axis().is_parallel_to(&Vector::new(-2.0, 0.0, 0.0), 1e-12));
+        assert!(!Vector::x_axis().is_parallel_to(&Vector::y_axis(), 1e-12));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::drawing::AUTO_REPLACE_HANDLE;
+use crate::entities::*;
+use crate::enums::AcadVersion;
+use crate::{Drawing, Handle, Point};
+
+/// The chord tolerance used when curves are converted to polylines, as a fraction of the size of
+/// the curve.
+const RELATIVE_CHORD_TOLERANCE: f64 = 1.0e-3;
+
+// internal visibility only
+impl Entity {
+    /// Returns the entities that represent this one in a drawing of the specified version.  True
+    /// colors become the closest index color before R2004.  Entities the version doesn't support
+    /// are converted: an `LwPolyline` to a `Polyline`, an `MText` to one `Text` per line, and
+    /// curves such as an `Ellipse` or `Spline` to tessellated polylines.  Anything else the version
+    /// doesn't support, including unknown entities before R13, is dropped.  The first entity keeps
+    /// the handle; the others have none.
+    pub(crate) fn converted_to(&self, version: AcadVersion, drawing: &Drawing) -> Vec<Entity> {
+        let mut common = self.common.clone();
+        if version < AcadVersion::R2004 {
+            if let Some(true_color) = common.get_true_color() {
+                common.color = true_color.to_index_color();
+                common.set_true_color(None);
+            }
+        }
+
+        // unknown entities come from R13 or later and can't be converted
+        if version < AcadVersion::R13 && matches!(self.specific, EntityType::Unknown { .. }) {
+            return vec![];
+        }
+        if self.specific.is_supported_on_version(version) {
+            return vec![Entity {
+                common,
+                specific: self.specific.clone(),
+            }];
+        }
+
+        let specifics = match self.specific {
+            EntityType::LwPolyline(ref poly) => {
+                vec![EntityType::Polyline(poly.to_polyline(common.elevation))]
+            }
+            EntityType::MText(ref m_text) => m_text
+                .to_text_lines()
+                .into_iter()
+                .filter(|t| !t.value.is_empty())
+                .map(EntityType::Text)
+                .collect(),
+            _ => {
+                let size = match self.bounding_box(drawing) {
+                    Some(bounding_box) => bounding_box.min.distance_to(&bounding_box.max),
+                    None => return vec![],
+                };
+                self.to_polylines(size * RELATIVE_CHORD_TOLERANCE)
+                    .into_iter()
+                    .filter(|points| points.len() > 1)
+                    .map(|points| EntityType::Polyline(polyline_from_points(points)))
+                    .collect()
+            }
+        };
+        specifics
+            .into_iter()
+            .enumerate()
+            .map(|(i, specific)| {
+                let mut common = common.clone();
+                if i > 0 {
+                    common.handle = Handle::empty();
+                }
+                Entity { common, specific }
+            })
+            .collect()
+    }
+}
+
+/// Creates a `Polyline` through the world points, which is 2D if they're all at the same
+/// elevation and 3D otherwise.  It's closed if the last point repeats the first.
+fn polyline_from_points(mut points: Vec<Point>) -> Polyline {
+    let is_closed = points.len() > 2 && points[0] == points[points.len() - 1];
+    if is_closed {
+        points.pop();
+    }
+
+    let elevation = points[0].z;
+    let is_planar = points.iter().all(|p| p.z == elevation);
+    let mut poly = Polyline::default();
+    if is_planar {
+        poly.location = Point::new(0.0, 0.0, elevation);
+    } else {
+        poly.set_is_3d_polyline(true);
+    }
+    poly.set_is_closed(is_closed);
+    poly.__vertices_and_handles = points
+        .into_iter()
+        .map(|p| {
+            let location = if is_planar {
+                Point::new(p.x, p.y, 0.0)
+            } else {
+                p
+            };
+            let mut vertex = Vertex::new(location);
+            vertex.set_is_3d_polyline_vertex(!is_planar);
+            (vertex, AUTO_REPLACE_HANDLE)
+        })
+        .collect();
+    poly
+}